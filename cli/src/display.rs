@@ -0,0 +1,416 @@
+// Human-readable output for decoded accounts and instructions, plus the
+// decimal <-> base-unit conversion for amounts typed on the command line.
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+use solana_transaction::{uses_durable_nonce, Transaction as SolanaTransaction};
+use squad_mint_client::{
+    decode_handle, fees, instructions, pda, rosca, vote, Config, DuesSchedule,
+    JoinRequestCustodialWallet, ProposalKind, Rosca, SquadMintFund, Stream, Transaction,
+};
+
+use crate::{offline, rpc::Simulation};
+
+// "1.5" -> 1_500_000 for a 6-decimal mint. Rejects more fractional digits than
+// the mint has rather than silently rounding someone's money.
+pub fn parse_amount(text: &str, decimals: u8) -> Result<u64, String> {
+    let (whole, fraction) = match text.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (text, None),
+    };
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if !digits(whole) || !fraction.is_none_or(digits) {
+        return Err(format!("'{text}' is not a decimal amount"));
+    }
+    let fraction = fraction.unwrap_or("");
+    if fraction.len() > decimals as usize {
+        return Err(format!("'{text}' has more than {decimals} decimal places"));
+    }
+    let scale = 10u64.pow(decimals as u32);
+    let padded = format!("{fraction:0<width$}", width = decimals as usize);
+    whole
+        .parse::<u64>()
+        .ok()
+        .and_then(|w| w.checked_mul(scale))
+        .and_then(|w| w.checked_add(padded.parse().unwrap_or(0)))
+        .ok_or_else(|| format!("'{text}' is too large"))
+}
+
+pub fn format_amount(amount: u64, decimals: u8) -> String {
+    let scale = 10u64.pow(decimals as u32);
+    if decimals == 0 {
+        return amount.to_string();
+    }
+    format!(
+        "{}.{:0width$}",
+        amount / scale,
+        amount % scale,
+        width = decimals as usize
+    )
+}
+
+fn handle(fund: &SquadMintFund) -> String {
+    decode_handle(fund.account_handle())
+        .map(str::to_string)
+        .unwrap_or_else(|_| format!("{:?}", fund.account_handle()))
+}
+
+fn yes_no(flag: bool) -> &'static str {
+    if flag {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+pub fn print_fund(address: &Pubkey, fund: &SquadMintFund, decimals: u8) {
+    println!("fund             {address}");
+    println!("handle           {}", handle(fund));
+    println!("layout version   {}", fund.version().get());
+    println!("owner            {}", fund.owner());
+    if fund.effective_owner() != fund.owner() {
+        println!("recovered owner  {}", fund.effective_owner());
+    }
+    println!("vault            {}", pda::vault(address));
+    println!(
+        "join amount      {}",
+        format_amount(fund.join_amount(), decimals)
+    );
+    if fund.max_join_amount() > fund.join_amount() {
+        println!(
+            "  up to          {}",
+            format_amount(fund.max_join_amount(), decimals)
+        );
+    }
+    println!(
+        "admission        {}",
+        match (fund.requires_invite(), fund.invite_root()) {
+            (false, _) => "anyone may request".to_string(),
+            (true, None) => "signed invite".to_string(),
+            (true, Some(root)) => format!(
+                "signed invite or allowlist {}",
+                root.iter().map(|b| format!("{b:02x}")).collect::<String>()
+            ),
+        }
+    );
+    println!("open admission   {}", yes_no(fund.open_admission()));
+    println!(
+        "members          {}/{}",
+        fund.members().len(),
+        SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE
+    );
+    for (i, member) in fund.members().iter().enumerate() {
+        println!(
+            "  [{i}] {member}  contributed {}",
+            format_amount(fund.contribution_of(i), decimals)
+        );
+    }
+    println!("master nonce     {}", fund.master_nonce());
+    println!("active vote      {}", yes_no(fund.has_active_vote()));
+    if fund.has_active_vote() {
+        println!(
+            "  proposal       {}",
+            pda::proposal(address, fund.master_nonce())
+        );
+    }
+    println!(
+        "guardians        {} (threshold {})",
+        fund.guardians().len(),
+        fund.guardian_threshold()
+    );
+    for guardian in fund.guardians() {
+        println!("  {guardian}");
+    }
+    println!("pending recovery {}", yes_no(fund.has_pending_recovery()));
+    if let Some(terms) = fund.dissolution() {
+        println!(
+            "dissolving       {:?}, rent to {}",
+            terms.distribution, terms.rent_recipient
+        );
+    }
+}
+
+pub fn print_config(address: &Pubkey, config: &Config) {
+    println!("config           {address}");
+    println!("admin            {}", config.admin());
+    println!(
+        "allowed mints    {}/{}",
+        config.allowed_mints().len(),
+        Config::MAX_ALLOWED_MINTS
+    );
+    for mint in config.allowed_mints() {
+        println!("  {mint}");
+    }
+    println!("treasury         {}", config.treasury());
+    println!("join fee         {} bps", config.join_fee_bps());
+    println!("payout fee       {} bps", config.payout_fee_bps());
+    println!(
+        "reject fee       {} tokens",
+        format_amount(config.reject_fee(), fees::REFERENCE_DECIMALS)
+    );
+}
+
+// One line per fund, for `fund list`.
+pub fn print_fund_row(address: &Pubkey, fund: &SquadMintFund) {
+    println!(
+        "{address}  {:<15}  members {}  nonce {}{}",
+        handle(fund),
+        fund.members().len(),
+        fund.master_nonce(),
+        if fund.has_active_vote() {
+            "  (vote open)"
+        } else {
+            ""
+        }
+    );
+}
+
+pub fn print_proposal(
+    address: &Pubkey,
+    proposal: &Transaction,
+    fund: &SquadMintFund,
+    decimals: u8,
+) {
+    let message = &proposal.message_data;
+    println!("proposal         {address}");
+    println!("fund             {}", proposal.belongs_to_squad_mint_fund);
+    println!("nonce            {}", message.nonce);
+    match proposal.kind {
+        ProposalKind::Payout => {
+            println!("kind             payout");
+            println!(
+                "amount           {}",
+                format_amount(message.amount, decimals)
+            );
+            println!("to               {}", message.proposed_to_account);
+        }
+        ProposalKind::Dissolve { distribution } => {
+            println!("kind             dissolve ({distribution:?})");
+            println!("rent to          {}", message.proposed_to_account);
+        }
+        ProposalKind::Stream => {
+            println!("kind             stream");
+            println!(
+                "amount           {}",
+                format_amount(message.amount, decimals)
+            );
+            println!("stream           {}", message.proposed_to_account);
+        }
+        ProposalKind::CancelStream => {
+            println!("kind             cancel stream");
+            println!("stream           {}", message.proposed_to_account);
+        }
+    }
+    println!("proposer         {}", message.proposer_account);
+    println!("votes");
+    for (i, member) in fund.members().iter().enumerate() {
+        let bit = vote::member_bit(i);
+        let vote = match (proposal.voted_mask & bit != 0, proposal.votes & bit != 0) {
+            (false, _) => "-",
+            (true, true) => "yes",
+            (true, false) => "no",
+        };
+        println!("  [{i}] {member}  {vote}");
+    }
+}
+
+pub fn print_dues(schedule: &DuesSchedule, fund: &SquadMintFund, decimals: u8) {
+    println!("dues             {}", pda::dues(&schedule.fund));
+    println!("fund             {}", schedule.fund);
+    println!(
+        "amount           {} every {} days",
+        format_amount(schedule.amount, decimals),
+        schedule.period_seconds / (24 * 60 * 60)
+    );
+    println!("started          {}", schedule.start);
+    println!("members (periods paid, as of the last collection)");
+    for (i, member) in fund.members().iter().enumerate() {
+        let paid = match schedule.periods_paid.get(i) {
+            Some(periods) => periods.to_string(),
+            None => "-".to_string(), // admitted since
+        };
+        let arrears = if schedule.in_arrears(i) {
+            "  IN ARREARS"
+        } else {
+            ""
+        };
+        println!("  [{i}] {member}  {paid}{arrears}");
+    }
+}
+
+pub fn print_rosca(rotation: &Rosca, fund: &SquadMintFund, schedule: &DuesSchedule, decimals: u8) {
+    let rounds = rotation.order.len();
+    println!("rotation         {}", pda::rosca(&rotation.fund));
+    println!("fund             {}", rotation.fund);
+    if rotation.dues_start != schedule.start {
+        println!("status           stranded: dues were restarted; start a new rotation");
+    } else if let Some(next) = rotation.next_recipient() {
+        let through = rotation.first_period + rotation.round;
+        let funded = rosca::round_is_funded(&schedule.periods_paid, rounds, through);
+        println!(
+            "next round       {} of {rounds}, to [{next}], {}",
+            rotation.round + 1,
+            if funded {
+                "ready to pay"
+            } else {
+                "waiting on dues"
+            }
+        );
+        println!(
+            "pot              {} so far",
+            format_amount(rotation.pots[rotation.round as usize], decimals)
+        );
+    } else {
+        println!("status           complete");
+    }
+    println!("order");
+    for (round, (&index, &pot)) in rotation.order.iter().zip(&rotation.pots).enumerate() {
+        let paid = if (round as u32) < rotation.round {
+            "  paid"
+        } else {
+            ""
+        };
+        println!(
+            "  {}. [{index}] {}  {}{paid}",
+            round + 1,
+            fund.members()[index as usize],
+            format_amount(pot, decimals)
+        );
+    }
+}
+
+pub fn print_stream(address: &Pubkey, stream: &Stream, balance: u64, now: i64, decimals: u8) {
+    println!("stream           {address}");
+    println!("fund             {}", stream.fund);
+    println!("recipient        {}", stream.recipient);
+    println!("proposal nonce   {}", stream.nonce);
+    println!(
+        "vesting          {} to {} (cliff {})",
+        stream.start, stream.end, stream.cliff
+    );
+    println!("escrowed         {}", format_amount(balance, decimals));
+    println!(
+        "claimed          {}",
+        format_amount(stream.claimed, decimals)
+    );
+    println!(
+        "claimable now    {}",
+        format_amount(stream.claimable(balance, now), decimals)
+    );
+    if let Some(cancelled_at) = stream.cancelled_at {
+        println!("cancelled at     {cancelled_at}");
+    }
+}
+
+pub fn print_join_request(address: &Pubkey, request: &JoinRequestCustodialWallet, decimals: u8) {
+    println!("join request     {address}");
+    println!(
+        "fund             {}",
+        request.request_to_join_squad_mint_fund
+    );
+    println!("joiner           {}", request.request_to_join_user);
+    println!(
+        "escrowed         {}",
+        format_amount(request.join_amount(), decimals)
+    );
+    println!("escrow account   {}", pda::join_escrow(address));
+}
+
+pub fn print_instruction(ix: &Instruction) {
+    println!("program          {}", ix.program_id);
+    println!("accounts");
+    for (i, meta) in ix.accounts.iter().enumerate() {
+        let flags = match (meta.is_signer, meta.is_writable) {
+            (true, true) => "signer, writable",
+            (true, false) => "signer",
+            (false, true) => "writable",
+            (false, false) => "",
+        };
+        println!("  [{i:>2}] {}  {flags}", meta.pubkey);
+    }
+    println!("data             {}", bs58::encode(&ix.data).into_string());
+}
+
+// Everything a signer needs to decide whether to sign: what each instruction
+// does, the accounts it touches, and who has signed so far.
+pub fn print_transaction(tx: &SolanaTransaction) {
+    let message = &tx.message;
+    println!("fee payer        {}", message.account_keys[0]);
+    let nonced = uses_durable_nonce(tx).is_some();
+    println!(
+        "{}{}",
+        if nonced {
+            "durable nonce    "
+        } else {
+            "blockhash        "
+        },
+        message.recent_blockhash
+    );
+    for (i, ix) in offline::instructions(tx).iter().enumerate() {
+        let what = if i == 0 && nonced {
+            format!(
+                "advance nonce account {} (authority {})",
+                ix.accounts[0].pubkey, ix.accounts[2].pubkey
+            )
+        } else {
+            instructions::describe(ix).unwrap_or_else(|| "unrecognized instruction".to_string())
+        };
+        println!("instruction {i}    {what}");
+        print_instruction(ix);
+    }
+    let missing = offline::missing_signers(tx);
+    println!("signatures");
+    for key in &message.account_keys[..tx.signatures.len()] {
+        let status = if missing.contains(key) {
+            "MISSING"
+        } else {
+            "signed"
+        };
+        println!("  {key}  {status}");
+    }
+}
+
+pub fn print_simulation(simulation: &Simulation) {
+    match &simulation.err {
+        None => println!("simulation       ok"),
+        Some(err) => println!("simulation       FAILED: {err}"),
+    }
+    if let Some(units) = simulation.units_consumed {
+        println!("compute units    {units}");
+    }
+    for log in &simulation.logs {
+        println!("  {log}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_decimal_amounts_exactly() {
+        assert_eq!(parse_amount("1", 6), Ok(1_000_000));
+        assert_eq!(parse_amount("1.11", 6), Ok(1_110_000));
+        assert_eq!(parse_amount("0.000001", 6), Ok(1));
+        assert_eq!(parse_amount("42", 0), Ok(42));
+        assert_eq!(parse_amount("18446744073709.551615", 6), Ok(u64::MAX));
+    }
+
+    #[test]
+    fn rejects_ambiguous_or_lossy_amounts() {
+        for bad in ["", ".5", "1.", "-1", "1,5", "1e6", " 1", "0x10"] {
+            assert!(parse_amount(bad, 6).is_err(), "{bad:?} parsed");
+        }
+        assert!(parse_amount("0.0000001", 6).is_err());
+        assert!(parse_amount("18446744073709.551616", 6).is_err());
+    }
+
+    #[test]
+    fn formats_with_every_decimal() {
+        assert_eq!(format_amount(1_110_000, 6), "1.110000");
+        assert_eq!(format_amount(1, 6), "0.000001");
+        assert_eq!(format_amount(7, 0), "7");
+        for amount in [0, 1, 999_999, 1_000_000, u64::MAX] {
+            assert_eq!(parse_amount(&format_amount(amount, 6), 6), Ok(amount));
+        }
+    }
+}
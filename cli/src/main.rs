@@ -0,0 +1,1357 @@
+//! `squadmint`: operate squad_mint_multi_sig funds from the command line.
+//!
+//! Every command signs with one keypair (`--keypair`), which is both the fee
+//! payer and the acting role: the owner for `join accept/reject`, the joiner
+//! for `join request`, a member for `proposal create/vote`, the upgrade
+//! authority or config admin for `config init/set-*`. `--dry-run`
+//! prints the transaction and its simulation instead of sending it.
+//!
+//! Keys that never touch a networked machine: build with `--nonce` (a durable
+//! nonce account, so the transaction doesn't expire) and `--unsigned <FILE>`
+//! (with `--signer <PUBKEY>` for the offline key), carry the file across,
+//! `tx sign` it there, carry it back and `tx send` it.
+
+mod display;
+mod offline;
+mod rpc;
+
+use std::{
+    cell::OnceCell,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction, Discriminator};
+use anchor_spl::{
+    token,
+    token_2022::{
+        self,
+        spl_token_2022::{extension::StateWithExtensions, state::Mint},
+    },
+};
+use anyhow::{anyhow, bail, Context as _, Result};
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use serde_json::json;
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_signer::Signer;
+use solana_system_interface::instruction::advance_nonce_account;
+use solana_transaction::Transaction as SolanaTransaction;
+use squad_mint_client::{
+    encode_handle, fees, instructions, invite, layout, pda, state, Config, DuesSchedule,
+    HandleRegistry, Invite, Membership, Rosca, SquadMintFund, Stream, StreamTerms, TokenMint,
+    PROGRAM_ID,
+};
+
+use display::{format_amount, parse_amount};
+use rpc::RpcClient;
+
+#[derive(Parser)]
+#[command(name = "squadmint", version, about)]
+struct Cli {
+    #[command(flatten)]
+    global: GlobalArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+struct GlobalArgs {
+    /// JSON-RPC endpoint (defaults to a local solana-test-validator)
+    #[arg(
+        long,
+        short = 'u',
+        global = true,
+        env = "SQUADMINT_RPC_URL",
+        default_value = "http://127.0.0.1:8899"
+    )]
+    url: String,
+
+    /// Signer and fee payer [default: ~/.config/solana/id.json]
+    #[arg(long, short = 'k', global = true, env = "SQUADMINT_KEYPAIR")]
+    keypair: Option<PathBuf>,
+
+    /// Token mint the fund holds (SPL Token or Token-2022) [default: the
+    /// program config's allowed mint, if it allows exactly one]
+    #[arg(long, global = true)]
+    mint: Option<Pubkey>,
+
+    /// Print the transaction and simulate it instead of sending
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Durable nonce account to use instead of a recent blockhash; the
+    /// transaction stays valid until the nonce is advanced
+    #[arg(long, global = true)]
+    nonce: Option<Pubkey>,
+
+    /// Write the transaction unsigned to FILE, for `tx sign` on the machine
+    /// holding the key, instead of sending it
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        requires = "nonce",
+        conflicts_with = "dry_run"
+    )]
+    unsigned: Option<PathBuf>,
+
+    /// With --unsigned: the acting key, whose keypair is elsewhere
+    /// [default: --keypair's address]
+    #[arg(long, global = true, requires = "unsigned")]
+    signer: Option<Pubkey>,
+
+    /// With --unsigned: who pays the fees [default: --signer]
+    #[arg(long, global = true, requires = "unsigned")]
+    fee_payer: Option<Pubkey>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create, inspect and find funds
+    #[command(subcommand)]
+    Fund(FundCommand),
+    /// Request to join a fund, or accept / reject a request (owner)
+    #[command(subcommand)]
+    Join(JoinCommand),
+    /// Open, vote on and inspect payout proposals
+    #[command(subcommand)]
+    Proposal(ProposalCommand),
+    /// Set, approve, collect and inspect a fund's recurring dues
+    #[command(subcommand)]
+    Dues(DuesCommand),
+    /// Run a rotating-savings rotation over the fund's dues
+    #[command(subcommand)]
+    Rosca(RoscaCommand),
+    /// Claim from, inspect and close vesting streams
+    #[command(subcommand)]
+    Stream(StreamCommand),
+    /// Inspect a fund's token vault
+    #[command(subcommand)]
+    Vault(VaultCommand),
+    /// Review, sign and send transaction files written with --unsigned
+    #[command(subcommand)]
+    Tx(TxCommand),
+    /// Show or change the program config: allowed mints and its admin
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Subcommand)]
+enum FundCommand {
+    /// Create a fund owned by --keypair
+    Create {
+        /// 1-15 bytes of UTF-8; with the owner it determines the fund address
+        #[arg(long)]
+        handle: String,
+        /// Deposit each joiner escrows, in tokens (e.g. 1.5)
+        #[arg(long)]
+        join_amount: String,
+        /// Guardian for owner recovery (repeatable)
+        #[arg(long = "guardian")]
+        guardians: Vec<Pubkey>,
+        #[arg(long, default_value_t = 0)]
+        guardian_threshold: u8,
+        /// Also claim the handle as the fund's global name (fails if taken)
+        #[arg(long)]
+        claim_handle: bool,
+    },
+    /// Print a fund's decoded state
+    Show { fund: Pubkey },
+    /// List funds, optionally only those with a given owner or member
+    List {
+        #[arg(long)]
+        owner: Option<Pubkey>,
+        #[arg(long)]
+        member: Option<Pubkey>,
+    },
+    /// Upgrade a fund to the current account layout (--fee-payer pays the extra rent)
+    Migrate { fund: Pubkey },
+    /// Register the membership index entry of every member who lacks one
+    Index { fund: Pubkey },
+    /// Claim the fund's handle as its global name (owner only)
+    ClaimHandle { fund: Pubkey },
+    /// Print the fund that claimed a handle
+    Resolve { handle: String },
+    /// Free a handle whose fund has been closed
+    ReleaseHandle { handle: String },
+    /// Set who may request to join (owner only); without flags, anyone
+    InvitePolicy {
+        fund: Pubkey,
+        /// Join requests need an owner-signed invite or an allowlist proof
+        #[arg(long)]
+        require_invite: bool,
+        /// Wallets (one per line) whose allowlist proofs are accepted
+        #[arg(long, requires = "require_invite")]
+        allowlist: Option<PathBuf>,
+    },
+    /// Let joiners contribute any amount from MIN to MAX (owner only)
+    JoinRange {
+        fund: Pubkey,
+        min: String,
+        /// Defaults to MIN, a fixed price
+        #[arg(long)]
+        max: Option<String>,
+    },
+    /// Admit joiners instantly, without owner approval (owner only)
+    OpenAdmission {
+        fund: Pubkey,
+        #[arg(action = ArgAction::Set)]
+        open: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum JoinCommand {
+    /// Escrow a join deposit from --keypair's token account, or join at once
+    /// if the fund has open admission
+    Request {
+        fund: Pubkey,
+        /// Defaults to the fund's (minimum) join amount
+        #[arg(long)]
+        amount: Option<String>,
+        /// Owner-signed invite from `join invite`, with --invite-expires
+        #[arg(long, requires = "invite_expires", conflicts_with = "allowlist")]
+        invite_signature: Option<String>,
+        #[arg(long, requires = "invite_signature")]
+        invite_expires: Option<i64>,
+        /// The allowlist file the fund's invite root was built from
+        #[arg(long)]
+        allowlist: Option<PathBuf>,
+    },
+    /// Sign an invite for `invitee` to an invite-only fund (owner only, offline-safe)
+    Invite {
+        fund: Pubkey,
+        invitee: Pubkey,
+        #[arg(long, default_value_t = 7)]
+        valid_days: i64,
+    },
+    /// Admit a joiner, moving their deposit into the vault (owner only)
+    Accept { fund: Pubkey, joiner: Pubkey },
+    /// Refund a joiner's deposit (owner only)
+    Reject { fund: Pubkey, joiner: Pubkey },
+    /// Print a pending join request
+    Show { fund: Pubkey, joiner: Pubkey },
+    /// Upgrade a join request opened by an older program version (--fee-payer pays the extra rent)
+    Migrate { fund: Pubkey, joiner: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum ProposalCommand {
+    /// Propose paying `amount` from the vault to `to` (members only)
+    Create {
+        fund: Pubkey,
+        #[arg(long)]
+        amount: String,
+        /// Recipient wallet; its token account is created on payout if missing
+        #[arg(long)]
+        to: Pubkey,
+    },
+    /// Propose streaming `amount` to `to`, vesting over --days (members only)
+    Stream {
+        fund: Pubkey,
+        #[arg(long)]
+        amount: String,
+        #[arg(long)]
+        to: Pubkey,
+        #[arg(long)]
+        days: i64,
+        /// Nothing is claimable until this many days in
+        #[arg(long, default_value_t = 0)]
+        cliff_days: i64,
+        /// Unix timestamp vesting starts at [default: now]
+        #[arg(long)]
+        start: Option<i64>,
+    },
+    /// Propose cancelling STREAM; the unvested rest returns to the vault (members only)
+    CancelStream { fund: Pubkey, stream: Pubkey },
+    /// Vote on the fund's open proposal; the deciding vote executes it
+    Vote { fund: Pubkey, vote: Vote },
+    /// Print a proposal and its votes (default: the fund's open proposal)
+    Show {
+        fund: Pubkey,
+        #[arg(long)]
+        nonce: Option<u64>,
+    },
+    /// Upgrade a proposal opened by an older program version (--fee-payer pays the extra rent)
+    Migrate {
+        fund: Pubkey,
+        #[arg(long)]
+        nonce: Option<u64>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DuesCommand {
+    /// (Re)start dues of AMOUNT per member every --period-days (owner only)
+    Set {
+        fund: Pubkey,
+        amount: String,
+        #[arg(long, default_value_t = 30)]
+        period_days: i64,
+    },
+    /// Let the fund pull up to ALLOWANCE in dues from --keypair's token account
+    Approve { fund: Pubkey, allowance: String },
+    /// Pull every member's outstanding dues into the vault (anyone)
+    Collect { fund: Pubkey },
+    /// Print the schedule and who is in arrears
+    Show { fund: Pubkey },
+    /// Stop collecting dues (owner only; anyone once the fund is closed)
+    Close { fund: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum RoscaCommand {
+    /// Start a rotation over the current members, from this dues period (owner only)
+    Start {
+        fund: Pubkey,
+        /// Shuffle the payout order instead of using the member order
+        #[arg(long)]
+        draw: bool,
+    },
+    /// Pay the current round's pot to its member once its dues are in (anyone)
+    Pay { fund: Pubkey },
+    /// Print the payout order and the next round
+    Show { fund: Pubkey },
+    /// End the rotation (owner only; anyone once the fund is closed)
+    Close { fund: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum StreamCommand {
+    /// Withdraw what has vested to --keypair's token account (the recipient)
+    Claim { stream: Pubkey },
+    /// Print a stream's schedule and what has vested
+    Show { stream: Pubkey },
+    /// Close a stream with nothing left to pay (anyone)
+    Close { stream: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum VaultCommand {
+    /// Print the vault's token balance
+    Balance { fund: Pubkey },
+}
+
+#[derive(Subcommand)]
+enum TxCommand {
+    /// Decode a transaction file and show who still has to sign (offline)
+    Inspect { file: PathBuf },
+    /// Add --keypair's signature to a transaction file, in place (offline)
+    Sign { file: PathBuf },
+    /// Send a transaction file once every signature is on it
+    Send { file: PathBuf },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print the config
+    Show,
+    /// Create the config (the program's upgrade authority, once)
+    Init {
+        #[arg(long)]
+        admin: Pubkey,
+        /// Mint funds may use (repeatable)
+        #[arg(long = "allow")]
+        allowed_mints: Vec<Pubkey>,
+    },
+    /// Replace the allowed mints (admin only)
+    SetMints {
+        /// Mint funds may use (repeatable); omit to allow none
+        #[arg(long = "allow")]
+        allowed_mints: Vec<Pubkey>,
+    },
+    /// Hand the config to a new admin (admin only)
+    SetAdmin { new_admin: Pubkey },
+    /// Set the protocol fee treasury and rates (admin only)
+    SetFee {
+        #[arg(long)]
+        treasury: Pubkey,
+        /// Fee on join deposits, in basis points
+        #[arg(long, default_value_t = 0)]
+        join_bps: u16,
+        /// Fee on executed payouts, in basis points
+        #[arg(long, default_value_t = 0)]
+        payout_bps: u16,
+        /// Flat fee kept from rejected join deposits, in tokens of whichever mint
+        #[arg(long, default_value = "0")]
+        reject_fee: String,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Vote {
+    Yes,
+    No,
+}
+
+struct Context {
+    rpc: RpcClient,
+    keypair_path: PathBuf,
+    mint: Option<Pubkey>,
+    // The mint's address, token program and decimals, read from the chain on
+    // first use.
+    mint_account: OnceCell<(TokenMint, u8)>,
+    dry_run: bool,
+    nonce: Option<Pubkey>,
+    unsigned: Option<PathBuf>,
+    signer: Option<Pubkey>,
+    fee_payer: Option<Pubkey>,
+}
+
+impl Context {
+    fn new(args: GlobalArgs) -> Result<Self> {
+        let keypair_path = match args.keypair {
+            Some(path) => path,
+            None => {
+                let home = std::env::var_os("HOME").ok_or_else(|| anyhow!("HOME is not set"))?;
+                PathBuf::from(home).join(".config/solana/id.json")
+            }
+        };
+        Ok(Self {
+            rpc: RpcClient::new(&args.url),
+            keypair_path,
+            mint: args.mint,
+            mint_account: OnceCell::new(),
+            dry_run: args.dry_run,
+            nonce: args.nonce,
+            unsigned: args.unsigned,
+            signer: args.signer,
+            fee_payer: args.fee_payer,
+        })
+    }
+
+    // Loaded on first use so read-only commands work without a keypair.
+    fn keypair(&self) -> Result<Keypair> {
+        read_keypair_file(&self.keypair_path)
+            .map_err(|e| anyhow!("keypair {}: {e}", self.keypair_path.display()))
+    }
+
+    // The acting key: --signer when building for an offline signer, otherwise
+    // --keypair's address.
+    fn signer(&self) -> Result<Pubkey> {
+        match self.signer {
+            Some(signer) => Ok(signer),
+            None => Ok(self.keypair()?.pubkey()),
+        }
+    }
+
+    fn fee_payer(&self) -> Result<Pubkey> {
+        match self.fee_payer {
+            Some(fee_payer) => Ok(fee_payer),
+            None => self.signer(),
+        }
+    }
+
+    fn mint(&self) -> Result<&(TokenMint, u8)> {
+        if let Some(mint) = self.mint_account.get() {
+            return Ok(mint);
+        }
+        let address = match self.mint {
+            Some(address) => address,
+            None => match self.config()?.allowed_mints() {
+                [address] => *address,
+                allowed => bail!(
+                    "the program allows {} mints; choose one with --mint",
+                    allowed.len()
+                ),
+            },
+        };
+        let (owner, data) = self
+            .rpc
+            .account(&address)?
+            .ok_or_else(|| anyhow!("mint {address} does not exist"))?;
+        if owner != token::ID && owner != token_2022::ID {
+            bail!("{address} is not a token mint (owned by {owner})");
+        }
+        let decimals = StateWithExtensions::<Mint>::unpack(&data)
+            .with_context(|| format!("{address} is not a token mint"))?
+            .base
+            .decimals;
+        Ok(self
+            .mint_account
+            .get_or_init(|| (TokenMint::new(address, owner), decimals)))
+    }
+
+    fn token_mint(&self) -> Result<TokenMint> {
+        Ok(self.mint()?.0)
+    }
+
+    fn decimals(&self) -> Result<u8> {
+        Ok(self.mint()?.1)
+    }
+
+    fn amount(&self, text: &str) -> Result<u64> {
+        parse_amount(text, self.decimals()?).map_err(|e| anyhow!(e))
+    }
+
+    fn config(&self) -> Result<Config> {
+        let address = pda::config();
+        let data = self.rpc.account_data(&address)?.ok_or_else(|| {
+            anyhow!("the program config ({address}) does not exist; create it with `config init`")
+        })?;
+        state::config(&data).with_context(|| format!("{address} is not the program config"))
+    }
+
+    fn fund(&self, address: &Pubkey) -> Result<SquadMintFund> {
+        let data = self
+            .rpc
+            .account_data(address)?
+            .ok_or_else(|| anyhow!("fund {address} does not exist"))?;
+        state::fund(&data).with_context(|| format!("{address} is not a fund"))
+    }
+
+    fn handle_registry(&self, handle: &str) -> Result<HandleRegistry> {
+        let address = pda::handle_registry(&encode_handle(handle)?);
+        let data = self
+            .rpc
+            .account_data(&address)?
+            .ok_or_else(|| anyhow!("handle {handle:?} is not claimed"))?;
+        state::handle_registry(&data).with_context(|| format!("{address} is not a handle registry"))
+    }
+
+    fn dues(&self, fund: &Pubkey) -> Result<DuesSchedule> {
+        let address = pda::dues(fund);
+        let data = self
+            .rpc
+            .account_data(&address)?
+            .ok_or_else(|| anyhow!("fund {fund} has no dues schedule"))?;
+        state::dues(&data).with_context(|| format!("{address} is not a dues schedule"))
+    }
+
+    fn rosca(&self, fund: &Pubkey) -> Result<Rosca> {
+        let address = pda::rosca(fund);
+        let data = self
+            .rpc
+            .account_data(&address)?
+            .ok_or_else(|| anyhow!("fund {fund} has no rotation"))?;
+        state::rosca(&data).with_context(|| format!("{address} is not a rotation"))
+    }
+
+    fn stream(&self, address: &Pubkey) -> Result<Stream> {
+        let data = self
+            .rpc
+            .account_data(address)?
+            .ok_or_else(|| anyhow!("stream {address} does not exist"))?;
+        state::stream(&data).with_context(|| format!("{address} is not a stream"))
+    }
+
+    fn submit(&self, ix: Instruction) -> Result<()> {
+        self.submit_all(vec![ix])
+    }
+
+    // With --unsigned: write the transaction for offline signing. Otherwise
+    // sign with --keypair and send (or, with --dry-run, simulate).
+    fn submit_all(&self, mut instructions: Vec<Instruction>) -> Result<()> {
+        let blockhash = match &self.nonce {
+            Some(nonce) => {
+                let data = self
+                    .rpc
+                    .account_data(nonce)?
+                    .ok_or_else(|| anyhow!("nonce account {nonce} does not exist"))?;
+                let (authority, value) = offline::nonce_state(&data)
+                    .with_context(|| format!("nonce account {nonce}"))?;
+                instructions.insert(0, advance_nonce_account(nonce, &authority));
+                value
+            }
+            None => self.rpc.latest_blockhash()?,
+        };
+        let mut tx = SolanaTransaction::new_with_payer(&instructions, Some(&self.fee_payer()?));
+        tx.message.recent_blockhash = blockhash;
+
+        if let Some(path) = &self.unsigned {
+            offline::write(path, &tx)?;
+            display::print_transaction(&tx);
+            println!("unsigned         {}", path.display());
+            return Ok(());
+        }
+        tx.try_partial_sign(&[&self.keypair()?], blockhash)?;
+        let missing = offline::missing_signers(&tx);
+        if !missing.is_empty() {
+            bail!(
+                "the transaction also needs signatures from {missing:?}; \
+                 write it with --unsigned and sign it with `tx sign`"
+            );
+        }
+        self.send(&tx)
+    }
+
+    fn send(&self, tx: &SolanaTransaction) -> Result<()> {
+        if self.dry_run {
+            display::print_transaction(tx);
+            let simulation = self.rpc.simulate(tx)?;
+            display::print_simulation(&simulation);
+            if simulation.err.is_some() {
+                bail!("simulation failed");
+            }
+            return Ok(());
+        }
+        let signature = self.rpc.send_and_confirm(tx)?;
+        println!("signature        {signature}");
+        Ok(())
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let ctx = Context::new(cli.global)?;
+    match cli.command {
+        Command::Fund(command) => fund(&ctx, command),
+        Command::Join(command) => join(&ctx, command),
+        Command::Proposal(command) => proposal(&ctx, command),
+        Command::Dues(command) => dues(&ctx, command),
+        Command::Rosca(command) => rosca(&ctx, command),
+        Command::Stream(command) => stream(&ctx, command),
+        Command::Tx(command) => tx(&ctx, command),
+        Command::Config(command) => config(&ctx, command),
+        Command::Vault(VaultCommand::Balance { fund }) => {
+            let vault = pda::vault(&fund);
+            let balance = ctx.rpc.token_balance(&vault)?;
+            println!("vault            {vault}");
+            println!(
+                "balance          {}",
+                format_amount(balance, ctx.decimals()?)
+            );
+            Ok(())
+        }
+    }
+}
+
+fn fund(ctx: &Context, command: FundCommand) -> Result<()> {
+    match command {
+        FundCommand::Create {
+            handle,
+            join_amount,
+            guardians,
+            guardian_threshold,
+            claim_handle,
+        } => {
+            let owner = ctx.signer()?;
+            let handle = encode_handle(&handle)?;
+            let fund = pda::fund(&handle, &owner);
+            let mut ixs = vec![instructions::initialize(
+                &ctx.fee_payer()?,
+                &owner,
+                &handle,
+                &ctx.token_mint()?,
+                ctx.amount(&join_amount)?,
+                guardians,
+                guardian_threshold,
+            )];
+            if claim_handle {
+                ixs.push(instructions::claim_handle(
+                    &ctx.fee_payer()?,
+                    &fund,
+                    &owner,
+                    &handle,
+                ));
+            }
+            println!("fund             {fund}");
+            ctx.submit_all(ixs)
+        }
+        FundCommand::Show { fund } => {
+            display::print_fund(&fund, &ctx.fund(&fund)?, ctx.decimals()?);
+            Ok(())
+        }
+        FundCommand::List {
+            owner,
+            member: Some(member),
+        } => {
+            // One Membership per fund the wallet is in, so no fund scan.
+            let filters = json!([
+                { "memcmp": {
+                    "offset": layout::DISCRIMINATOR,
+                    "bytes": bs58::encode(Membership::DISCRIMINATOR).into_string(),
+                }},
+                { "memcmp": {
+                    "offset": layout::membership::MEMBER,
+                    "bytes": member.to_string(),
+                }},
+            ]);
+            for (address, data) in ctx.rpc.program_accounts(&PROGRAM_ID, filters)? {
+                let membership = match state::membership(&data) {
+                    Ok(membership) => membership,
+                    Err(e) => {
+                        eprintln!("{address}: skipped, does not decode ({e})");
+                        continue;
+                    }
+                };
+                match ctx.fund(&membership.fund) {
+                    Ok(fund)
+                        if fund.members().contains(&member)
+                            && owner.is_none_or(|o| fund.owner() == o) =>
+                    {
+                        display::print_fund_row(&membership.fund, &fund)
+                    }
+                    Ok(_) => {}
+                    Err(e) => eprintln!("{address}: stale membership ({e:#})"),
+                }
+            }
+            Ok(())
+        }
+        FundCommand::List {
+            owner,
+            member: None,
+        } => {
+            let mut filters = vec![json!({ "memcmp": {
+                "offset": layout::DISCRIMINATOR,
+                "bytes": bs58::encode(SquadMintFund::DISCRIMINATOR).into_string(),
+            }})];
+            if let Some(owner) = owner {
+                filters.push(json!({ "memcmp": {
+                    "offset": layout::fund::OWNER,
+                    "bytes": owner.to_string(),
+                }}));
+            }
+            for (address, data) in ctx.rpc.program_accounts(&PROGRAM_ID, json!(filters))? {
+                match state::fund(&data) {
+                    Ok(fund) => display::print_fund_row(&address, &fund),
+                    Err(e) => eprintln!("{address}: skipped, does not decode ({e})"),
+                }
+            }
+            Ok(())
+        }
+        FundCommand::Migrate { fund } => {
+            let version = ctx.fund(&fund)?.version();
+            if version.is_current() {
+                println!("fund {fund} is already at v{}", version.get());
+                return Ok(());
+            }
+            ctx.submit(instructions::migrate_fund(&ctx.fee_payer()?, &fund))
+        }
+        FundCommand::Index { fund } => {
+            for member in ctx.fund(&fund)?.members() {
+                if ctx
+                    .rpc
+                    .account_data(&pda::membership(&fund, member))?
+                    .is_some()
+                {
+                    continue;
+                }
+                println!("member           {member}");
+                ctx.submit(instructions::register_membership(
+                    &ctx.fee_payer()?,
+                    &fund,
+                    member,
+                ))?;
+            }
+            Ok(())
+        }
+        FundCommand::ClaimHandle { fund } => {
+            let handle = *ctx.fund(&fund)?.account_handle();
+            ctx.submit(instructions::claim_handle(
+                &ctx.fee_payer()?,
+                &fund,
+                &ctx.signer()?,
+                &handle,
+            ))
+        }
+        FundCommand::Resolve { handle } => {
+            println!("fund             {}", ctx.handle_registry(&handle)?.fund);
+            Ok(())
+        }
+        FundCommand::InvitePolicy {
+            fund,
+            require_invite,
+            allowlist,
+        } => {
+            let invite_root = match allowlist {
+                Some(path) => {
+                    let leaves: Vec<_> = read_allowlist(&path)?.iter().map(invite::leaf).collect();
+                    Some(invite::root(&leaves))
+                }
+                None => None,
+            };
+            ctx.submit(instructions::set_invite_policy(
+                &fund,
+                &ctx.signer()?,
+                require_invite,
+                invite_root,
+            ))
+        }
+        FundCommand::JoinRange { fund, min, max } => {
+            let min = ctx.amount(&min)?;
+            let max = match max {
+                Some(text) => ctx.amount(&text)?,
+                None => min,
+            };
+            ctx.submit(instructions::set_join_range(
+                &fund,
+                &ctx.signer()?,
+                &ctx.token_mint()?,
+                min,
+                max,
+            ))
+        }
+        FundCommand::OpenAdmission { fund, open } => ctx.submit(instructions::set_open_admission(
+            &fund,
+            &ctx.signer()?,
+            open,
+        )),
+        FundCommand::ReleaseHandle { handle } => {
+            let registry = ctx.handle_registry(&handle)?;
+            ctx.submit(instructions::release_handle(
+                &registry.handle,
+                &registry.fund,
+                &registry.rent_payer,
+            ))
+        }
+    }
+}
+
+fn dues(ctx: &Context, command: DuesCommand) -> Result<()> {
+    match command {
+        DuesCommand::Set {
+            fund,
+            amount,
+            period_days,
+        } => ctx.submit(instructions::set_dues(
+            &ctx.fee_payer()?,
+            &fund,
+            &ctx.signer()?,
+            &ctx.token_mint()?,
+            ctx.amount(&amount)?,
+            period_days * 24 * 60 * 60,
+        )),
+        DuesCommand::Approve { fund, allowance } => {
+            println!("delegate         {}", pda::dues(&fund));
+            ctx.submit(instructions::approve_dues(
+                &ctx.signer()?,
+                &fund,
+                &ctx.token_mint()?,
+                ctx.decimals()?,
+                ctx.amount(&allowance)?,
+            ))
+        }
+        DuesCommand::Collect { fund } => {
+            let members = ctx.fund(&fund)?.members().to_vec();
+            ctx.submit(instructions::collect_dues(
+                &fund,
+                &ctx.token_mint()?,
+                &members,
+            ))
+        }
+        DuesCommand::Show { fund } => {
+            let schedule = ctx.dues(&fund)?;
+            display::print_dues(&schedule, &ctx.fund(&fund)?, ctx.decimals()?);
+            Ok(())
+        }
+        DuesCommand::Close { fund } => {
+            let schedule = ctx.dues(&fund)?;
+            ctx.submit(instructions::close_dues(
+                &fund,
+                &ctx.signer()?,
+                &schedule.rent_payer,
+            ))
+        }
+    }
+}
+
+fn rosca(ctx: &Context, command: RoscaCommand) -> Result<()> {
+    match command {
+        RoscaCommand::Start { fund, draw } => ctx.submit(instructions::start_rosca(
+            &ctx.fee_payer()?,
+            &fund,
+            &ctx.signer()?,
+            draw,
+        )),
+        RoscaCommand::Pay { fund } => {
+            let rotation = ctx.rosca(&fund)?;
+            let index = rotation
+                .next_recipient()
+                .ok_or_else(|| anyhow!("every round of this rotation has been paid"))?;
+            let recipient = ctx.fund(&fund)?.members()[index];
+            println!("recipient        {recipient}");
+            ctx.submit(instructions::pay_rosca_round(
+                &ctx.fee_payer()?,
+                &fund,
+                &recipient,
+                &ctx.token_mint()?,
+                &ctx.config()?.treasury(),
+            ))
+        }
+        RoscaCommand::Show { fund } => {
+            let rotation = ctx.rosca(&fund)?;
+            display::print_rosca(
+                &rotation,
+                &ctx.fund(&fund)?,
+                &ctx.dues(&fund)?,
+                ctx.decimals()?,
+            );
+            Ok(())
+        }
+        RoscaCommand::Close { fund } => {
+            let rotation = ctx.rosca(&fund)?;
+            ctx.submit(instructions::close_rosca(
+                &fund,
+                &ctx.signer()?,
+                &rotation.rent_payer,
+            ))
+        }
+    }
+}
+
+fn stream(ctx: &Context, command: StreamCommand) -> Result<()> {
+    match command {
+        StreamCommand::Claim { stream } => ctx.submit(instructions::claim_stream(
+            &stream,
+            &ctx.signer()?,
+            &ctx.token_mint()?,
+        )),
+        StreamCommand::Show { stream } => {
+            let state = ctx.stream(&stream)?;
+            let escrow = ctx.token_mint()?.ata(&stream);
+            let balance = ctx.rpc.token_balance(&escrow)?;
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+            display::print_stream(&stream, &state, balance, now, ctx.decimals()?);
+            Ok(())
+        }
+        StreamCommand::Close { stream } => {
+            let state = ctx.stream(&stream)?;
+            ctx.submit(instructions::close_stream(
+                &state.fund,
+                state.nonce,
+                &state.rent_payer,
+                &ctx.token_mint()?,
+            ))
+        }
+    }
+}
+
+fn join(ctx: &Context, command: JoinCommand) -> Result<()> {
+    match command {
+        JoinCommand::Request {
+            fund,
+            amount,
+            invite_signature,
+            invite_expires,
+            allowlist,
+        } => {
+            let signer = ctx.signer()?;
+            let state = ctx.fund(&fund)?;
+            let amount = match amount {
+                Some(text) => ctx.amount(&text)?,
+                None => state.join_amount(),
+            };
+            let mut ixs = vec![];
+            let invite = match (invite_signature, invite_expires, allowlist) {
+                (Some(signature), Some(expires_at), _) => {
+                    let signature: [u8; 64] = bs58::decode(&signature)
+                        .into_vec()
+                        .ok()
+                        .and_then(|bytes| bytes.try_into().ok())
+                        .ok_or_else(|| anyhow!("--invite-signature is not a signature"))?;
+                    ixs.push(instructions::signed_invite(
+                        &state.effective_owner(),
+                        &signature,
+                        &fund,
+                        &signer,
+                        expires_at,
+                    ));
+                    Some(Invite::Signed { expires_at })
+                }
+                (_, _, Some(path)) => {
+                    let leaves: Vec<_> = read_allowlist(&path)?.iter().map(invite::leaf).collect();
+                    let index = leaves
+                        .iter()
+                        .position(|leaf| *leaf == invite::leaf(&signer))
+                        .ok_or_else(|| anyhow!("{signer} is not on {}", path.display()))?;
+                    Some(Invite::Allowlisted {
+                        proof: invite::proof(&leaves, index),
+                    })
+                }
+                _ => None,
+            };
+            if state.open_admission() {
+                ixs.push(instructions::join_open_fund(
+                    &ctx.fee_payer()?,
+                    &fund,
+                    &signer,
+                    &ctx.token_mint()?,
+                    amount,
+                    &ctx.config()?.treasury(),
+                    invite,
+                ));
+                println!("joining          {fund} (open admission)");
+            } else {
+                ixs.push(instructions::initiate_join_request(
+                    &ctx.fee_payer()?,
+                    &fund,
+                    &signer,
+                    &ctx.token_mint()?,
+                    amount,
+                    invite,
+                ));
+                println!("join request     {}", pda::join_request(&fund, &signer));
+            }
+            ctx.submit_all(ixs)
+        }
+        JoinCommand::Invite {
+            fund,
+            invitee,
+            valid_days,
+        } => {
+            let keypair = ctx.keypair()?;
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+            let expires_at = now + valid_days * 24 * 60 * 60;
+            let signature = keypair.sign_message(&invite::message(&fund, &invitee, expires_at));
+            println!("signed by        {}", keypair.pubkey());
+            println!("expires at       {expires_at}");
+            println!("signature        {signature}");
+            println!(
+                "invitee runs     squadmint join request {fund} \
+                 --invite-signature {signature} --invite-expires {expires_at}"
+            );
+            Ok(())
+        }
+        JoinCommand::Accept { fund, joiner } | JoinCommand::Reject { fund, joiner } => {
+            let accept = matches!(command, JoinCommand::Accept { .. });
+            let signer = ctx.signer()?;
+            let request = join_request(ctx, &fund, &joiner)?;
+            if request.rent_payer == Pubkey::default() {
+                bail!("the join request predates the rent payer; run `join migrate {fund} {joiner}` first");
+            }
+            let build = if accept {
+                instructions::add_member
+            } else {
+                instructions::reject_member
+            };
+            let ix = build(
+                &ctx.fee_payer()?,
+                &fund,
+                &signer,
+                &joiner,
+                &request.rent_payer,
+                &ctx.token_mint()?,
+                &ctx.config()?.treasury(),
+            );
+            ctx.submit(ix)
+        }
+        JoinCommand::Show { fund, joiner } => {
+            let request = join_request(ctx, &fund, &joiner)?;
+            display::print_join_request(
+                &pda::join_request(&fund, &joiner),
+                &request,
+                ctx.decimals()?,
+            );
+            Ok(())
+        }
+        JoinCommand::Migrate { fund, joiner } => {
+            let version = join_request(ctx, &fund, &joiner)?.trailer.version;
+            if version.is_current() {
+                println!("the join request is already at v{}", version.get());
+                return Ok(());
+            }
+            ctx.submit(instructions::migrate_join_request(
+                &ctx.fee_payer()?,
+                &fund,
+                &joiner,
+            ))
+        }
+    }
+}
+
+fn join_request(
+    ctx: &Context,
+    fund: &Pubkey,
+    joiner: &Pubkey,
+) -> Result<squad_mint_client::JoinRequestCustodialWallet> {
+    let address = pda::join_request(fund, joiner);
+    let data = ctx
+        .rpc
+        .account_data(&address)?
+        .ok_or_else(|| anyhow!("no pending join request from {joiner} to {fund}"))?;
+    Ok(state::join_request(&data)?)
+}
+
+fn proposal(ctx: &Context, command: ProposalCommand) -> Result<()> {
+    match command {
+        ProposalCommand::Create { fund, amount, to } => {
+            let signer = ctx.signer()?;
+            let nonce = ctx.fund(&fund)?.master_nonce();
+            let ix = instructions::create_proposal(
+                &ctx.fee_payer()?,
+                &fund,
+                nonce,
+                &signer,
+                &ctx.token_mint()?,
+                ctx.amount(&amount)?,
+                &to,
+            );
+            println!("proposal         {}", pda::proposal(&fund, nonce));
+            ctx.submit(ix)
+        }
+        ProposalCommand::Stream {
+            fund,
+            amount,
+            to,
+            days,
+            cliff_days,
+            start,
+        } => {
+            let signer = ctx.signer()?;
+            let nonce = ctx.fund(&fund)?.master_nonce();
+            let start = match start {
+                Some(start) => start,
+                None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64,
+            };
+            let terms = StreamTerms {
+                recipient: to,
+                amount: ctx.amount(&amount)?,
+                start,
+                cliff: start + cliff_days * 24 * 60 * 60,
+                end: start + days * 24 * 60 * 60,
+            };
+            let ix = instructions::propose_stream(
+                &ctx.fee_payer()?,
+                &fund,
+                nonce,
+                &signer,
+                &ctx.token_mint()?,
+                terms,
+            );
+            println!("proposal         {}", pda::proposal(&fund, nonce));
+            println!("stream           {}", pda::stream(&fund, nonce));
+            ctx.submit(ix)
+        }
+        ProposalCommand::CancelStream { fund, stream } => {
+            let signer = ctx.signer()?;
+            let nonce = ctx.fund(&fund)?.master_nonce();
+            let ix = instructions::propose_stream_cancel(
+                &ctx.fee_payer()?,
+                &fund,
+                nonce,
+                &signer,
+                &stream,
+            );
+            println!("proposal         {}", pda::proposal(&fund, nonce));
+            ctx.submit(ix)
+        }
+        ProposalCommand::Vote { fund, vote } => {
+            let signer = ctx.signer()?;
+            let state = ctx.fund(&fund)?;
+            if !state.has_active_vote() {
+                bail!("fund {fund} has no open proposal");
+            }
+            let proposal = open_proposal(ctx, &fund, state.master_nonce())?;
+            if proposal.rent_payer == Pubkey::default() {
+                bail!("the proposal predates the rent payer; run `proposal migrate {fund}` first");
+            }
+            let ix = instructions::submit_and_execute(
+                &ctx.fee_payer()?,
+                &fund,
+                &signer,
+                &proposal,
+                &ctx.token_mint()?,
+                &ctx.config()?.treasury(),
+                matches!(vote, Vote::Yes),
+            );
+            ctx.submit(ix)
+        }
+        ProposalCommand::Show { fund, nonce } => {
+            let state = ctx.fund(&fund)?;
+            let nonce = nonce.unwrap_or(state.master_nonce());
+            let proposal = open_proposal(ctx, &fund, nonce)?;
+            display::print_proposal(
+                &pda::proposal(&fund, nonce),
+                &proposal,
+                &state,
+                ctx.decimals()?,
+            );
+            Ok(())
+        }
+        ProposalCommand::Migrate { fund, nonce } => {
+            let nonce = match nonce {
+                Some(nonce) => nonce,
+                None => ctx.fund(&fund)?.master_nonce(),
+            };
+            let version = open_proposal(ctx, &fund, nonce)?.trailer.version;
+            if version.is_current() {
+                println!("the proposal is already at v{}", version.get());
+                return Ok(());
+            }
+            ctx.submit(instructions::migrate_proposal(
+                &ctx.fee_payer()?,
+                &fund,
+                nonce,
+            ))
+        }
+    }
+}
+
+// `inspect` and `sign` never touch the network, so they run on the offline
+// machine.
+fn tx(ctx: &Context, command: TxCommand) -> Result<()> {
+    match command {
+        TxCommand::Inspect { file } => {
+            display::print_transaction(&offline::read(&file)?);
+            Ok(())
+        }
+        TxCommand::Sign { file } => {
+            let mut tx = offline::read(&file)?;
+            let keypair = ctx.keypair()?;
+            display::print_transaction(&tx);
+            let blockhash = tx.message.recent_blockhash;
+            tx.try_partial_sign(&[&keypair], blockhash)
+                .map_err(|e| anyhow!("cannot sign as {}: {e}", keypair.pubkey()))?;
+            offline::write(&file, &tx)?;
+            println!("signed as        {}", keypair.pubkey());
+            let missing = offline::missing_signers(&tx);
+            if missing.is_empty() {
+                println!("complete; send it with `squadmint tx send`");
+            } else {
+                println!("still needs      {missing:?}");
+            }
+            Ok(())
+        }
+        TxCommand::Send { file } => {
+            let tx = offline::read(&file)?;
+            let missing = offline::missing_signers(&tx);
+            if !missing.is_empty() {
+                bail!("{} still needs signatures from {missing:?}", file.display());
+            }
+            ctx.send(&tx)
+        }
+    }
+}
+
+fn config(ctx: &Context, command: ConfigCommand) -> Result<()> {
+    match command {
+        ConfigCommand::Show => {
+            display::print_config(&pda::config(), &ctx.config()?);
+            Ok(())
+        }
+        ConfigCommand::Init {
+            admin,
+            allowed_mints,
+        } => {
+            let ix = instructions::initialize_config(
+                &ctx.fee_payer()?,
+                &ctx.signer()?,
+                &admin,
+                allowed_mints,
+            );
+            println!("config           {}", pda::config());
+            ctx.submit(ix)
+        }
+        ConfigCommand::SetMints { allowed_mints } => ctx.submit(instructions::set_allowed_mints(
+            &ctx.signer()?,
+            allowed_mints,
+        )),
+        ConfigCommand::SetAdmin { new_admin } => {
+            ctx.submit(instructions::set_config_admin(&ctx.signer()?, &new_admin))
+        }
+        ConfigCommand::SetFee {
+            treasury,
+            join_bps,
+            payout_bps,
+            reject_fee,
+        } => ctx.submit(instructions::set_protocol_fee(
+            &ctx.signer()?,
+            &treasury,
+            join_bps,
+            payout_bps,
+            parse_amount(&reject_fee, fees::REFERENCE_DECIMALS).map_err(|e| anyhow!(e))?,
+        )),
+    }
+}
+
+// One wallet per line; blank lines and `#` comments are skipped. Joiners build
+// their proof from the same file the owner built the root from.
+fn read_allowlist(path: &std::path::Path) -> Result<Vec<Pubkey>> {
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("cannot read {}", path.display()))?;
+    let wallets = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            line.parse()
+                .with_context(|| format!("{}: {line:?} is not a wallet", path.display()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if wallets.is_empty() {
+        bail!("{} lists no wallets", path.display());
+    }
+    Ok(wallets)
+}
+
+// Resolved proposals are closed, so only the open one (if any) can be read.
+fn open_proposal(
+    ctx: &Context,
+    fund: &Pubkey,
+    nonce: u64,
+) -> Result<squad_mint_client::Transaction> {
+    let address = pda::proposal(fund, nonce);
+    let data = ctx
+        .rpc
+        .account_data(&address)?
+        .ok_or_else(|| anyhow!("no open proposal at nonce {nonce} ({address})"))?;
+    Ok(state::proposal(&data)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn global_flags_work_after_the_subcommand() {
+        let cli = Cli::try_parse_from([
+            "squadmint",
+            "proposal",
+            "vote",
+            &Pubkey::new_unique().to_string(),
+            "yes",
+            "--dry-run",
+            "--url",
+            "http://localhost:8899",
+        ])
+        .unwrap();
+        assert!(cli.global.dry_run);
+        assert_eq!(cli.global.url, "http://localhost:8899");
+        assert_eq!(cli.global.mint, None);
+    }
+
+    #[test]
+    fn a_join_request_takes_one_kind_of_invite_with_all_its_parts() {
+        let fund = Pubkey::new_unique().to_string();
+        let request = |extra: &[&str]| {
+            let args = ["squadmint", "join", "request", fund.as_str()];
+            Cli::try_parse_from(args.iter().chain(extra))
+        };
+        assert!(request(&["--invite-signature", "sig", "--invite-expires", "1"]).is_ok());
+        assert!(request(&["--allowlist", "wallets.txt"]).is_ok());
+        assert!(request(&["--invite-signature", "sig"]).is_err());
+        assert!(request(&[
+            "--invite-signature",
+            "sig",
+            "--invite-expires",
+            "1",
+            "--allowlist",
+            "wallets.txt"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn unsigned_output_requires_a_durable_nonce() {
+        let fund = Pubkey::new_unique().to_string();
+        let signer = Pubkey::new_unique().to_string();
+        let vote = ["squadmint", "proposal", "vote", &fund, "yes"];
+        let offline = [&vote[..], &["--unsigned", "vote.tx", "--signer", &signer]].concat();
+        assert!(Cli::try_parse_from(&offline).is_err());
+
+        let nonce = Pubkey::new_unique().to_string();
+        let cli = Cli::try_parse_from([&offline[..], &["--nonce", &nonce]].concat()).unwrap();
+        assert_eq!(cli.global.signer, Some(signer.parse().unwrap()));
+        assert_eq!(cli.global.unsigned, Some(PathBuf::from("vote.tx")));
+
+        // --signer only means something when the keypair isn't used to sign.
+        assert!(Cli::try_parse_from([&vote[..], &["--signer", &signer]].concat()).is_err());
+    }
+}
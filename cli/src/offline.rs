@@ -0,0 +1,144 @@
+// Air-gapped signing. The online machine builds a transaction against a
+// durable nonce, whose stored value stands in for the recent blockhash so the
+// transaction doesn't expire while it travels, and writes it unsigned. The
+// offline machine reviews and signs it with no network access (`tx inspect`,
+// `tx sign`); the online machine sends it (`tx send`).
+//
+// The file is the wire transaction (bincode) as one line of base64: the bytes
+// `sendTransaction` takes, so nothing is re-encoded between the two machines.
+
+use std::{fs, path::Path};
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+    },
+};
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use solana_nonce::{state::State, versions::Versions};
+use solana_sanitize::Sanitize;
+use solana_transaction::Transaction;
+
+pub fn read(path: &Path) -> Result<Transaction> {
+    let text = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let bytes = BASE64_STANDARD
+        .decode(text.trim())
+        .with_context(|| format!("{} is not base64", path.display()))?;
+    let tx: Transaction = bincode::deserialize(&bytes)
+        .with_context(|| format!("{} is not a transaction", path.display()))?;
+    // Malformed account indexes would otherwise panic while decoding.
+    tx.sanitize()
+        .map_err(|e| anyhow!("{} is not a valid transaction: {e}", path.display()))?;
+    Ok(tx)
+}
+
+pub fn write(path: &Path, tx: &Transaction) -> Result<()> {
+    let encoded = BASE64_STANDARD.encode(bincode::serialize(tx)?);
+    fs::write(path, encoded + "\n").with_context(|| format!("writing {}", path.display()))
+}
+
+// The authority and current value of an initialized nonce account.
+pub fn nonce_state(data: &[u8]) -> Result<(Pubkey, Hash)> {
+    let versions: Versions = bincode::deserialize(data).context("not a nonce account")?;
+    match versions.state() {
+        State::Initialized(nonce) => Ok((nonce.authority, nonce.blockhash())),
+        State::Uninitialized => bail!("nonce account is not initialized"),
+    }
+}
+
+// Required signers whose signature is still empty, in message order.
+pub fn missing_signers(tx: &Transaction) -> Vec<Pubkey> {
+    tx.signatures
+        .iter()
+        .zip(&tx.message.account_keys)
+        .filter(|(signature, _)| **signature == Default::default())
+        .map(|(_, key)| *key)
+        .collect()
+}
+
+// The message's compiled instructions back as Instructions, for display.
+// Indexes are in bounds for anything that passed `read` or was built locally.
+pub fn instructions(tx: &Transaction) -> Vec<Instruction> {
+    let message = &tx.message;
+    message
+        .instructions
+        .iter()
+        .map(|ix| Instruction {
+            program_id: message.account_keys[ix.program_id_index as usize],
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|&index| {
+                    let index = index as usize;
+                    AccountMeta {
+                        pubkey: message.account_keys[index],
+                        is_signer: message.is_signer(index),
+                        is_writable: message.is_maybe_writable(index, None),
+                    }
+                })
+                .collect(),
+            data: ix.data.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_keypair::Keypair;
+    use solana_nonce::state::{Data, DurableNonce};
+    use solana_signer::Signer;
+    use solana_system_interface::instruction::{advance_nonce_account, transfer};
+
+    #[test]
+    fn reads_the_nonce_value_and_authority() {
+        let authority = Pubkey::new_unique();
+        let durable = DurableNonce::from_blockhash(&Hash::new_unique());
+        let account = Versions::new(State::Initialized(Data::new(authority, durable, 5000)));
+        let data = bincode::serialize(&account).unwrap();
+        assert_eq!(nonce_state(&data).unwrap(), (authority, *durable.as_hash()));
+
+        let data = bincode::serialize(&Versions::new(State::Uninitialized)).unwrap();
+        assert!(nonce_state(&data).is_err());
+        assert!(nonce_state(&[1, 2, 3]).is_err());
+    }
+
+    // Fee payer signs online, the member offline, in either order; the file
+    // carries the partial signatures across.
+    #[test]
+    fn partial_signatures_survive_the_file_round_trip() {
+        let (fee_payer, member) = (Keypair::new(), Keypair::new());
+        let nonce = Pubkey::new_unique();
+        let value = Hash::new_unique();
+        let mut tx = Transaction::new_with_payer(
+            &[
+                advance_nonce_account(&nonce, &fee_payer.pubkey()),
+                transfer(&member.pubkey(), &Pubkey::new_unique(), 1),
+            ],
+            Some(&fee_payer.pubkey()),
+        );
+        tx.message.recent_blockhash = value;
+        assert_eq!(
+            missing_signers(&tx),
+            vec![fee_payer.pubkey(), member.pubkey()]
+        );
+
+        let path = std::env::temp_dir().join(format!("squadmint-{}.tx", Pubkey::new_unique()));
+        write(&path, &tx).unwrap();
+        let mut tx = read(&path).unwrap();
+        tx.try_partial_sign(&[&member], value).unwrap();
+        write(&path, &tx).unwrap();
+        let mut tx = read(&path).unwrap();
+        assert_eq!(missing_signers(&tx), vec![fee_payer.pubkey()]);
+        tx.try_partial_sign(&[&fee_payer], value).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(missing_signers(&tx).is_empty());
+        assert_eq!(tx.message.recent_blockhash, value);
+        assert_eq!(instructions(&tx)[1].accounts[0].pubkey, member.pubkey());
+        assert!(instructions(&tx)[1].accounts[0].is_signer);
+    }
+}
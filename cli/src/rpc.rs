@@ -0,0 +1,256 @@
+// Thin JSON-RPC client: only the handful of methods the CLI needs, over
+// blocking HTTP. Everything is read at `confirmed` commitment, the same level
+// transactions are confirmed at, so a command sees its own previous writes.
+
+use std::{thread, time::Duration};
+
+use anchor_lang::{prelude::Pubkey, solana_program::hash::Hash};
+use anyhow::{anyhow, bail, Context, Result};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde_json::{json, Value};
+use solana_transaction::Transaction;
+
+const COMMITMENT: &str = "confirmed";
+// A blockhash is valid for ~60-90s; stop polling once it can no longer land.
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(90);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub struct RpcClient {
+    url: String,
+    agent: ureq::Agent,
+}
+
+pub struct Simulation {
+    pub err: Option<Value>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response: Value = self
+            .agent
+            .post(&self.url)
+            .send_json(request)
+            .with_context(|| format!("{method} request to {}", self.url))?
+            .into_json()
+            .with_context(|| format!("{method}: response is not JSON"))?;
+        into_result(method, response)
+    }
+
+    // None if the account doesn't exist.
+    pub fn account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        Ok(self.account(address)?.map(|(_, data)| data))
+    }
+
+    // (owner program, data); None if the account doesn't exist.
+    pub fn account(&self, address: &Pubkey) -> Result<Option<(Pubkey, Vec<u8>)>> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": COMMITMENT }]),
+        )?;
+        match &result["value"] {
+            Value::Null => Ok(None),
+            account => {
+                let owner = account["owner"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("getAccountInfo: missing owner"))?
+                    .parse()?;
+                Ok(Some((owner, decode_data(&account["data"])?)))
+            }
+        }
+    }
+
+    // `filters` is passed through as getProgramAccounts' `filters` array.
+    pub fn program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: Value,
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        let result = self.call(
+            "getProgramAccounts",
+            json!([program_id.to_string(), {
+                "encoding": "base64",
+                "commitment": COMMITMENT,
+                "filters": filters,
+            }]),
+        )?;
+        let accounts = result
+            .as_array()
+            .ok_or_else(|| anyhow!("getProgramAccounts: expected an array"))?;
+        accounts
+            .iter()
+            .map(|entry| {
+                let pubkey = entry["pubkey"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("getProgramAccounts: missing pubkey"))?
+                    .parse()?;
+                Ok((pubkey, decode_data(&entry["account"]["data"])?))
+            })
+            .collect()
+    }
+
+    // Base units, as a u64 (the RPC returns it as a string).
+    pub fn token_balance(&self, token_account: &Pubkey) -> Result<u64> {
+        let result = self.call(
+            "getTokenAccountBalance",
+            json!([token_account.to_string(), { "commitment": COMMITMENT }]),
+        )?;
+        result["value"]["amount"]
+            .as_str()
+            .ok_or_else(|| anyhow!("getTokenAccountBalance: missing amount"))?
+            .parse()
+            .context("getTokenAccountBalance: amount is not a u64")
+    }
+
+    pub fn latest_blockhash(&self) -> Result<Hash> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": COMMITMENT }]))?;
+        result["value"]["blockhash"]
+            .as_str()
+            .ok_or_else(|| anyhow!("getLatestBlockhash: missing blockhash"))?
+            .parse()
+            .map_err(|e| anyhow!("getLatestBlockhash: {e:?}"))
+    }
+
+    pub fn simulate(&self, tx: &Transaction) -> Result<Simulation> {
+        let result = self.call(
+            "simulateTransaction",
+            json!([encode_transaction(tx)?, {
+                "encoding": "base64",
+                "commitment": COMMITMENT,
+                "sigVerify": false,
+            }]),
+        )?;
+        let value = &result["value"];
+        Ok(Simulation {
+            err: Some(value["err"].clone()).filter(|err| !err.is_null()),
+            logs: value["logs"]
+                .as_array()
+                .map(|logs| {
+                    logs.iter()
+                        .filter_map(|l| l.as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            units_consumed: value["unitsConsumed"].as_u64(),
+        })
+    }
+
+    // Sends with preflight (so program errors come back with their logs before
+    // anything lands) and polls until the signature is confirmed.
+    pub fn send_and_confirm(&self, tx: &Transaction) -> Result<String> {
+        let signature = self
+            .call(
+                "sendTransaction",
+                json!([encode_transaction(tx)?, {
+                    "encoding": "base64",
+                    "preflightCommitment": COMMITMENT,
+                }]),
+            )?
+            .as_str()
+            .ok_or_else(|| anyhow!("sendTransaction: expected a signature"))?
+            .to_string();
+
+        let mut waited = Duration::ZERO;
+        while waited < CONFIRM_TIMEOUT {
+            let result = self.call("getSignatureStatuses", json!([[signature]]))?;
+            let status = &result["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    bail!("transaction {signature} failed: {}", status["err"]);
+                }
+                if matches!(
+                    status["confirmationStatus"].as_str(),
+                    Some("confirmed" | "finalized")
+                ) {
+                    return Ok(signature);
+                }
+            }
+            thread::sleep(POLL_INTERVAL);
+            waited += POLL_INTERVAL;
+        }
+        bail!("transaction {signature} not confirmed after {CONFIRM_TIMEOUT:?}")
+    }
+}
+
+fn encode_transaction(tx: &Transaction) -> Result<String> {
+    Ok(BASE64_STANDARD.encode(bincode::serialize(tx)?))
+}
+
+// `data` is `[<base64>, "base64"]` for every encoding="base64" request.
+fn decode_data(data: &Value) -> Result<Vec<u8>> {
+    let encoded = data[0]
+        .as_str()
+        .ok_or_else(|| anyhow!("account data is not base64-encoded"))?;
+    Ok(BASE64_STANDARD.decode(encoded)?)
+}
+
+// JSON-RPC errors become anyhow errors. Preflight failures carry the
+// simulation logs in `error.data.logs`; include them, they name the program
+// error.
+fn into_result(method: &str, mut response: Value) -> Result<Value> {
+    let error = response["error"].take();
+    if error.is_null() {
+        return Ok(response["result"].take());
+    }
+    let mut message = format!(
+        "{method}: {}",
+        error["message"].as_str().unwrap_or("unknown RPC error")
+    );
+    if let Some(logs) = error["data"]["logs"].as_array() {
+        for log in logs.iter().filter_map(Value::as_str) {
+            message.push_str("\n  ");
+            message.push_str(log);
+        }
+    }
+    Err(anyhow!(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preflight_errors_include_the_program_logs() {
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "error": {
+                "code": -32002,
+                "message": "Transaction simulation failed: Error processing Instruction 0",
+                "data": { "logs": [
+                    "Program BW1d... invoke [1]",
+                    "Program log: AnchorError occurred. Error Code: MemberNotPartOfFund.",
+                ] }
+            }
+        });
+        let err = into_result("sendTransaction", response)
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("sendTransaction: Transaction simulation failed"));
+        assert!(
+            err.contains("\n  Program log: AnchorError occurred. Error Code: MemberNotPartOfFund.")
+        );
+    }
+
+    #[test]
+    fn results_and_account_data_decode() {
+        let response = json!({ "jsonrpc": "2.0", "id": 1, "result": { "value": null } });
+        assert!(into_result("getAccountInfo", response).unwrap()["value"].is_null());
+        assert_eq!(
+            decode_data(&json!(["AQID", "base64"])).unwrap(),
+            vec![1, 2, 3]
+        );
+        assert!(decode_data(&json!("AQID")).is_err());
+    }
+}
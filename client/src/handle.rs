@@ -0,0 +1,98 @@
+// The fund handle is stored, and seeds the fund PDA, as exactly
+// SQUAD_MINT_MAX_HANDLE_SIZE bytes: the UTF-8 string left-aligned and
+// right-padded with NUL. `initialize` takes those bytes as-is, so the padding
+// here must match what every other client does (see encodeHandle in
+// tests/helper_function.ts) or the fund lands at a different address.
+
+use std::fmt;
+
+use squad_mint_multi_sig::SquadMintFund;
+
+pub const HANDLE_SIZE: usize = SquadMintFund::SQUAD_MINT_MAX_HANDLE_SIZE;
+
+pub type Handle = [u8; HANDLE_SIZE];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HandleError {
+    Empty,
+    // Length in bytes, not chars: multi-byte UTF-8 counts against the 15.
+    TooLong(usize),
+    // A NUL would be indistinguishable from padding once stored.
+    ContainsNul,
+}
+
+impl fmt::Display for HandleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandleError::Empty => write!(f, "handle must not be empty"),
+            HandleError::TooLong(len) => {
+                write!(f, "handle must be at most {HANDLE_SIZE} bytes, got {len}")
+            }
+            HandleError::ContainsNul => write!(f, "handle must not contain NUL bytes"),
+        }
+    }
+}
+
+impl std::error::Error for HandleError {}
+
+pub fn encode_handle(handle: &str) -> Result<Handle, HandleError> {
+    let bytes = handle.as_bytes();
+    if bytes.is_empty() {
+        return Err(HandleError::Empty);
+    }
+    if bytes.len() > HANDLE_SIZE {
+        return Err(HandleError::TooLong(bytes.len()));
+    }
+    if bytes.contains(&0) {
+        return Err(HandleError::ContainsNul);
+    }
+    let mut out = [0u8; HANDLE_SIZE];
+    out[..bytes.len()].copy_from_slice(bytes);
+    Ok(out)
+}
+
+// Inverse of encode_handle for a fund's stored `account_handle`. Fails only if
+// the bytes before the padding aren't UTF-8 (the program itself never checks).
+pub fn decode_handle(handle: &Handle) -> Result<&str, std::str::Utf8Error> {
+    let len = handle
+        .iter()
+        .rposition(|&b| b != 0)
+        .map_or(0, |last| last + 1);
+    std::str::from_utf8(&handle[..len])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pads_with_trailing_nul() {
+        assert_eq!(
+            &encode_handle("squad").unwrap(),
+            b"squad\0\0\0\0\0\0\0\0\0\0"
+        );
+        assert_eq!(
+            &encode_handle("exactly15bytes!").unwrap(),
+            b"exactly15bytes!"
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        for handle in ["a", "squad_mint", "exactly15bytes!", "fünf€"] {
+            assert_eq!(decode_handle(&encode_handle(handle).unwrap()), Ok(handle));
+        }
+    }
+
+    #[test]
+    fn rejects_what_the_program_or_padding_cannot_represent() {
+        assert_eq!(encode_handle(""), Err(HandleError::Empty));
+        assert_eq!(
+            encode_handle("sixteen_bytes_xx"),
+            Err(HandleError::TooLong(16))
+        );
+        // 8 chars, 16 bytes.
+        assert_eq!(encode_handle("€€€€€ab"), Err(HandleError::TooLong(17)));
+        assert_eq!(encode_handle("a\0b"), Err(HandleError::ContainsNul));
+    }
+}
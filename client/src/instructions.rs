@@ -0,0 +1,1410 @@
+// One builder per program instruction. Arguments are the keys the caller has to
+// choose (signers, the fund, the mint, who gets refunds); everything derivable
+// (PDAs, ATAs, program ids) is derived here. Builders only assemble the
+// instruction: signing, fee payer and blockhash are the caller's.
+//
+// Accounts whose rent is refunded on close (join requests, proposals,
+// recovery requests, memberships, handle claims, dues schedules, rotations,
+// streams) take `rent_payer`: the program only accepts the key that paid,
+// which is stored on the account (`rent_payer` field, see [`crate::state`]).
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::instruction::{AccountMeta, Instruction},
+    system_program, AnchorDeserialize, Discriminator, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{associated_token, token_2022};
+use solana_sdk_ids::{ed25519_program, sysvar};
+use squad_mint_multi_sig::{accounts, instruction};
+
+use crate::{
+    decode_handle, invite, pda, Distribution, DuesSchedule, Handle, Invite, ProposalKind,
+    StreamTerms, TokenMint, Transaction, PROGRAM_ID,
+};
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+// ---- Fund --------------------------------------------------------------------
+
+// Creates the fund at pda::fund(handle, owner) and its vault. An empty
+// `guardians` with threshold 0 opts out of social recovery.
+pub fn initialize(
+    fee_payer: &Pubkey,
+    owner: &Pubkey,
+    handle: &Handle,
+    mint: &TokenMint,
+    join_amount: u64,
+    guardians: Vec<Pubkey>,
+    guardian_threshold: u8,
+) -> Instruction {
+    let multisig = pda::fund(handle, owner);
+    build(
+        accounts::Initialize {
+            fee_payer: *fee_payer,
+            multisig_owner: *owner,
+            multisig,
+            config: pda::config(),
+            mint: mint.address,
+            multisig_ata: pda::vault(&multisig),
+            membership: pda::membership(&multisig, owner),
+            token_program: mint.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::Initialize {
+            account_handle: *handle,
+            join_amount,
+            guardians,
+            guardian_threshold,
+        },
+    )
+}
+
+// ---- Membership ----------------------------------------------------------------
+
+// Escrows `join_amount` from the joiner's canonical ATA. Must be within the
+// fund's join range, now and when add_member accepts it. `invite` is needed when the
+// fund requires one; a signed invite also needs `signed_invite` right before
+// this instruction.
+pub fn initiate_join_request(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    joiner: &Pubkey,
+    mint: &TokenMint,
+    join_amount: u64,
+    invite: Option<Invite>,
+) -> Instruction {
+    let join_custodial_account = pda::join_request(fund, joiner);
+    build(
+        accounts::CreateJoinRequestProposal {
+            proposing_joiner: *joiner,
+            multisig: *fund,
+            fee_payer: *fee_payer,
+            config: pda::config(),
+            mint: mint.address,
+            multisig_ata: pda::vault(fund),
+            join_custodial_account,
+            join_custodial_account_ata: pda::join_escrow(&join_custodial_account),
+            proposing_joiner_ata: pda::member_ata(joiner, mint),
+            instructions_sysvar: sysvar::instructions::ID,
+            token_program: mint.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitiateJoinRequest {
+            join_amount,
+            invite,
+        },
+    )
+}
+
+// Instant join for a fund with open admission: pays `join_amount` (less the
+// join fee) from the joiner's canonical ATA straight into the vault and makes
+// them a member. `invite` as for initiate_join_request.
+pub fn join_open_fund(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    joiner: &Pubkey,
+    mint: &TokenMint,
+    join_amount: u64,
+    treasury: &Pubkey,
+    invite: Option<Invite>,
+) -> Instruction {
+    build(
+        accounts::JoinOpenFund {
+            multisig: *fund,
+            joiner: *joiner,
+            fee_payer: *fee_payer,
+            mint: mint.address,
+            joiner_ata: pda::member_ata(joiner, mint),
+            multisig_ata: pda::vault(fund),
+            membership: pda::membership(fund, joiner),
+            config: pda::config(),
+            treasury: *treasury,
+            treasury_ata: mint.ata(treasury),
+            instructions_sysvar: sysvar::instructions::ID,
+            token_program: mint.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::JoinOpenFund {
+            join_amount,
+            invite,
+        },
+    )
+}
+
+// The Ed25519 program instruction carrying an owner-signed invite; goes right
+// before initiate_join_request with `Invite::Signed { expires_at }`. The owner
+// signs `invite::message(fund, invitee, expires_at)`, possibly offline.
+pub fn signed_invite(
+    owner: &Pubkey,
+    signature: &[u8; 64],
+    fund: &Pubkey,
+    invitee: &Pubkey,
+    expires_at: i64,
+) -> Instruction {
+    Instruction {
+        program_id: ed25519_program::ID,
+        accounts: vec![],
+        data: invite::ed25519_instruction_data(
+            owner,
+            signature,
+            &invite::message(fund, invitee, expires_at),
+        ),
+    }
+}
+
+// Owner only. `require_invite` gates join requests on an invite; `invite_root`
+// (see invite::root) additionally accepts allowlist proofs. The fund must be
+// at the current layout (migrate_fund).
+pub fn set_invite_policy(
+    fund: &Pubkey,
+    owner: &Pubkey,
+    require_invite: bool,
+    invite_root: Option<[u8; 32]>,
+) -> Instruction {
+    build(
+        accounts::SetAdmissionPolicy {
+            multisig: *fund,
+            multisig_owner: *owner,
+        },
+        instruction::SetInvitePolicy {
+            require_invite,
+            invite_root,
+        },
+    )
+}
+
+// Owner only. Joiners then contribute any amount from `min_join_amount` to
+// `max_join_amount`; equal bounds are a fixed price. Same layout requirement
+// as set_invite_policy. `mint` is the fund's, whose decimals the program's
+// minimum is scaled to.
+pub fn set_join_range(
+    fund: &Pubkey,
+    owner: &Pubkey,
+    mint: &TokenMint,
+    min_join_amount: u64,
+    max_join_amount: u64,
+) -> Instruction {
+    build(
+        accounts::SetJoinRange {
+            multisig: *fund,
+            multisig_owner: *owner,
+            mint: mint.address,
+            multisig_ata: pda::vault(fund),
+        },
+        instruction::SetJoinRange {
+            min_join_amount,
+            max_join_amount,
+        },
+    )
+}
+
+// Owner only. An open fund admits joiners through join_open_fund and refuses
+// escrowed join requests. Same layout requirement as set_invite_policy.
+pub fn set_open_admission(fund: &Pubkey, owner: &Pubkey, open: bool) -> Instruction {
+    build(
+        accounts::SetAdmissionPolicy {
+            multisig: *fund,
+            multisig_owner: *owner,
+        },
+        instruction::SetOpenAdmission { open },
+    )
+}
+
+// `owner` is the fund's effective owner (the recovered owner after a recovery).
+pub fn add_member(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    owner: &Pubkey,
+    joiner: &Pubkey,
+    rent_payer: &Pubkey,
+    mint: &TokenMint,
+    treasury: &Pubkey,
+) -> Instruction {
+    let join_custodial_account = pda::join_request(fund, joiner);
+    build(
+        accounts::AddMember {
+            multisig: *fund,
+            fee_payer: *fee_payer,
+            multisig_owner: *owner,
+            mint: mint.address,
+            proposing_joiner: *joiner,
+            rent_payer: *rent_payer,
+            join_custodial_account,
+            join_custodial_account_ata: pda::join_escrow(&join_custodial_account),
+            multisig_ata: pda::vault(fund),
+            membership: pda::membership(fund, joiner),
+            config: pda::config(),
+            treasury: *treasury,
+            treasury_ata: mint.ata(treasury),
+            token_program: mint.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::AddMember {
+            new_member: *joiner,
+        },
+    )
+}
+
+// Refunds the escrow to the joiner's canonical ATA (created if missing).
+pub fn reject_member(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    owner: &Pubkey,
+    joiner: &Pubkey,
+    rent_payer: &Pubkey,
+    mint: &TokenMint,
+    treasury: &Pubkey,
+) -> Instruction {
+    let join_custodial_account = pda::join_request(fund, joiner);
+    build(
+        accounts::RejectMember {
+            multisig: *fund,
+            fee_payer: *fee_payer,
+            multisig_owner: *owner,
+            mint: mint.address,
+            proposing_joiner: *joiner,
+            proposing_joiner_ata: pda::member_ata(joiner, mint),
+            rent_payer: *rent_payer,
+            join_custodial_account,
+            join_custodial_account_ata: pda::join_escrow(&join_custodial_account),
+            multisig_ata: pda::vault(fund),
+            config: pda::config(),
+            treasury: *treasury,
+            treasury_ata: mint.ata(treasury),
+            token_program: mint.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::RejectMember {
+            new_member: *joiner,
+        },
+    )
+}
+
+// Permissionless refund of a join request the fund can no longer accept.
+pub fn cancel_stale_join_request(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    joiner: &Pubkey,
+    rent_payer: &Pubkey,
+    mint: &TokenMint,
+) -> Instruction {
+    let join_custodial_account = pda::join_request(fund, joiner);
+    build(
+        accounts::CancelStaleJoinRequest {
+            multisig: *fund,
+            fee_payer: *fee_payer,
+            mint: mint.address,
+            proposing_joiner: *joiner,
+            proposing_joiner_ata: pda::member_ata(joiner, mint),
+            rent_payer: *rent_payer,
+            join_custodial_account,
+            join_custodial_account_ata: pda::join_escrow(&join_custodial_account),
+            token_program: mint.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CancelStaleJoinRequest,
+    )
+}
+
+// ---- Proposals -----------------------------------------------------------------
+
+// `nonce` must be the fund's current master_nonce. The proposer's YES is
+// recorded on creation.
+pub fn create_proposal(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    nonce: u64,
+    proposer: &Pubkey,
+    mint: &TokenMint,
+    amount: u64,
+    to: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::CreateProposal {
+            transaction: pda::proposal(fund, nonce),
+            multisig: *fund,
+            fee_payer: *fee_payer,
+            proposer: *proposer,
+            mint: mint.address,
+            multisig_ata: pda::vault(fund),
+            token_program: mint.token_program,
+            system_program: system_program::ID,
+        },
+        instruction::CreateProposal {
+            amount,
+            proposed_to_account: *to,
+        },
+    )
+}
+
+// Casts `submitter`'s vote on the fund's open proposal, executing or closing
+// it if the vote decides it. `proposal` is the decoded proposal account (see
+// state::proposal); its nonce, recipient and rent payer fix the accounts.
+pub fn submit_and_execute(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    submitter: &Pubkey,
+    proposal: &Transaction,
+    mint: &TokenMint,
+    treasury: &Pubkey,
+    vote: bool,
+) -> Instruction {
+    let recipient = proposal.message_data.proposed_to_account;
+    build(
+        accounts::SubmitAndExecute {
+            transaction: pda::proposal(fund, proposal.message_data.nonce),
+            rent_payer: proposal.rent_payer,
+            multisig: *fund,
+            fee_payer: *fee_payer,
+            submitter: *submitter,
+            proposed_to_owner: recipient,
+            multisig_ata: pda::vault(fund),
+            proposed_to_ata: pda::member_ata(&recipient, mint),
+            mint: mint.address,
+            config: pda::config(),
+            treasury: *treasury,
+            treasury_ata: mint.ata(treasury),
+            token_program: mint.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            // A CancelStream proposal's recipient is the stream itself.
+            stream: (proposal.kind == ProposalKind::CancelStream).then_some(recipient),
+        },
+        instruction::SubmitAndExecute { vote },
+    )
+}
+
+// ---- Recovery ------------------------------------------------------------------
+
+pub fn set_guardians(
+    fund: &Pubkey,
+    owner: &Pubkey,
+    guardians: Vec<Pubkey>,
+    guardian_threshold: u8,
+) -> Instruction {
+    build(
+        accounts::SetGuardians {
+            multisig: *fund,
+            multisig_owner: *owner,
+        },
+        instruction::SetGuardians {
+            guardians,
+            guardian_threshold,
+        },
+    )
+}
+
+pub fn initiate_recovery(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    guardian: &Pubkey,
+    new_owner: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::InitiateRecovery {
+            multisig: *fund,
+            fee_payer: *fee_payer,
+            guardian: *guardian,
+            recovery_request: pda::recovery(fund),
+            system_program: system_program::ID,
+        },
+        instruction::InitiateRecovery {
+            new_owner: *new_owner,
+        },
+    )
+}
+
+pub fn approve_recovery(fund: &Pubkey, guardian: &Pubkey) -> Instruction {
+    build(
+        accounts::ApproveRecovery {
+            multisig: *fund,
+            guardian: *guardian,
+            recovery_request: pda::recovery(fund),
+        },
+        instruction::ApproveRecovery,
+    )
+}
+
+// Permissionless once the quorum and delay are met.
+pub fn execute_recovery(fund: &Pubkey, rent_payer: &Pubkey) -> Instruction {
+    build(
+        accounts::ExecuteRecovery {
+            multisig: *fund,
+            rent_payer: *rent_payer,
+            recovery_request: pda::recovery(fund),
+        },
+        instruction::ExecuteRecovery,
+    )
+}
+
+pub fn cancel_recovery(fund: &Pubkey, owner: &Pubkey, rent_payer: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelRecovery {
+            multisig: *fund,
+            multisig_owner: *owner,
+            rent_payer: *rent_payer,
+            recovery_request: pda::recovery(fund),
+        },
+        instruction::CancelRecovery,
+    )
+}
+
+// ---- Dissolution ---------------------------------------------------------------
+
+// Opens a Dissolve proposal at `nonce` (the fund's current master_nonce); it is
+// voted on with submit_and_execute like any other proposal.
+pub fn propose_dissolution(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    nonce: u64,
+    proposer: &Pubkey,
+    distribution: Distribution,
+    rent_recipient: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::ProposeDissolution {
+            transaction: pda::proposal(fund, nonce),
+            multisig: *fund,
+            fee_payer: *fee_payer,
+            proposer: *proposer,
+            system_program: system_program::ID,
+        },
+        instruction::ProposeDissolution {
+            distribution,
+            rent_recipient: *rent_recipient,
+        },
+    )
+}
+
+// Permissionless crank after a Dissolve proposal passes. `members` is the
+// fund's member list in stored order; their canonical ATAs are passed as the
+// remaining accounts the program pays out to. `dues` is the fund's decoded
+// dues schedule, if it has one (see state::dues): it is closed with the fund,
+// its rent going back to whoever paid it.
+pub fn dissolve_fund(
+    fund: &Pubkey,
+    rent_recipient: &Pubkey,
+    dues: Option<&DuesSchedule>,
+    mint: &TokenMint,
+    members: &[Pubkey],
+) -> Instruction {
+    let mut ix = build(
+        accounts::DissolveFund {
+            multisig: *fund,
+            rent_recipient: *rent_recipient,
+            mint: mint.address,
+            multisig_ata: pda::vault(fund),
+            dues: pda::dues(fund),
+            // Unused without a schedule; any writable account does.
+            dues_rent_payer: dues.map_or(*rent_recipient, |schedule| schedule.rent_payer),
+            token_program: mint.token_program,
+        },
+        instruction::DissolveFund,
+    );
+    ix.accounts.extend(
+        members
+            .iter()
+            .map(|member| AccountMeta::new(pda::member_ata(member, mint), false)),
+    );
+    ix
+}
+
+// Permissionless: grows a fund written by an older program version to the
+// current layout, `payer` covering the extra rent. A no-op on current funds.
+pub fn migrate_fund(payer: &Pubkey, fund: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateFund {
+            multisig: *fund,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateFund,
+    )
+}
+
+// Permissionless, like migrate_fund: bring a proposal or a join request opened
+// by an older program version to the current layout. Its rent is then
+// refunded to the proposer or the joiner, whom the old layout names.
+pub fn migrate_proposal(payer: &Pubkey, fund: &Pubkey, nonce: u64) -> Instruction {
+    build(
+        accounts::MigrateProposal {
+            transaction: pda::proposal(fund, nonce),
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateProposal,
+    )
+}
+
+pub fn migrate_join_request(payer: &Pubkey, fund: &Pubkey, joiner: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateJoinRequest {
+            join_custodial_account: pda::join_request(fund, joiner),
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateJoinRequest,
+    )
+}
+
+// Creates `member`'s missing Membership for a fund they belong to (one
+// admitted before the index existed, or the new key after a recovery).
+pub fn register_membership(fee_payer: &Pubkey, fund: &Pubkey, member: &Pubkey) -> Instruction {
+    build(
+        accounts::RegisterMembership {
+            multisig: *fund,
+            member: *member,
+            fee_payer: *fee_payer,
+            membership: pda::membership(fund, member),
+            system_program: system_program::ID,
+        },
+        instruction::RegisterMembership,
+    )
+}
+
+// Permissionless: closes the Membership of a wallet that is no longer in the
+// fund (or whose fund was dissolved), refunding `rent_payer`.
+pub fn close_membership(fund: &Pubkey, member: &Pubkey, rent_payer: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseMembership {
+            multisig: *fund,
+            member: *member,
+            rent_payer: *rent_payer,
+            membership: pda::membership(fund, member),
+        },
+        instruction::CloseMembership,
+    )
+}
+
+// ---- Dues ----------------------------------------------------------------------
+
+// Owner only. (Re)starts the schedule: `amount` per member every
+// `period_seconds`, the first period due at once.
+pub fn set_dues(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    owner: &Pubkey,
+    mint: &TokenMint,
+    amount: u64,
+    period_seconds: i64,
+) -> Instruction {
+    build(
+        accounts::SetDues {
+            multisig: *fund,
+            multisig_owner: *owner,
+            fee_payer: *fee_payer,
+            dues: pda::dues(fund),
+            mint: mint.address,
+            multisig_ata: pda::vault(fund),
+            system_program: system_program::ID,
+        },
+        instruction::SetDues {
+            amount,
+            period_seconds,
+        },
+    )
+}
+
+// Not a program instruction: `member` approves the fund's dues PDA to pull up
+// to `allowance` from their canonical ATA. Approving again replaces the
+// allowance; 0 revokes it in effect.
+pub fn approve_dues(
+    member: &Pubkey,
+    fund: &Pubkey,
+    mint: &TokenMint,
+    decimals: u8,
+    allowance: u64,
+) -> Instruction {
+    token_2022::spl_token_2022::instruction::approve_checked(
+        &mint.token_program,
+        &pda::member_ata(member, mint),
+        &mint.address,
+        &pda::dues(fund),
+        member,
+        &[],
+        allowance,
+        decimals,
+    )
+    .expect("a TokenMint's token program is SPL Token or Token-2022")
+}
+
+// Permissionless crank. `members` is the fund's member list in stored order;
+// their canonical ATAs are the remaining accounts dues are pulled from. A
+// running rotation's pots are credited along the way.
+pub fn collect_dues(fund: &Pubkey, mint: &TokenMint, members: &[Pubkey]) -> Instruction {
+    let mut ix = build(
+        accounts::CollectDues {
+            multisig: *fund,
+            dues: pda::dues(fund),
+            rosca: pda::rosca(fund),
+            mint: mint.address,
+            multisig_ata: pda::vault(fund),
+            token_program: mint.token_program,
+        },
+        instruction::CollectDues,
+    );
+    ix.accounts.extend(
+        members
+            .iter()
+            .map(|member| AccountMeta::new(pda::member_ata(member, mint), false)),
+    );
+    ix
+}
+
+// `authority` is the fund's effective owner, or anyone once the fund is
+// closed. Refunds `rent_payer`.
+pub fn close_dues(fund: &Pubkey, authority: &Pubkey, rent_payer: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseDues {
+            multisig: *fund,
+            authority: *authority,
+            rent_payer: *rent_payer,
+            dues: pda::dues(fund),
+        },
+        instruction::CloseDues,
+    )
+}
+
+// ---- Rotating savings ----------------------------------------------------------
+
+// Owner only. Starts a rotation over the current members from the current dues
+// period, in stored member order or, with `draw`, a shuffled one.
+pub fn start_rosca(fee_payer: &Pubkey, fund: &Pubkey, owner: &Pubkey, draw: bool) -> Instruction {
+    build(
+        accounts::StartRosca {
+            multisig: *fund,
+            multisig_owner: *owner,
+            fee_payer: *fee_payer,
+            dues: pda::dues(fund),
+            rosca: pda::rosca(fund),
+            system_program: system_program::ID,
+        },
+        instruction::StartRosca { draw },
+    )
+}
+
+// Permissionless crank. `recipient` is the member whose round it is: the
+// fund's members[rosca.next_recipient()].
+pub fn pay_rosca_round(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    recipient: &Pubkey,
+    mint: &TokenMint,
+    treasury: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::PayRoscaRound {
+            multisig: *fund,
+            dues: pda::dues(fund),
+            rosca: pda::rosca(fund),
+            fee_payer: *fee_payer,
+            recipient: *recipient,
+            recipient_ata: pda::member_ata(recipient, mint),
+            multisig_ata: pda::vault(fund),
+            mint: mint.address,
+            config: pda::config(),
+            treasury: *treasury,
+            treasury_ata: mint.ata(treasury),
+            token_program: mint.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::PayRoscaRound,
+    )
+}
+
+// `authority` is the fund's effective owner, or anyone once the fund is
+// closed. Refunds `rent_payer`.
+pub fn close_rosca(fund: &Pubkey, authority: &Pubkey, rent_payer: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseRosca {
+            multisig: *fund,
+            authority: *authority,
+            rent_payer: *rent_payer,
+            rosca: pda::rosca(fund),
+        },
+        instruction::CloseRosca,
+    )
+}
+
+// ---- Streams -------------------------------------------------------------------
+
+// Opens a stream and the proposal that funds it, both at the fund's current
+// master_nonce `nonce`. Vote on it with submit_and_execute like any payout.
+pub fn propose_stream(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    nonce: u64,
+    proposer: &Pubkey,
+    mint: &TokenMint,
+    terms: StreamTerms,
+) -> Instruction {
+    let stream = pda::stream(fund, nonce);
+    build(
+        accounts::ProposeStream {
+            transaction: pda::proposal(fund, nonce),
+            multisig: *fund,
+            fee_payer: *fee_payer,
+            proposer: *proposer,
+            stream,
+            escrow: mint.ata(&stream),
+            mint: mint.address,
+            multisig_ata: pda::vault(fund),
+            token_program: mint.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ProposeStream { terms },
+    )
+}
+
+// Opens a vote to cancel `stream`, as the proposal at the fund's current
+// master_nonce `nonce`.
+pub fn propose_stream_cancel(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    nonce: u64,
+    proposer: &Pubkey,
+    stream: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::ProposeStreamCancel {
+            transaction: pda::proposal(fund, nonce),
+            multisig: *fund,
+            fee_payer: *fee_payer,
+            proposer: *proposer,
+            stream: *stream,
+            system_program: system_program::ID,
+        },
+        instruction::ProposeStreamCancel,
+    )
+}
+
+// The recipient withdraws what has vested so far to their canonical ATA.
+pub fn claim_stream(stream: &Pubkey, recipient: &Pubkey, mint: &TokenMint) -> Instruction {
+    build(
+        accounts::ClaimStream {
+            stream: *stream,
+            recipient: *recipient,
+            escrow: mint.ata(stream),
+            recipient_ata: pda::member_ata(recipient, mint),
+            mint: mint.address,
+            token_program: mint.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ClaimStream,
+    )
+}
+
+// Permissionless once the stream has nothing left to pay. Refunds
+// `rent_payer` for the stream and its escrow.
+pub fn close_stream(
+    fund: &Pubkey,
+    nonce: u64,
+    rent_payer: &Pubkey,
+    mint: &TokenMint,
+) -> Instruction {
+    let stream = pda::stream(fund, nonce);
+    build(
+        accounts::CloseStream {
+            multisig: *fund,
+            rent_payer: *rent_payer,
+            stream,
+            escrow: mint.ata(&stream),
+            mint: mint.address,
+            token_program: mint.token_program,
+        },
+        instruction::CloseStream,
+    )
+}
+
+// ---- Handle registry -----------------------------------------------------------
+
+// Claims the fund's handle as its global name; fails if another fund holds it.
+// `owner` is the fund's effective owner. Send it alongside `initialize` to
+// claim the name at creation.
+pub fn claim_handle(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    owner: &Pubkey,
+    handle: &Handle,
+) -> Instruction {
+    build(
+        accounts::ClaimHandle {
+            multisig: *fund,
+            multisig_owner: *owner,
+            fee_payer: *fee_payer,
+            handle_registry: pda::handle_registry(handle),
+            system_program: system_program::ID,
+        },
+        instruction::ClaimHandle,
+    )
+}
+
+// Permissionless: frees the handle once `fund` (its claimant) is closed,
+// refunding `rent_payer`.
+pub fn release_handle(handle: &Handle, fund: &Pubkey, rent_payer: &Pubkey) -> Instruction {
+    build(
+        accounts::ReleaseHandle {
+            multisig: *fund,
+            rent_payer: *rent_payer,
+            handle_registry: pda::handle_registry(handle),
+        },
+        instruction::ReleaseHandle,
+    )
+}
+
+// ---- Program config ------------------------------------------------------------
+
+// One-time creation of the config, signed by the program's upgrade authority.
+pub fn initialize_config(
+    fee_payer: &Pubkey,
+    upgrade_authority: &Pubkey,
+    admin: &Pubkey,
+    allowed_mints: Vec<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::InitializeConfig {
+            fee_payer: *fee_payer,
+            upgrade_authority: *upgrade_authority,
+            config: pda::config(),
+            program: PROGRAM_ID,
+            program_data: pda::program_data(),
+            system_program: system_program::ID,
+        },
+        instruction::InitializeConfig {
+            admin: *admin,
+            allowed_mints,
+        },
+    )
+}
+
+// Replaces the allowed mint list (admin only).
+pub fn set_allowed_mints(admin: &Pubkey, allowed_mints: Vec<Pubkey>) -> Instruction {
+    build(
+        accounts::UpdateConfig {
+            config: pda::config(),
+            admin: *admin,
+        },
+        instruction::SetAllowedMints { allowed_mints },
+    )
+}
+
+pub fn set_config_admin(admin: &Pubkey, new_admin: &Pubkey) -> Instruction {
+    build(
+        accounts::UpdateConfig {
+            config: pda::config(),
+            admin: *admin,
+        },
+        instruction::SetConfigAdmin {
+            new_admin: *new_admin,
+        },
+    )
+}
+
+// Sets the treasury and fee rates (admin only). Rates are in basis points of
+// the amount moved, `reject_fee` in millionths of a token of whichever mint
+// (see fees::in_mint_units); the program caps them at fees::MAX_FEE_BPS and
+// fees::MAX_REJECT_FEE.
+pub fn set_protocol_fee(
+    admin: &Pubkey,
+    treasury: &Pubkey,
+    join_fee_bps: u16,
+    payout_fee_bps: u16,
+    reject_fee: u64,
+) -> Instruction {
+    build(
+        accounts::UpdateConfig {
+            config: pda::config(),
+            admin: *admin,
+        },
+        instruction::SetProtocolFee {
+            treasury: *treasury,
+            join_fee_bps,
+            payout_fee_bps,
+            reject_fee,
+        },
+    )
+}
+
+// ---- Decoding ----------------------------------------------------------------
+
+// The inverse of the builders, for showing whoever signs an instruction what
+// it does (an offline signer has nothing else to go on): its name and
+// arguments, amounts in base units. None if `ix` is not a call into this
+// program or its data does not decode.
+pub fn describe(ix: &Instruction) -> Option<String> {
+    if ix.program_id != PROGRAM_ID {
+        return None;
+    }
+    let data = ix.data.as_slice();
+    if let Some(args) = decode::<instruction::Initialize>(data) {
+        let handle = decode_handle(&args.account_handle)
+            .map(str::to_string)
+            .unwrap_or_else(|_| format!("{:?}", args.account_handle));
+        return Some(format!(
+            "initialize handle {handle:?} join_amount {} guardians {:?} guardian_threshold {}",
+            args.join_amount, args.guardians, args.guardian_threshold,
+        ));
+    }
+    if let Some(args) = decode::<instruction::InitiateJoinRequest>(data) {
+        return Some(format!(
+            "initiate_join_request join_amount {}{}",
+            args.join_amount,
+            describe_invite(args.invite)
+        ));
+    }
+    if let Some(args) = decode::<instruction::JoinOpenFund>(data) {
+        return Some(format!(
+            "join_open_fund join_amount {}{}",
+            args.join_amount,
+            describe_invite(args.invite)
+        ));
+    }
+    if let Some(args) = decode::<instruction::SetInvitePolicy>(data) {
+        return Some(format!(
+            "set_invite_policy require_invite {} invite_root {}",
+            args.require_invite,
+            args.invite_root
+                .map_or("none".to_string(), |root| hex(&root))
+        ));
+    }
+    if let Some(args) = decode::<instruction::SetJoinRange>(data) {
+        return Some(format!(
+            "set_join_range min_join_amount {} max_join_amount {}",
+            args.min_join_amount, args.max_join_amount
+        ));
+    }
+    if let Some(args) = decode::<instruction::SetOpenAdmission>(data) {
+        return Some(format!("set_open_admission open {}", args.open));
+    }
+    if let Some(args) = decode::<instruction::AddMember>(data) {
+        return Some(format!("add_member new_member {}", args.new_member));
+    }
+    if let Some(args) = decode::<instruction::RejectMember>(data) {
+        return Some(format!("reject_member new_member {}", args.new_member));
+    }
+    if decode::<instruction::CancelStaleJoinRequest>(data).is_some() {
+        return Some("cancel_stale_join_request".to_string());
+    }
+    if let Some(args) = decode::<instruction::CreateProposal>(data) {
+        return Some(format!(
+            "create_proposal amount {} to {}",
+            args.amount, args.proposed_to_account
+        ));
+    }
+    if let Some(args) = decode::<instruction::SubmitAndExecute>(data) {
+        let vote = if args.vote { "yes" } else { "no" };
+        return Some(format!("submit_and_execute vote {vote}"));
+    }
+    if let Some(args) = decode::<instruction::SetGuardians>(data) {
+        return Some(format!(
+            "set_guardians guardians {:?} guardian_threshold {}",
+            args.guardians, args.guardian_threshold
+        ));
+    }
+    if let Some(args) = decode::<instruction::InitiateRecovery>(data) {
+        return Some(format!("initiate_recovery new_owner {}", args.new_owner));
+    }
+    if decode::<instruction::ApproveRecovery>(data).is_some() {
+        return Some("approve_recovery".to_string());
+    }
+    if decode::<instruction::ExecuteRecovery>(data).is_some() {
+        return Some("execute_recovery".to_string());
+    }
+    if decode::<instruction::CancelRecovery>(data).is_some() {
+        return Some("cancel_recovery".to_string());
+    }
+    if let Some(args) = decode::<instruction::ProposeDissolution>(data) {
+        return Some(format!(
+            "propose_dissolution distribution {:?} rent_recipient {}",
+            args.distribution, args.rent_recipient
+        ));
+    }
+    if decode::<instruction::DissolveFund>(data).is_some() {
+        return Some("dissolve_fund".to_string());
+    }
+    if decode::<instruction::MigrateFund>(data).is_some() {
+        return Some("migrate_fund".to_string());
+    }
+    if decode::<instruction::MigrateProposal>(data).is_some() {
+        return Some("migrate_proposal".to_string());
+    }
+    if decode::<instruction::MigrateJoinRequest>(data).is_some() {
+        return Some("migrate_join_request".to_string());
+    }
+    if decode::<instruction::RegisterMembership>(data).is_some() {
+        return Some("register_membership".to_string());
+    }
+    if decode::<instruction::CloseMembership>(data).is_some() {
+        return Some("close_membership".to_string());
+    }
+    if let Some(args) = decode::<instruction::SetDues>(data) {
+        return Some(format!(
+            "set_dues amount {} period_seconds {}",
+            args.amount, args.period_seconds
+        ));
+    }
+    if decode::<instruction::CollectDues>(data).is_some() {
+        return Some("collect_dues".to_string());
+    }
+    if decode::<instruction::CloseDues>(data).is_some() {
+        return Some("close_dues".to_string());
+    }
+    if let Some(args) = decode::<instruction::StartRosca>(data) {
+        return Some(format!("start_rosca draw {}", args.draw));
+    }
+    if decode::<instruction::PayRoscaRound>(data).is_some() {
+        return Some("pay_rosca_round".to_string());
+    }
+    if decode::<instruction::CloseRosca>(data).is_some() {
+        return Some("close_rosca".to_string());
+    }
+    if let Some(args) = decode::<instruction::ProposeStream>(data) {
+        let terms = args.terms;
+        return Some(format!(
+            "propose_stream amount {} to {} start {} cliff {} end {}",
+            terms.amount, terms.recipient, terms.start, terms.cliff, terms.end
+        ));
+    }
+    if decode::<instruction::ProposeStreamCancel>(data).is_some() {
+        return Some("propose_stream_cancel".to_string());
+    }
+    if decode::<instruction::ClaimStream>(data).is_some() {
+        return Some("claim_stream".to_string());
+    }
+    if decode::<instruction::CloseStream>(data).is_some() {
+        return Some("close_stream".to_string());
+    }
+    if decode::<instruction::ClaimHandle>(data).is_some() {
+        return Some("claim_handle".to_string());
+    }
+    if decode::<instruction::ReleaseHandle>(data).is_some() {
+        return Some("release_handle".to_string());
+    }
+    if let Some(args) = decode::<instruction::InitializeConfig>(data) {
+        return Some(format!(
+            "initialize_config admin {} allowed_mints {:?}",
+            args.admin, args.allowed_mints
+        ));
+    }
+    if let Some(args) = decode::<instruction::SetAllowedMints>(data) {
+        return Some(format!(
+            "set_allowed_mints allowed_mints {:?}",
+            args.allowed_mints
+        ));
+    }
+    if let Some(args) = decode::<instruction::SetConfigAdmin>(data) {
+        return Some(format!("set_config_admin new_admin {}", args.new_admin));
+    }
+    if let Some(args) = decode::<instruction::SetProtocolFee>(data) {
+        return Some(format!(
+            "set_protocol_fee treasury {} join_fee_bps {} payout_fee_bps {} reject_fee {}",
+            args.treasury, args.join_fee_bps, args.payout_fee_bps, args.reject_fee
+        ));
+    }
+    None
+}
+
+// `data` as instruction T: its discriminator, then exactly its arguments.
+fn decode<T: Discriminator + AnchorDeserialize>(data: &[u8]) -> Option<T> {
+    let args = data.strip_prefix(T::DISCRIMINATOR)?;
+    T::try_from_slice(args).ok()
+}
+
+fn describe_invite(invite: Option<Invite>) -> String {
+    match invite {
+        None => String::new(),
+        Some(Invite::Signed { expires_at }) => format!(" signed invite until {expires_at}"),
+        Some(Invite::Allowlisted { proof }) => {
+            format!(" allowlist proof of {} hashes", proof.len())
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encode_handle, TransactionMessage};
+
+    fn key() -> Pubkey {
+        Pubkey::new_unique()
+    }
+
+    #[test]
+    fn targets_the_program_with_its_discriminators() {
+        let ix = approve_recovery(&key(), &key());
+        assert_eq!(ix.program_id, PROGRAM_ID);
+        assert_eq!(ix.data, instruction::ApproveRecovery::DISCRIMINATOR);
+
+        let ix = submit_and_execute(
+            &key(),
+            &key(),
+            &key(),
+            &Transaction::default(),
+            &TokenMint::spl_token(key()),
+            &key(),
+            true,
+        );
+        assert_eq!(&ix.data[..8], instruction::SubmitAndExecute::DISCRIMINATOR);
+        assert_eq!(ix.data[8..], [1]);
+    }
+
+    // Signers and writable accounts are what a wallet has to get right; check
+    // them against the program's account constraints for the busiest builder.
+    #[test]
+    fn submit_and_execute_derives_every_account() {
+        let (fee_payer, fund, submitter) = (key(), key(), key());
+        let mint = TokenMint::token_2022(key());
+        let recipient = key();
+        let proposal = Transaction {
+            message_data: TransactionMessage {
+                nonce: 3,
+                proposed_to_account: recipient,
+                ..Default::default()
+            },
+            rent_payer: key(),
+            ..Default::default()
+        };
+
+        let treasury = key();
+        let ix = submit_and_execute(
+            &fee_payer, &fund, &submitter, &proposal, &mint, &treasury, false,
+        );
+        let meta = |pubkey: Pubkey| {
+            ix.accounts
+                .iter()
+                .find(|m| m.pubkey == pubkey)
+                .unwrap_or_else(|| panic!("{pubkey} missing"))
+                .clone()
+        };
+        assert_eq!(
+            meta(pda::proposal(&fund, 3)),
+            AccountMeta::new(pda::proposal(&fund, 3), false)
+        );
+        assert_eq!(
+            meta(proposal.rent_payer),
+            AccountMeta::new(proposal.rent_payer, false)
+        );
+        assert_eq!(meta(fee_payer), AccountMeta::new(fee_payer, true));
+        assert_eq!(meta(submitter), AccountMeta::new_readonly(submitter, true));
+        assert_eq!(meta(recipient), AccountMeta::new_readonly(recipient, false));
+        let recipient_ata = pda::member_ata(&recipient, &mint);
+        assert_eq!(meta(recipient_ata), AccountMeta::new(recipient_ata, false));
+        assert_eq!(meta(treasury), AccountMeta::new_readonly(treasury, false));
+        let treasury_ata = mint.ata(&treasury);
+        assert_eq!(meta(treasury_ata), AccountMeta::new(treasury_ata, false));
+    }
+
+    #[test]
+    fn initialize_config_is_signed_by_the_upgrade_authority() {
+        let (fee_payer, authority, admin) = (key(), key(), key());
+        let ix = initialize_config(&fee_payer, &authority, &admin, vec![key()]);
+        assert_eq!(ix.accounts[1], AccountMeta::new_readonly(authority, true));
+        assert_eq!(ix.accounts[2], AccountMeta::new(pda::config(), false));
+        assert_eq!(
+            ix.accounts[4],
+            AccountMeta::new_readonly(pda::program_data(), false)
+        );
+        assert!(describe(&ix)
+            .unwrap()
+            .starts_with(&format!("initialize_config admin {admin}")));
+    }
+
+    #[test]
+    fn dissolve_fund_appends_member_atas_in_order() {
+        let (fund, mint) = (key(), TokenMint::spl_token(key()));
+        let members = [key(), key(), key()];
+        let ix = dissolve_fund(&fund, &key(), None, &mint, &members);
+        let tail = &ix.accounts[ix.accounts.len() - members.len()..];
+        for (meta, member) in tail.iter().zip(&members) {
+            assert_eq!(
+                *meta,
+                AccountMeta::new(pda::member_ata(member, &mint), false)
+            );
+        }
+    }
+
+    #[test]
+    fn dissolve_fund_refunds_the_dues_schedule_to_its_rent_payer() {
+        let (fund, rent_recipient) = (key(), key());
+        let mint = TokenMint::spl_token(key());
+        let schedule = DuesSchedule {
+            rent_payer: key(),
+            ..Default::default()
+        };
+        for (dues, refunded) in [
+            (None, rent_recipient),
+            (Some(&schedule), schedule.rent_payer),
+        ] {
+            let ix = dissolve_fund(&fund, &rent_recipient, dues, &mint, &[]);
+            assert_eq!(ix.accounts[4], AccountMeta::new(pda::dues(&fund), false));
+            assert_eq!(ix.accounts[5], AccountMeta::new(refunded, false));
+        }
+    }
+
+    #[test]
+    fn describe_names_the_instruction_and_its_arguments() {
+        let (fund, member, to) = (key(), key(), key());
+        let mint = TokenMint::spl_token(key());
+        let ix = create_proposal(&member, &fund, 0, &member, &mint, 1_500_000, &to);
+        assert_eq!(
+            describe(&ix).as_deref(),
+            Some(format!("create_proposal amount 1500000 to {to}").as_str())
+        );
+        let ix = submit_and_execute(
+            &member,
+            &fund,
+            &member,
+            &Transaction::default(),
+            &mint,
+            &to,
+            false,
+        );
+        assert_eq!(describe(&ix).as_deref(), Some("submit_and_execute vote no"));
+        let ix = add_member(&member, &fund, &member, &to, &to, &mint, &key());
+        assert_eq!(describe(&ix), Some(format!("add_member new_member {to}")));
+        let handle = encode_handle("squad").unwrap();
+        let ix = initialize(&member, &member, &handle, &mint, 7, vec![], 0);
+        assert_eq!(
+            describe(&ix).as_deref(),
+            Some("initialize handle \"squad\" join_amount 7 guardians [] guardian_threshold 0")
+        );
+    }
+
+    #[test]
+    fn a_signed_invite_carries_the_owners_message_for_the_join_request() {
+        let (owner, fund, invitee) = (key(), key(), key());
+        let ix = signed_invite(&owner, &[9; 64], &fund, &invitee, 1_700_000_000);
+        assert_eq!(ix.program_id, ed25519_program::ID);
+        let (signer, message) = invite::ed25519_signed(&ix.data).unwrap();
+        assert_eq!(signer, owner);
+        assert_eq!(message, invite::message(&fund, &invitee, 1_700_000_000));
+
+        let invite = Invite::Signed {
+            expires_at: 1_700_000_000,
+        };
+        let mint = TokenMint::spl_token(key());
+        let ix = initiate_join_request(&key(), &fund, &invitee, &mint, 5, Some(invite));
+        assert_eq!(
+            describe(&ix).as_deref(),
+            Some("initiate_join_request join_amount 5 signed invite until 1700000000")
+        );
+    }
+
+    #[test]
+    fn members_approve_the_funds_dues_pda_on_their_own_ata() {
+        use token_2022::spl_token_2022::instruction::TokenInstruction;
+
+        let (member, fund) = (key(), key());
+        let mint = TokenMint::token_2022(key());
+        let ix = approve_dues(&member, &fund, &mint, 6, 12_000_000);
+        assert_eq!(ix.program_id, mint.token_program);
+        let keys: Vec<_> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(
+            keys,
+            [mint.ata(&member), mint.address, pda::dues(&fund), member]
+        );
+        assert!(matches!(
+            TokenInstruction::unpack(&ix.data),
+            Ok(TokenInstruction::ApproveChecked {
+                amount: 12_000_000,
+                decimals: 6
+            })
+        ));
+    }
+
+    #[test]
+    fn a_rosca_round_pays_the_recipients_own_ata() {
+        let (payer, fund, recipient, treasury) = (key(), key(), key(), key());
+        let mint = TokenMint::token_2022(key());
+        let ix = pay_rosca_round(&payer, &fund, &recipient, &mint, &treasury);
+        let accounts = accounts::PayRoscaRound {
+            multisig: fund,
+            dues: pda::dues(&fund),
+            rosca: pda::rosca(&fund),
+            fee_payer: payer,
+            recipient,
+            recipient_ata: mint.ata(&recipient),
+            multisig_ata: pda::vault(&fund),
+            mint: mint.address,
+            config: pda::config(),
+            treasury,
+            treasury_ata: mint.ata(&treasury),
+            token_program: mint.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        };
+        assert_eq!(ix.accounts, accounts.to_account_metas(None));
+        assert_eq!(describe(&ix).as_deref(), Some("pay_rosca_round"));
+        let ix = start_rosca(&payer, &fund, &payer, true);
+        assert_eq!(describe(&ix).as_deref(), Some("start_rosca draw true"));
+    }
+
+    #[test]
+    fn only_a_stream_cancel_vote_passes_the_stream_account() {
+        let (member, fund, treasury) = (key(), key(), key());
+        let mint = TokenMint::spl_token(key());
+        let stream = pda::stream(&fund, 3);
+        let mut proposal = Transaction::default();
+        proposal.message_data.proposed_to_account = stream;
+        proposal.kind = ProposalKind::Stream;
+        let ix = submit_and_execute(&member, &fund, &member, &proposal, &mint, &treasury, true);
+        // Funding a stream pays its escrow; the optional account is left out.
+        assert!(ix
+            .accounts
+            .iter()
+            .any(|meta| meta.pubkey == mint.ata(&stream)));
+        assert_eq!(ix.accounts.last().unwrap().pubkey, PROGRAM_ID);
+
+        proposal.kind = ProposalKind::CancelStream;
+        let ix = submit_and_execute(&member, &fund, &member, &proposal, &mint, &treasury, true);
+        let last = ix.accounts.last().unwrap();
+        assert_eq!((last.pubkey, last.is_writable), (stream, true));
+
+        let terms = StreamTerms {
+            recipient: member,
+            amount: 12_000_000,
+            start: 100,
+            cliff: 200,
+            end: 1_000,
+        };
+        let ix = propose_stream(&member, &fund, 3, &member, &mint, terms);
+        assert_eq!(
+            describe(&ix),
+            Some(format!(
+                "propose_stream amount 12000000 to {member} start 100 cliff 200 end 1000"
+            ))
+        );
+    }
+
+    #[test]
+    fn describe_rejects_other_programs_and_garbage() {
+        let mut ix = approve_recovery(&key(), &key());
+        assert_eq!(describe(&ix).as_deref(), Some("approve_recovery"));
+        ix.data.push(0); // trailing bytes
+        assert_eq!(describe(&ix), None);
+        ix.data = instruction::CreateProposal::DISCRIMINATOR.to_vec(); // args missing
+        assert_eq!(describe(&ix), None);
+        ix.data = instruction::ApproveRecovery::DISCRIMINATOR.to_vec();
+        ix.program_id = key();
+        assert_eq!(describe(&ix), None);
+    }
+}
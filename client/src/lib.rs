@@ -0,0 +1,51 @@
+//! Rust client for the `squad_mint_multi_sig` program.
+//!
+//! What every integrator otherwise re-derives by hand from the program source
+//! (or `tests/helper_function.ts`):
+//!
+//! - [`handle`]: the fixed `[u8; 15]` fund handle (UTF-8, NUL-padded).
+//! - [`pda`]: the config, fund, vault, proposal, join-request, join-escrow,
+//!   recovery, membership, handle-registry, dues, rotation and stream addresses.
+//! - [`instructions`]: one builder per program instruction.
+//! - [`TokenMint`]: the fund's mint and its token program (SPL Token or
+//!   Token-2022), which every token account derivation depends on.
+//! - [`state`]: decoding of the program's accounts from raw account data.
+//! - [`layout`]: field byte offsets for `getProgramAccounts` memcmp filters.
+//! - [`invite`]: owner-signed invite messages and allowlist Merkle roots/proofs.
+//! - [`dues`]: which dues periods are owed and collectable.
+//! - [`rosca`]: rotation orders and when a round is funded.
+//! - [`vesting`]: how much of a stream has vested.
+//!
+//! Builders use the program's own Anchor `accounts` / `instruction` types, so a
+//! renamed account or argument breaks this crate's build rather than producing
+//! instructions the program rejects at runtime.
+
+pub mod handle;
+pub mod instructions;
+pub mod mint;
+pub mod pda;
+pub mod state;
+
+pub use handle::{decode_handle, encode_handle, Handle, HandleError};
+pub use mint::TokenMint;
+// Quorum math and vote bitmask slots, exactly as the program applies them.
+pub use squad_mint_multi_sig::vote;
+// Field offsets for getProgramAccounts memcmp filters.
+pub use squad_mint_multi_sig::layout;
+// Dues period math, exactly as collect_dues applies it.
+pub use squad_mint_multi_sig::dues;
+// Rotation orders and round funding, exactly as pay_rosca_round checks them.
+pub use squad_mint_multi_sig::rosca;
+// Protocol fee caps, and flat amounts in a mint's decimals.
+pub use squad_mint_multi_sig::fees;
+// Stream vesting, exactly as claim_stream applies it.
+pub use squad_mint_multi_sig::vesting;
+// Invite messages, Ed25519 instruction layout and allowlist Merkle trees.
+pub use squad_mint_multi_sig::invite::{self, Invite};
+// Account layout versions; see migrate_fund.
+pub use squad_mint_multi_sig::version::{AccountTrailer, AccountVersion};
+pub use squad_mint_multi_sig::{
+    Config, DissolutionTerms, Distribution, DuesSchedule, ErrorCode, HandleRegistry,
+    JoinRequestCustodialWallet, Membership, ProposalKind, RecoveryRequest, Rosca, SquadMintFund,
+    Stream, StreamTerms, Transaction, TransactionMessage, ID as PROGRAM_ID,
+};
@@ -0,0 +1,57 @@
+// A fund's mint together with the token program that owns it. The program
+// takes the token program as an account and derives every associated token
+// account with its id, so legacy SPL Token and Token-2022 mints differ in both.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id, token, token_2022,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenMint {
+    pub address: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl TokenMint {
+    // `token_program` is the owner of the mint account.
+    pub const fn new(address: Pubkey, token_program: Pubkey) -> Self {
+        Self {
+            address,
+            token_program,
+        }
+    }
+
+    pub const fn spl_token(address: Pubkey) -> Self {
+        Self::new(address, token::ID)
+    }
+
+    pub const fn token_2022(address: Pubkey) -> Self {
+        Self::new(address, token_2022::ID)
+    }
+
+    // `owner`'s canonical token account for this mint.
+    pub fn ata(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &self.address, &self.token_program)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_spl::associated_token::get_associated_token_address;
+
+    #[test]
+    fn atas_depend_on_the_token_program() {
+        let (owner, address) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let legacy = TokenMint::spl_token(address);
+        assert_eq!(
+            legacy.ata(&owner),
+            get_associated_token_address(&owner, &address)
+        );
+        assert_ne!(
+            legacy.ata(&owner),
+            TokenMint::token_2022(address).ata(&owner)
+        );
+    }
+}
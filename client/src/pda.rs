@@ -0,0 +1,112 @@
+// Program-derived addresses, with the same seeds as the program's account
+// constraints. Each returns the canonical (highest) bump's address, which is
+// the only one the program accepts.
+
+use anchor_lang::prelude::Pubkey;
+use solana_sdk_ids::bpf_loader_upgradeable;
+
+use crate::{Handle, TokenMint, PROGRAM_ID};
+
+pub const VAULT_SEED: &[u8] = b"token_vault";
+pub const PROPOSAL_SEED: &[u8] = b"proposal_tx_data";
+pub const JOIN_REQUEST_SEED: &[u8] = b"join_custodial_account";
+pub const JOIN_ESCROW_SEED: &[u8] = b"join_custodial_account_ata";
+pub const RECOVERY_SEED: &[u8] = b"recovery_request";
+pub const CONFIG_SEED: &[u8] = b"config";
+pub const MEMBERSHIP_SEED: &[u8] = b"membership";
+pub const HANDLE_SEED: &[u8] = b"handle";
+pub const DUES_SEED: &[u8] = b"dues";
+pub const ROSCA_SEED: &[u8] = b"rosca";
+pub const STREAM_SEED: &[u8] = b"stream";
+
+// The program-wide config: its admin and the mints funds may use.
+pub fn config() -> Pubkey {
+    Pubkey::find_program_address(&[CONFIG_SEED], &PROGRAM_ID).0
+}
+
+// Not a program PDA: the upgradeable loader's record of the program, naming
+// its upgrade authority. Only `initialize_config` reads it.
+pub fn program_data() -> Pubkey {
+    Pubkey::find_program_address(&[PROGRAM_ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+// `[handle, owner]`. `owner` is the creator even after a guardian recovery:
+// recovery moves owner privileges, not the address.
+pub fn fund(handle: &Handle, owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[handle, owner.as_ref()], &PROGRAM_ID).0
+}
+
+// The fund's token account, owned by the fund PDA.
+pub fn vault(fund: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[VAULT_SEED, fund.as_ref()], &PROGRAM_ID).0
+}
+
+// One per proposal; `nonce` is the fund's master_nonce when it was opened, so
+// the open proposal is always at the fund's current nonce.
+pub fn proposal(fund: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[PROPOSAL_SEED, fund.as_ref(), &nonce.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+    .0
+}
+
+pub fn join_request(fund: &Pubkey, joiner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[JOIN_REQUEST_SEED, fund.as_ref(), joiner.as_ref()],
+        &PROGRAM_ID,
+    )
+    .0
+}
+
+// The token account holding a join request's deposit until it is accepted or
+// refunded. Seeded by the join request, not the fund.
+pub fn join_escrow(join_request: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[JOIN_ESCROW_SEED, join_request.as_ref()], &PROGRAM_ID).0
+}
+
+pub fn recovery(fund: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[RECOVERY_SEED, fund.as_ref()], &PROGRAM_ID).0
+}
+
+// The index entry recording that `member` belongs to `fund`. To list a
+// wallet's funds, filter Membership accounts on layout::membership::MEMBER.
+pub fn membership(fund: &Pubkey, member: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[MEMBERSHIP_SEED, fund.as_ref(), member.as_ref()],
+        &PROGRAM_ID,
+    )
+    .0
+}
+
+// Keyed by handle alone: the fund that claimed `handle`, if any (see
+// instructions::claim_handle).
+pub fn handle_registry(handle: &Handle) -> Pubkey {
+    Pubkey::find_program_address(&[HANDLE_SEED, handle], &PROGRAM_ID).0
+}
+
+// The fund's dues schedule, and the delegate members approve for it (see
+// instructions::approve_dues).
+pub fn dues(fund: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[DUES_SEED, fund.as_ref()], &PROGRAM_ID).0
+}
+
+// The fund's rotating-savings rotation (see instructions::start_rosca).
+pub fn rosca(fund: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[ROSCA_SEED, fund.as_ref()], &PROGRAM_ID).0
+}
+
+// A stream, seeded by the nonce of the proposal that funds it. Its escrow is
+// its canonical ATA (TokenMint::ata).
+pub fn stream(fund: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[STREAM_SEED, fund.as_ref(), &nonce.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+    .0
+}
+
+// Not a program PDA, but where payouts, refunds and dissolution shares land.
+pub fn member_ata(member: &Pubkey, mint: &TokenMint) -> Pubkey {
+    mint.ata(member)
+}
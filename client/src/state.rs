@@ -0,0 +1,193 @@
+// Decoding of the program's accounts from raw account data (as returned by
+// getAccountInfo). Each checks the 8-byte Anchor discriminator first, so
+// passing the wrong account type is an error rather than garbage.
+
+use anchor_lang::{AccountDeserialize, Result};
+
+use crate::{
+    Config, DuesSchedule, HandleRegistry, JoinRequestCustodialWallet, Membership, RecoveryRequest,
+    Rosca, SquadMintFund, Stream, Transaction,
+};
+
+pub fn config(data: &[u8]) -> Result<Config> {
+    decode(data)
+}
+
+pub fn fund(data: &[u8]) -> Result<SquadMintFund> {
+    decode_zero_extended(data, 8 + SquadMintFund::MAX_SIZE)
+}
+
+pub fn proposal(data: &[u8]) -> Result<Transaction> {
+    decode_zero_extended(data, 8 + Transaction::MAX_SIZE)
+}
+
+pub fn join_request(data: &[u8]) -> Result<JoinRequestCustodialWallet> {
+    decode_zero_extended(data, 8 + JoinRequestCustodialWallet::MAX_SIZE)
+}
+
+pub fn recovery_request(data: &[u8]) -> Result<RecoveryRequest> {
+    decode(data)
+}
+
+pub fn membership(data: &[u8]) -> Result<Membership> {
+    decode(data)
+}
+
+pub fn handle_registry(data: &[u8]) -> Result<HandleRegistry> {
+    decode(data)
+}
+
+pub fn dues(data: &[u8]) -> Result<DuesSchedule> {
+    decode(data)
+}
+
+pub fn rosca(data: &[u8]) -> Result<Rosca> {
+    decode(data)
+}
+
+pub fn stream(data: &[u8]) -> Result<Stream> {
+    decode(data)
+}
+
+fn decode<T: AccountDeserialize>(mut data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut data)
+}
+
+// Accounts an older program allocated for a shorter layout end before the
+// fields appended since. Read them as the migrate_* instructions do, with
+// those fields as zeros.
+fn decode_zero_extended<T: AccountDeserialize>(data: &[u8], space: usize) -> Result<T> {
+    if data.len() < 8 || data.len() >= space {
+        return decode(data);
+    }
+    let mut padded = data.to_vec();
+    padded.resize(space, 0);
+    decode(&padded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AccountTrailer, AccountVersion, ProposalKind, TransactionMessage};
+    use anchor_lang::{
+        error::{Error, ErrorCode as AnchorErrorCode},
+        prelude::Pubkey,
+        AccountSerialize, AnchorSerialize, Discriminator,
+    };
+
+    fn account_data<T: AccountSerialize>(account: &T, space: usize) -> Vec<u8> {
+        let mut data = Vec::with_capacity(8 + space);
+        account.try_serialize(&mut data).unwrap();
+        // Accounts are allocated at full size; the tail is zeroed, not trimmed.
+        data.resize(8 + space, 0);
+        data
+    }
+
+    fn anchor_code(err: Error) -> u32 {
+        match err {
+            Error::AnchorError(e) => e.error_code_number,
+            other => panic!("expected an Anchor error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn decodes_zero_padded_account_data() {
+        let rent_payer = Pubkey::new_unique();
+        let transaction = Transaction {
+            belongs_to_squad_mint_fund: Pubkey::new_unique(),
+            voted_mask: 0b011,
+            votes: 0b001,
+            message_data: TransactionMessage {
+                amount: 5_000_000,
+                proposer_account: Pubkey::new_unique(),
+                proposed_to_account: Pubkey::new_unique(),
+                nonce: 7,
+            },
+            did_meet_threshold: false,
+            kind: ProposalKind::Payout,
+            rent_payer,
+            trailer: AccountTrailer::CURRENT,
+        };
+        let decoded = proposal(&account_data(&transaction, Transaction::MAX_SIZE)).unwrap();
+        assert_eq!(decoded.message_data.nonce, 7);
+        assert_eq!(decoded.voted_mask, 0b011);
+        assert_eq!(decoded.rent_payer, rent_payer);
+        assert_eq!(decoded.trailer.version, AccountVersion::CURRENT);
+
+        let fund_data = account_data(&SquadMintFund::default(), SquadMintFund::MAX_SIZE);
+        assert_eq!(fund(&fund_data).unwrap().master_nonce(), 0);
+    }
+
+    #[test]
+    fn decodes_proposals_and_join_requests_opened_before_the_rent_payer() {
+        // Byte for byte what the first release wrote: no kind, rent payer or
+        // trailer, and no slack after the last field.
+        let proposer = Pubkey::new_unique();
+        let mut data = Transaction::DISCRIMINATOR.to_vec();
+        (
+            Pubkey::new_unique(),
+            0b011u16,
+            0b001u16,
+            (5_000_000u64, proposer, Pubkey::new_unique(), 7u64),
+            false,
+        )
+            .serialize(&mut data)
+            .unwrap();
+        assert_eq!(data.len(), 8 + 117);
+        let decoded = proposal(&data).unwrap();
+        assert_eq!(decoded.message_data.proposer_account, proposer);
+        assert_eq!(decoded.kind, ProposalKind::Payout);
+        assert_eq!(decoded.rent_payer, Pubkey::default());
+        assert_eq!(decoded.trailer.version, AccountVersion::V1);
+
+        let joiner = Pubkey::new_unique();
+        let mut data = JoinRequestCustodialWallet::DISCRIMINATOR.to_vec();
+        (Pubkey::new_unique(), joiner, 1_110_000u64)
+            .serialize(&mut data)
+            .unwrap();
+        assert_eq!(data.len(), 8 + 72);
+        let decoded = join_request(&data).unwrap();
+        assert_eq!(decoded.request_to_join_user, joiner);
+        assert_eq!(decoded.join_amount(), 1_110_000);
+        assert_eq!(decoded.trailer.version, AccountVersion::V1);
+    }
+
+    #[test]
+    fn decodes_a_first_release_fund_with_every_member_slot_taken() {
+        let members: Vec<Pubkey> = (0..SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+        let mut data = SquadMintFund::DISCRIMINATOR.to_vec();
+        (
+            members[0],
+            [7u8; 15],
+            true,
+            members.clone(),
+            1_110_000u64,
+            3u64,
+        )
+            .serialize(&mut data)
+            .unwrap();
+        assert_eq!(data.len(), 8 + 324);
+        let decoded = fund(&data).unwrap();
+        assert_eq!(decoded.members(), members);
+        assert_eq!(decoded.master_nonce(), 3);
+        assert!(decoded.has_active_vote());
+        assert!(decoded.guardians().is_empty());
+        assert_eq!(decoded.contribution_of(1), 1_110_000);
+        assert_eq!(decoded.version(), AccountVersion::V1);
+    }
+
+    #[test]
+    fn rejects_another_account_type() {
+        let data = account_data(&SquadMintFund::default(), SquadMintFund::MAX_SIZE);
+        assert_eq!(
+            anchor_code(proposal(&data).unwrap_err()),
+            AnchorErrorCode::AccountDiscriminatorMismatch as u32
+        );
+        assert_eq!(
+            anchor_code(join_request(&[]).unwrap_err()),
+            AnchorErrorCode::AccountDiscriminatorNotFound as u32
+        );
+    }
+}
@@ -0,0 +1,1012 @@
+//! In-process test harness for `squad_mint_multi_sig` on [LiteSVM].
+//!
+//! Loads the `anchor build` artifact (`target/deploy/squad_mint_multi_sig.so`)
+//! into an in-process SVM and provides the setup `tests/helper_function.ts`
+//! gives the TypeScript suites: the program config, the test mint, wallets with
+//! funded ATAs, funds, join requests and proposals. PDAs and instructions come from
+//! `squad_mint_client`, which builds them from the program's own Anchor
+//! `accounts` / `instruction` types, so a renamed account or argument breaks
+//! the build here instead of failing at runtime.
+//!
+//! [LiteSVM]: https://github.com/LiteSVM/litesvm
+
+use anchor_lang::{
+    prelude::{Clock, Pubkey},
+    solana_program::{
+        instruction::{AccountMeta, Instruction, InstructionError},
+        program_option::COption,
+        program_pack::Pack,
+    },
+    AccountDeserialize, AnchorSerialize, Discriminator,
+};
+use anchor_spl::{
+    token::spl_token::{
+        self,
+        state::{Account as TokenAccount, AccountState, Mint},
+    },
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::{TransferFee, TransferFeeAmount, TransferFeeConfig},
+            BaseStateWithExtensions, BaseStateWithExtensionsMut, ExtensionType,
+            StateWithExtensions, StateWithExtensionsMut,
+        },
+        state::{Account as Token2022Account, Mint as Token2022Mint},
+    },
+};
+use litesvm::{types::TransactionResult, LiteSVM};
+use solana_account::Account;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+use squad_mint_client::{
+    instructions, invite, pda, Config, Distribution, DuesSchedule, Handle, HandleRegistry,
+    JoinRequestCustodialWallet, Membership, Rosca, SquadMintFund, Stream, StreamTerms, TokenMint,
+    Transaction as Proposal, PROGRAM_ID,
+};
+
+pub use squad_mint_multi_sig::{
+    invite::Invite,
+    version::{AccountTrailer, AccountVersion},
+    ErrorCode,
+};
+
+const PROGRAM_SO: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../target/deploy/squad_mint_multi_sig.so"
+);
+
+// Mirrors `decimals` / `amountToSmalletDecimal` in tests/helper_function.ts.
+pub const DECIMALS: u8 = 6;
+pub const ONE_USDC: u64 = 10u64.pow(DECIMALS as u32);
+// 1.11 USDC, the join amount every TS suite uses.
+pub const JOIN_AMOUNT: u64 = 1_110_000;
+// The test mint, a legacy SPL Token mint at the address the TS suites create
+// it at, and the only mint the config allows to begin with.
+pub const USDC_MINT: Pubkey =
+    Pubkey::from_str_const("37KQMrbBtkNFYJvDKW3tGxEs1WuvqcEeu44JGrjPkYsz");
+pub const MINT: TokenMint = TokenMint::spl_token(USDC_MINT);
+
+// MAX_SIZE of each account type in the program's first release.
+pub const BASELINE_FUND_SIZE: usize = 324;
+pub const BASELINE_PROPOSAL_SIZE: usize = 117;
+pub const BASELINE_JOIN_REQUEST_SIZE: usize = 72;
+
+pub struct Wallet {
+    pub keypair: Keypair,
+    pub ata: Pubkey, // canonical ATA for the wallet's mint (USDC unless made with wallet_for)
+}
+
+impl Wallet {
+    pub fn pubkey(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+}
+
+pub struct Fund {
+    pub pda: Pubkey,
+    pub members: Vec<Wallet>, // members[0] is the owner
+}
+
+impl Fund {
+    pub fn owner(&self) -> &Wallet {
+        &self.members[0]
+    }
+}
+
+pub struct TestEnv {
+    pub svm: LiteSVM,
+    pub fee_payer: Keypair,
+    pub config_admin: Keypair,
+}
+
+impl TestEnv {
+    pub fn new() -> Self {
+        let program = std::fs::read(PROGRAM_SO).unwrap_or_else(|e| {
+            panic!("{PROGRAM_SO}: {e} — run `anchor build` before these tests")
+        });
+        let mut svm = LiteSVM::new();
+        svm.add_program(PROGRAM_ID, &program);
+
+        let fee_payer = Keypair::new();
+        svm.airdrop(&fee_payer.pubkey(), 100_000_000_000)
+            .expect("airdrop to fee payer");
+
+        let mut env = Self {
+            svm,
+            fee_payer,
+            config_admin: Keypair::new(),
+        };
+        env.create_mint(USDC_MINT);
+        env.set_config(&[USDC_MINT]);
+        env
+    }
+
+    // ---- Accounts ---------------------------------------------------------
+
+    // Writes the config directly: `initialize_config` needs the upgradeable
+    // loader's ProgramData account, which `add_program` doesn't create. Like
+    // `initialize_config`, the admin is the treasury and every fee is zero.
+    pub fn set_config(&mut self, allowed_mints: &[Pubkey]) {
+        let admin = self.config_admin.pubkey();
+        let mut data = Config::DISCRIMINATOR.to_vec();
+        (
+            admin,
+            allowed_mints.to_vec(),
+            admin,
+            0u16,
+            0u16,
+            0u64,
+            AccountTrailer::CURRENT,
+        )
+            .serialize(&mut data)
+            .unwrap();
+        data.resize(8 + Config::MAX_SIZE, 0);
+        let account = Account {
+            lamports: self.svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.svm.set_account(pda::config(), account).unwrap();
+    }
+
+    pub fn config(&self) -> Config {
+        self.anchor_account(&pda::config())
+    }
+
+    pub fn treasury(&self) -> Pubkey {
+        self.config().treasury()
+    }
+
+    // The treasury's ATA for the test mint, where every fee lands.
+    pub fn treasury_ata(&self) -> Pubkey {
+        MINT.ata(&self.treasury())
+    }
+
+    // Sets the fee rates through the program, signed by the config admin.
+    pub fn set_protocol_fee(
+        &mut self,
+        treasury: &Pubkey,
+        join_fee_bps: u16,
+        payout_fee_bps: u16,
+        reject_fee: u64,
+    ) -> TransactionResult {
+        let admin = self.config_admin.insecure_clone();
+        let ix = instructions::set_protocol_fee(
+            &admin.pubkey(),
+            treasury,
+            join_fee_bps,
+            payout_fee_bps,
+            reject_fee,
+        );
+        self.send(&[ix], &[&admin])
+    }
+
+    pub fn create_mint(&mut self, mint: Pubkey) {
+        let state = Mint {
+            mint_authority: COption::Some(self.fee_payer.pubkey()),
+            supply: 0,
+            decimals: DECIMALS,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        let mut data = vec![0; Mint::LEN];
+        Mint::pack(state, &mut data).unwrap();
+        self.set_token_program_account(mint, data, spl_token::ID);
+    }
+
+    // Writes a Token-2022 mint with `extensions` initialised (zeroed) and
+    // `configure` run on them. The config doesn't allow it until `set_config`
+    // lists it.
+    pub fn create_token_2022_mint(
+        &mut self,
+        mint: Pubkey,
+        extensions: &[ExtensionType],
+        configure: impl FnOnce(&mut StateWithExtensionsMut<Token2022Mint>),
+    ) -> TokenMint {
+        let len = ExtensionType::try_calculate_account_len::<Token2022Mint>(extensions).unwrap();
+        let mut data = vec![0; len];
+        let mut state =
+            StateWithExtensionsMut::<Token2022Mint>::unpack_uninitialized(&mut data).unwrap();
+        configure(&mut state);
+        state.base = Token2022Mint {
+            mint_authority: COption::Some(self.fee_payer.pubkey()),
+            supply: 0,
+            decimals: DECIMALS,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        self.set_token_program_account(mint, data, spl_token_2022::ID);
+        TokenMint::token_2022(mint)
+    }
+
+    // A Token-2022 mint charging `basis_points` of every transfer, capped at
+    // `maximum_fee` base units, in every epoch.
+    pub fn create_fee_mint(
+        &mut self,
+        mint: Pubkey,
+        basis_points: u16,
+        maximum_fee: u64,
+    ) -> TokenMint {
+        self.create_token_2022_mint(mint, &[ExtensionType::TransferFeeConfig], |state| {
+            let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+            let fee = TransferFee {
+                epoch: 0.into(),
+                maximum_fee: maximum_fee.into(),
+                transfer_fee_basis_points: basis_points.into(),
+            };
+            config.older_transfer_fee = fee;
+            config.newer_transfer_fee = fee;
+        })
+    }
+
+    // Writes an initialized SPL token account holding `amount` base units.
+    pub fn set_token_account(&mut self, address: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) {
+        let state = TokenAccount {
+            mint,
+            owner,
+            amount,
+            delegate: COption::None,
+            state: AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        };
+        let mut data = vec![0; TokenAccount::LEN];
+        TokenAccount::pack(state, &mut data).unwrap();
+        self.set_token_program_account(address, data, spl_token::ID);
+    }
+
+    // The Token-2022 equivalent, with the extensions the ATA program would
+    // give an account for `mint` (a fee mint's TransferFeeAmount, which every
+    // transfer destination needs, plus ImmutableOwner).
+    pub fn set_token_2022_account(
+        &mut self,
+        address: Pubkey,
+        mint: Pubkey,
+        owner: Pubkey,
+        amount: u64,
+    ) {
+        let mint_data = self.data(&mint);
+        let mint_extensions = StateWithExtensions::<Token2022Mint>::unpack(&mint_data)
+            .unwrap()
+            .get_extension_types()
+            .unwrap();
+        let mut extensions = ExtensionType::get_required_init_account_extensions(&mint_extensions);
+        if !extensions.contains(&ExtensionType::ImmutableOwner) {
+            extensions.push(ExtensionType::ImmutableOwner);
+        }
+        let len =
+            ExtensionType::try_calculate_account_len::<Token2022Account>(&extensions).unwrap();
+        let mut data = vec![0; len];
+        let mut state =
+            StateWithExtensionsMut::<Token2022Account>::unpack_uninitialized(&mut data).unwrap();
+        for extension in extensions {
+            state.init_account_extension_from_type(extension).unwrap();
+        }
+        state.base = Token2022Account {
+            mint,
+            owner,
+            amount,
+            delegate: COption::None,
+            state: spl_token_2022::state::AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        self.set_token_program_account(address, data, spl_token_2022::ID);
+    }
+
+    fn set_token_program_account(&mut self, address: Pubkey, data: Vec<u8>, token_program: Pubkey) {
+        let account = Account {
+            lamports: self.svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: token_program,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.svm.set_account(address, account).unwrap();
+    }
+
+    // A fresh keypair with a canonical USDC ATA holding `whole_usdc`.
+    pub fn wallet(&mut self, whole_usdc: u64) -> Wallet {
+        let keypair = Keypair::new();
+        let ata = pda::member_ata(&keypair.pubkey(), &MINT);
+        self.set_token_account(ata, USDC_MINT, keypair.pubkey(), whole_usdc * ONE_USDC);
+        Wallet { keypair, ata }
+    }
+
+    // A fresh keypair with a canonical ATA for a Token-2022 `mint` holding
+    // `whole` tokens.
+    pub fn wallet_for(&mut self, mint: &TokenMint, whole: u64) -> Wallet {
+        let keypair = Keypair::new();
+        let ata = pda::member_ata(&keypair.pubkey(), mint);
+        self.set_token_2022_account(ata, mint.address, keypair.pubkey(), whole * ONE_USDC);
+        Wallet { keypair, ata }
+    }
+
+    // Adds `whole_usdc` to a fund's vault (the TS suites transfer from a treasury).
+    pub fn fund_vault(&mut self, fund: &Pubkey, whole_usdc: u64) {
+        let vault = vault_pda(fund);
+        let balance = self.token_balance(&vault);
+        self.set_token_account(vault, USDC_MINT, *fund, balance + whole_usdc * ONE_USDC);
+    }
+
+    pub fn exists(&self, address: &Pubkey) -> bool {
+        self.svm
+            .get_account(address)
+            .is_some_and(|a| a.lamports > 0)
+    }
+
+    pub fn lamports(&self, address: &Pubkey) -> u64 {
+        self.svm.get_account(address).map_or(0, |a| a.lamports)
+    }
+
+    // SPL Token or Token-2022: the base layouts are the same. With a fee mint
+    // this is what the account holds, not counting fees withheld on it.
+    pub fn token_balance(&self, address: &Pubkey) -> u64 {
+        let account = self
+            .svm
+            .get_account(address)
+            .unwrap_or_else(|| panic!("token account {address} does not exist"));
+        StateWithExtensions::<Token2022Account>::unpack(&account.data)
+            .unwrap()
+            .base
+            .amount
+    }
+
+    // Transfer fees withheld on a Token-2022 account (harvestable to the mint).
+    pub fn withheld_on_account(&self, address: &Pubkey) -> u64 {
+        let data = self.data(address);
+        let state = StateWithExtensions::<Token2022Account>::unpack(&data).unwrap();
+        state
+            .get_extension::<TransferFeeAmount>()
+            .unwrap()
+            .withheld_amount
+            .into()
+    }
+
+    // Transfer fees harvested to a fee mint.
+    pub fn withheld_on_mint(&self, mint: &Pubkey) -> u64 {
+        let data = self.data(mint);
+        let state = StateWithExtensions::<Token2022Mint>::unpack(&data).unwrap();
+        state
+            .get_extension::<TransferFeeConfig>()
+            .unwrap()
+            .withheld_amount
+            .into()
+    }
+
+    // Funds, proposals and join requests decode as the client does, so one
+    // the first release wrote reads with its missing fields as zeros.
+    pub fn fund(&self, fund: &Pubkey) -> SquadMintFund {
+        squad_mint_client::state::fund(&self.data(fund)).unwrap()
+    }
+
+    pub fn data_len(&self, address: &Pubkey) -> usize {
+        self.svm.get_account(address).map_or(0, |a| a.data.len())
+    }
+
+    pub fn proposal(&self, proposal: &Pubkey) -> Proposal {
+        squad_mint_client::state::proposal(&self.data(proposal)).unwrap()
+    }
+
+    pub fn join_request(&self, join_request: &Pubkey) -> JoinRequestCustodialWallet {
+        squad_mint_client::state::join_request(&self.data(join_request)).unwrap()
+    }
+
+    pub fn membership(&self, membership: &Pubkey) -> Membership {
+        self.anchor_account(membership)
+    }
+
+    pub fn dues(&self, fund: &Pubkey) -> DuesSchedule {
+        self.anchor_account(&pda::dues(fund))
+    }
+
+    pub fn rosca(&self, fund: &Pubkey) -> Rosca {
+        self.anchor_account(&pda::rosca(fund))
+    }
+
+    pub fn stream(&self, stream: &Pubkey) -> Stream {
+        self.anchor_account(stream)
+    }
+
+    pub fn now(&self) -> i64 {
+        self.svm.get_sysvar::<Clock>().unix_timestamp
+    }
+
+    pub fn advance_clock(&mut self, seconds: i64) {
+        let mut clock = self.svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += seconds;
+        self.svm.set_sysvar(&clock);
+    }
+
+    pub fn handle_registry(&self, handle: &str) -> HandleRegistry {
+        self.anchor_account(&handle_registry_pda(handle))
+    }
+
+    fn anchor_account<T: AccountDeserialize>(&self, address: &Pubkey) -> T {
+        T::try_deserialize(&mut self.data(address).as_slice()).unwrap()
+    }
+
+    fn data(&self, address: &Pubkey) -> Vec<u8> {
+        self.svm
+            .get_account(address)
+            .unwrap_or_else(|| panic!("account {address} does not exist"))
+            .data
+    }
+
+    // ---- Accounts the first release left behind ----------------------------
+    // The program's first release allocated each account for its own layout,
+    // none of the fields appended since. These rewrite an account field by
+    // field as that release wrote it, so the tests run against those bytes.
+
+    pub fn rewrite_fund_as_baseline(&mut self, fund: &Pubkey) {
+        let state = self.fund(fund);
+        let fields = (
+            state.owner(),
+            *state.account_handle(),
+            state.has_active_vote(),
+            state.members().to_vec(),
+            state.join_amount(),
+            state.master_nonce(),
+        );
+        self.write_baseline(
+            fund,
+            SquadMintFund::DISCRIMINATOR,
+            fields,
+            BASELINE_FUND_SIZE,
+        );
+    }
+
+    pub fn rewrite_proposal_as_baseline(&mut self, proposal: &Pubkey) {
+        let state = self.proposal(proposal);
+        let message = &state.message_data;
+        let fields = (
+            state.belongs_to_squad_mint_fund,
+            state.voted_mask,
+            state.votes,
+            (
+                message.amount,
+                message.proposer_account,
+                message.proposed_to_account,
+                message.nonce,
+            ),
+            state.did_meet_threshold,
+        );
+        self.write_baseline(
+            proposal,
+            Proposal::DISCRIMINATOR,
+            fields,
+            BASELINE_PROPOSAL_SIZE,
+        );
+    }
+
+    pub fn rewrite_join_request_as_baseline(&mut self, join_request: &Pubkey) {
+        let state = self.join_request(join_request);
+        let fields = (
+            state.request_to_join_squad_mint_fund,
+            state.request_to_join_user,
+            state.join_amount(),
+        );
+        self.write_baseline(
+            join_request,
+            JoinRequestCustodialWallet::DISCRIMINATOR,
+            fields,
+            BASELINE_JOIN_REQUEST_SIZE,
+        );
+    }
+
+    // Replaces `address` with `fields` in an allocation of `8 + space` bytes,
+    // zero-padded and holding exactly its rent.
+    fn write_baseline(
+        &mut self,
+        address: &Pubkey,
+        discriminator: &[u8],
+        fields: impl AnchorSerialize,
+        space: usize,
+    ) {
+        let mut data = discriminator.to_vec();
+        fields.serialize(&mut data).unwrap();
+        assert!(data.len() <= 8 + space, "fields overflow the allocation");
+        data.resize(8 + space, 0);
+        let account = Account {
+            lamports: self.svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: PROGRAM_ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.svm.set_account(*address, account).unwrap();
+    }
+
+    // ---- Transactions -----------------------------------------------------
+
+    // The shared fee payer always signs first; `signers` are the extra keys.
+    pub fn send(&mut self, ixs: &[Instruction], signers: &[&Keypair]) -> TransactionResult {
+        let mut all: Vec<&Keypair> = vec![&self.fee_payer];
+        all.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&self.fee_payer.pubkey()),
+            &all,
+            self.svm.latest_blockhash(),
+        );
+        let result = self.svm.send_transaction(tx);
+        // Retrying an identical instruction must not be deduplicated as a replay.
+        self.svm.expire_blockhash();
+        result
+    }
+
+    // ---- Flows (helper_function.ts equivalents) ---------------------------
+
+    pub fn initialize(&mut self, owner: &Wallet, handle: &str) -> Pubkey {
+        let ix = initialize_ix(
+            &self.fee_payer.pubkey(),
+            &owner.pubkey(),
+            handle,
+            &USDC_MINT,
+            JOIN_AMOUNT,
+            vec![],
+            0,
+        );
+        self.send(&[ix], &[&owner.keypair]).expect("initialize");
+        fund_pda(handle, &owner.pubkey())
+    }
+
+    pub fn initiate_join_request(
+        &mut self,
+        fund: &Pubkey,
+        joiner: &Wallet,
+        amount: u64,
+    ) -> TransactionResult {
+        let ix = initiate_join_request_ix(&self.fee_payer.pubkey(), fund, joiner, amount);
+        self.send(&[ix], &[&joiner.keypair])
+    }
+
+    pub fn set_invite_policy(
+        &mut self,
+        fund: &Pubkey,
+        owner: &Keypair,
+        require_invite: bool,
+        invite_root: Option<[u8; 32]>,
+    ) -> TransactionResult {
+        let ix =
+            instructions::set_invite_policy(fund, &owner.pubkey(), require_invite, invite_root);
+        self.send(&[ix], &[owner])
+    }
+
+    pub fn set_dues(
+        &mut self,
+        fund: &Pubkey,
+        owner: &Keypair,
+        amount: u64,
+        period_seconds: i64,
+    ) -> TransactionResult {
+        let ix = instructions::set_dues(
+            &self.fee_payer.pubkey(),
+            fund,
+            &owner.pubkey(),
+            &MINT,
+            amount,
+            period_seconds,
+        );
+        self.send(&[ix], &[owner])
+    }
+
+    pub fn approve_dues(&mut self, fund: &Pubkey, member: &Wallet, allowance: u64) {
+        let ix = instructions::approve_dues(&member.pubkey(), fund, &MINT, DECIMALS, allowance);
+        self.send(&[ix], &[&member.keypair]).expect("approve");
+    }
+
+    pub fn collect_dues(&mut self, fund: &Pubkey) -> TransactionResult {
+        let members = self.fund(fund).members().to_vec();
+        let ix = instructions::collect_dues(fund, &MINT, &members);
+        self.send(&[ix], &[])
+    }
+
+    pub fn start_rosca(&mut self, fund: &Pubkey, owner: &Keypair, draw: bool) -> TransactionResult {
+        let ix = instructions::start_rosca(&self.fee_payer.pubkey(), fund, &owner.pubkey(), draw);
+        self.send(&[ix], &[owner])
+    }
+
+    // Cranks the round as a third party: only the fee payer signs.
+    pub fn pay_rosca_round(&mut self, fund: &Pubkey, recipient: &Pubkey) -> TransactionResult {
+        let ix = instructions::pay_rosca_round(
+            &self.fee_payer.pubkey(),
+            fund,
+            recipient,
+            &MINT,
+            &self.treasury(),
+        );
+        self.send(&[ix], &[])
+    }
+
+    pub fn set_join_range(
+        &mut self,
+        fund: &Pubkey,
+        owner: &Keypair,
+        min_join_amount: u64,
+        max_join_amount: u64,
+    ) -> TransactionResult {
+        let ix = instructions::set_join_range(
+            fund,
+            &owner.pubkey(),
+            &MINT,
+            min_join_amount,
+            max_join_amount,
+        );
+        self.send(&[ix], &[owner])
+    }
+
+    pub fn set_open_admission(
+        &mut self,
+        fund: &Pubkey,
+        owner: &Keypair,
+        open: bool,
+    ) -> TransactionResult {
+        let ix = instructions::set_open_admission(fund, &owner.pubkey(), open);
+        self.send(&[ix], &[owner])
+    }
+
+    pub fn join_open_fund(&mut self, fund: &Pubkey, joiner: &Wallet) -> TransactionResult {
+        let ix = join_open_fund_ix(&self.fee_payer.pubkey(), fund, joiner, &self.treasury());
+        self.send(&[ix], &[&joiner.keypair])
+    }
+
+    pub fn add_member(
+        &mut self,
+        fund: &Pubkey,
+        joiner: &Pubkey,
+        owner: &Keypair,
+    ) -> TransactionResult {
+        let ix = add_member_ix(
+            &self.fee_payer.pubkey(),
+            fund,
+            joiner,
+            &owner.pubkey(),
+            &self.fee_payer.pubkey(),
+            &self.treasury(),
+        );
+        self.send(&[ix], &[owner])
+    }
+
+    // A fresh fund plus `extra_members` accepted members (each escrowing the
+    // join amount into the vault on the way in).
+    pub fn make_fund(&mut self, handle: &str, extra_members: usize) -> Fund {
+        let owner = self.wallet(2);
+        let pda = self.initialize(&owner, handle);
+        let mut members = vec![owner];
+        for _ in 0..extra_members {
+            let joiner = self.wallet(2);
+            self.initiate_join_request(&pda, &joiner, JOIN_AMOUNT)
+                .expect("initiate_join_request");
+            let owner = &members[0].keypair;
+            let ix = add_member_ix(
+                &self.fee_payer.pubkey(),
+                &pda,
+                &joiner.pubkey(),
+                &owner.pubkey(),
+                &self.fee_payer.pubkey(),
+                &self.treasury(),
+            );
+            self.send(&[ix], &[owner]).expect("add_member");
+            members.push(joiner);
+        }
+        Fund { pda, members }
+    }
+
+    // Opens a payout proposal at the fund's current nonce; the proposer's YES
+    // is recorded automatically.
+    pub fn create_proposal(
+        &mut self,
+        fund: &Pubkey,
+        proposer: &Keypair,
+        amount: u64,
+        to: &Pubkey,
+    ) -> TransactionResult {
+        let nonce = self.fund(fund).master_nonce();
+        let ix = create_proposal_ix(
+            &self.fee_payer.pubkey(),
+            fund,
+            nonce,
+            &proposer.pubkey(),
+            amount,
+            to,
+        );
+        self.send(&[ix], &[proposer])
+    }
+
+    pub fn propose_dissolution(
+        &mut self,
+        fund: &Pubkey,
+        proposer: &Keypair,
+        distribution: Distribution,
+        rent_recipient: &Pubkey,
+    ) -> TransactionResult {
+        let nonce = self.fund(fund).master_nonce();
+        let ix = instructions::propose_dissolution(
+            &self.fee_payer.pubkey(),
+            fund,
+            nonce,
+            &proposer.pubkey(),
+            distribution,
+            rent_recipient,
+        );
+        self.send(&[ix], &[proposer])
+    }
+
+    // Opens a stream proposal; the stream is at stream_pda(fund, nonce) for
+    // the fund's master_nonce before the call.
+    pub fn propose_stream(
+        &mut self,
+        fund: &Pubkey,
+        proposer: &Keypair,
+        terms: StreamTerms,
+    ) -> TransactionResult {
+        let nonce = self.fund(fund).master_nonce();
+        let ix = instructions::propose_stream(
+            &self.fee_payer.pubkey(),
+            fund,
+            nonce,
+            &proposer.pubkey(),
+            &MINT,
+            terms,
+        );
+        self.send(&[ix], &[proposer])
+    }
+
+    pub fn propose_stream_cancel(
+        &mut self,
+        fund: &Pubkey,
+        proposer: &Keypair,
+        stream: &Pubkey,
+    ) -> TransactionResult {
+        let nonce = self.fund(fund).master_nonce();
+        let ix = instructions::propose_stream_cancel(
+            &self.fee_payer.pubkey(),
+            fund,
+            nonce,
+            &proposer.pubkey(),
+            stream,
+        );
+        self.send(&[ix], &[proposer])
+    }
+
+    pub fn claim_stream(&mut self, stream: &Pubkey, recipient: &Keypair) -> TransactionResult {
+        let ix = instructions::claim_stream(stream, &recipient.pubkey(), &MINT);
+        self.send(&[ix], &[recipient])
+    }
+
+    // Closes as a third party: only the fee payer signs.
+    pub fn close_stream(&mut self, stream: &Pubkey) -> TransactionResult {
+        let state = self.stream(stream);
+        let ix = instructions::close_stream(&state.fund, state.nonce, &state.rent_payer, &MINT);
+        self.send(&[ix], &[])
+    }
+
+    // Votes on the fund's active proposal with the canonical accounts.
+    pub fn vote(&mut self, fund: &Pubkey, submitter: &Keypair, vote: bool) -> TransactionResult {
+        let ix = self.submit_and_execute_ix(fund, &submitter.pubkey(), vote);
+        self.send(&[ix], &[submitter])
+    }
+
+    pub fn submit_and_execute_ix(
+        &self,
+        fund: &Pubkey,
+        submitter: &Pubkey,
+        vote: bool,
+    ) -> Instruction {
+        let nonce = self.fund(fund).master_nonce();
+        let proposal = self.proposal(&proposal_pda(fund, nonce));
+        instructions::submit_and_execute(
+            &self.fee_payer.pubkey(),
+            fund,
+            submitter,
+            &proposal,
+            &MINT,
+            &self.treasury(),
+            vote,
+        )
+    }
+}
+
+impl Default for TestEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ---- PDAs and instruction builders -------------------------------------------
+// Thin wrappers over squad_mint_client that take the handle as a string and
+// fill in the test mint, so the tests read like helper_function.ts.
+
+pub fn encode_handle(handle: &str) -> Handle {
+    squad_mint_client::encode_handle(handle).expect("valid test handle")
+}
+
+pub fn fund_pda(handle: &str, owner: &Pubkey) -> Pubkey {
+    pda::fund(&encode_handle(handle), owner)
+}
+
+pub use pda::{
+    dues as dues_pda, join_escrow as join_escrow_pda, join_request as join_request_pda,
+    membership as membership_pda, proposal as proposal_pda, recovery as recovery_pda,
+    rosca as rosca_pda, stream as stream_pda, vault as vault_pda,
+};
+
+pub fn initialize_ix(
+    fee_payer: &Pubkey,
+    owner: &Pubkey,
+    handle: &str,
+    mint: &Pubkey,
+    join_amount: u64,
+    guardians: Vec<Pubkey>,
+    guardian_threshold: u8,
+) -> Instruction {
+    instructions::initialize(
+        fee_payer,
+        owner,
+        &encode_handle(handle),
+        &TokenMint::spl_token(*mint),
+        join_amount,
+        guardians,
+        guardian_threshold,
+    )
+}
+
+pub fn claim_handle_ix(fee_payer: &Pubkey, owner: &Pubkey, handle: &str) -> Instruction {
+    let handle = encode_handle(handle);
+    instructions::claim_handle(fee_payer, &pda::fund(&handle, owner), owner, &handle)
+}
+
+pub fn release_handle_ix(handle: &str, fund: &Pubkey, rent_payer: &Pubkey) -> Instruction {
+    instructions::release_handle(&encode_handle(handle), fund, rent_payer)
+}
+
+pub fn handle_registry_pda(handle: &str) -> Pubkey {
+    pda::handle_registry(&encode_handle(handle))
+}
+
+pub fn initiate_join_request_ix(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    joiner: &Wallet,
+    amount: u64,
+) -> Instruction {
+    instructions::initiate_join_request(fee_payer, fund, &joiner.pubkey(), &MINT, amount, None)
+}
+
+pub fn join_open_fund_ix(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    joiner: &Wallet,
+    treasury: &Pubkey,
+) -> Instruction {
+    instructions::join_open_fund(
+        fee_payer,
+        fund,
+        &joiner.pubkey(),
+        &MINT,
+        JOIN_AMOUNT,
+        treasury,
+        None,
+    )
+}
+
+pub fn invited_join_request_ix(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    joiner: &Wallet,
+    invite: Invite,
+) -> Instruction {
+    instructions::initiate_join_request(
+        fee_payer,
+        fund,
+        &joiner.pubkey(),
+        &MINT,
+        JOIN_AMOUNT,
+        Some(invite),
+    )
+}
+
+// `signer` signs the invite; the program only honours the fund owner's.
+pub fn signed_invite_ix(
+    signer: &Keypair,
+    fund: &Pubkey,
+    invitee: &Pubkey,
+    expires_at: i64,
+) -> Instruction {
+    let message = invite::message(fund, invitee, expires_at);
+    let signature = signer.sign_message(&message);
+    instructions::signed_invite(
+        &signer.pubkey(),
+        signature.as_array(),
+        fund,
+        invitee,
+        expires_at,
+    )
+}
+
+pub fn add_member_ix(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    joiner: &Pubkey,
+    owner: &Pubkey,
+    rent_payer: &Pubkey,
+    treasury: &Pubkey,
+) -> Instruction {
+    instructions::add_member(fee_payer, fund, owner, joiner, rent_payer, &MINT, treasury)
+}
+
+pub fn reject_member_ix(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    joiner: &Pubkey,
+    owner: &Pubkey,
+    rent_payer: &Pubkey,
+    treasury: &Pubkey,
+) -> Instruction {
+    instructions::reject_member(fee_payer, fund, owner, joiner, rent_payer, &MINT, treasury)
+}
+
+pub fn create_proposal_ix(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    nonce: u64,
+    proposer: &Pubkey,
+    amount: u64,
+    to: &Pubkey,
+) -> Instruction {
+    instructions::create_proposal(fee_payer, fund, nonce, proposer, &MINT, amount, to)
+}
+
+pub fn initiate_recovery_ix(
+    fee_payer: &Pubkey,
+    fund: &Pubkey,
+    guardian: &Pubkey,
+    new_owner: &Pubkey,
+) -> Instruction {
+    instructions::initiate_recovery(fee_payer, fund, guardian, new_owner)
+}
+
+// `member_atas` go in as remaining accounts, in `members` order. Takes the
+// ATAs rather than the members so tests can pass the wrong ones. For a fund
+// without a dues schedule.
+pub fn dissolve_fund_ix(
+    fund: &Pubkey,
+    rent_recipient: &Pubkey,
+    member_atas: &[Pubkey],
+) -> Instruction {
+    let mut ix = instructions::dissolve_fund(fund, rent_recipient, None, &MINT, &[]);
+    ix.accounts
+        .extend(member_atas.iter().map(|ata| AccountMeta::new(*ata, false)));
+    ix
+}
+
+// ---- Assertions --------------------------------------------------------------
+
+// Program errors surface as `Custom(code)`: 6000+ for the program's ErrorCode,
+// below that for Anchor's own constraint errors.
+pub fn assert_error(result: TransactionResult, expected: impl Into<u32>) {
+    let expected = expected.into();
+    match result {
+        Ok(_) => panic!("expected error {expected}, but the transaction succeeded"),
+        Err(failed) => match failed.err {
+            TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+                assert_eq!(code, expected, "logs: {:#?}", failed.meta.logs)
+            }
+            other => panic!(
+                "expected error {expected}, got {other:?}; logs: {:#?}",
+                failed.meta.logs
+            ),
+        },
+    }
+}
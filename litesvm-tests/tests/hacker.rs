@@ -0,0 +1,729 @@
+//! Rust port of tests/hacker_tests.ts — the red-team suite.
+//!
+//! Every test plays the attacker and proves an obvious "drain the money" move
+//! is rejected on-chain. Each builds its own throwaway fund(s) in a fresh SVM,
+//! so no state leaks between cases.
+
+use anchor_lang::{
+    error::ErrorCode as AnchorErrorCode,
+    prelude::{Clock, Pubkey},
+    solana_program::instruction::Instruction,
+    system_program, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token, associated_token::get_associated_token_address, token::spl_token,
+};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use squad_mint_client::StreamTerms;
+use squad_mint_litesvm_tests::*;
+use squad_mint_multi_sig::{accounts, instruction, Distribution, ID as PROGRAM_ID};
+
+// ============== Bad actors: non-members ==============
+
+#[test]
+fn non_member_cannot_propose_a_payout_to_themselves() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_nonmemProp", 0);
+    env.fund_vault(&fund.pda, 5);
+
+    let attacker = env.wallet(2);
+    let result = env.create_proposal(&fund.pda, &attacker.keypair, ONE_USDC, &attacker.pubkey());
+
+    assert_error(result, ErrorCode::MemberNotPartOfFund);
+    assert_eq!(env.token_balance(&vault_pda(&fund.pda)), 5 * ONE_USDC);
+}
+
+#[test]
+fn non_member_cannot_vote_on_an_active_proposal() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_nonmemVote", 1);
+    env.fund_vault(&fund.pda, 5);
+
+    let proposed_to = env.wallet(2);
+    env.create_proposal(
+        &fund.pda,
+        &fund.owner().keypair,
+        ONE_USDC,
+        &proposed_to.pubkey(),
+    )
+    .unwrap(); // 1 yes / 2 -> active
+
+    let attacker = env.wallet(2);
+    let result = env.vote(&fund.pda, &attacker.keypair, true);
+
+    assert_error(result, ErrorCode::MemberNotPartOfFund);
+    assert!(env.fund(&fund.pda).has_active_vote());
+}
+
+// ============== Bad actors: a malicious member ==============
+
+#[test]
+fn a_single_minority_member_cannot_drain_the_fund() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_minority", 2); // 3 members
+    env.fund_vault(&fund.pda, 5);
+    let vault = vault_pda(&fund.pda);
+    let attacker = &fund.members[1]; // a non-owner member, paying themselves
+
+    // Proposer auto-votes YES: 1/3 = 33% < 51%.
+    env.create_proposal(
+        &fund.pda,
+        &attacker.keypair,
+        3 * ONE_USDC,
+        &attacker.pubkey(),
+    )
+    .unwrap();
+    let vault_before = env.token_balance(&vault);
+
+    // Re-submitting their own vote is a no-op re-tally that never reaches 51%.
+    env.vote(&fund.pda, &attacker.keypair, true).unwrap();
+
+    assert!(env.fund(&fund.pda).has_active_vote());
+    assert_eq!(env.token_balance(&vault), vault_before);
+}
+
+#[test]
+fn cannot_redirect_an_approved_payout_to_an_attacker_controlled_ata() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_redirect", 1); // owner + memberA
+    env.fund_vault(&fund.pda, 5);
+    let vault = vault_pda(&fund.pda);
+
+    let proposed_to = env.wallet(2);
+    env.create_proposal(
+        &fund.pda,
+        &fund.owner().keypair,
+        2 * ONE_USDC,
+        &proposed_to.pubkey(),
+    )
+    .unwrap(); // 1/2 -> active
+
+    // memberA casts the deciding 2/2 vote but swaps in the attacker as recipient.
+    let attacker = env.wallet(2);
+    let member_a = &fund.members[1];
+    let mut ix = env.submit_and_execute_ix(&fund.pda, &member_a.pubkey(), true);
+    replace_account(&mut ix, &proposed_to.pubkey(), &attacker.pubkey());
+    replace_account(&mut ix, &proposed_to.ata, &attacker.ata);
+    let vault_before = env.token_balance(&vault);
+
+    let result = env.send(&[ix], &[&member_a.keypair]);
+
+    assert_error(result, ErrorCode::InvalidDestinationOwner);
+    assert_eq!(env.token_balance(&vault), vault_before);
+    assert_eq!(env.token_balance(&attacker.ata), 2 * ONE_USDC); // untouched
+}
+
+#[test]
+fn a_non_owner_member_cannot_add_a_colluder_to_manufacture_a_majority() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_sockpuppet", 1); // owner + memberA
+    let member_a = &fund.members[1];
+
+    let colluder = env.wallet(2);
+    env.initiate_join_request(&fund.pda, &colluder, JOIN_AMOUNT)
+        .unwrap();
+
+    // memberA is a member but NOT the owner -> acceptance must be rejected.
+    let result = env.add_member(&fund.pda, &colluder.pubkey(), &member_a.keypair);
+
+    assert_error(result, ErrorCode::CannotAddMember);
+    assert_eq!(env.fund(&fund.pda).members().len(), 2);
+}
+
+#[test]
+fn cannot_join_by_paying_less_than_the_required_join_amount() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_underpay", 0);
+
+    let attacker = env.wallet(2);
+    let result = env.initiate_join_request(&fund.pda, &attacker, ONE_USDC / 2); // fund requires 1.11
+
+    assert_error(result, ErrorCode::JoiningAmountShouldMatchTargetWallet);
+}
+
+#[test]
+fn cannot_propose_a_payout_back_to_the_fund_or_its_vault() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_selfPay", 0);
+    env.fund_vault(&fund.pda, 5);
+
+    // Neither could ever sign for an ATA derived from them.
+    for target in [fund.pda, vault_pda(&fund.pda)] {
+        let result = env.create_proposal(&fund.pda, &fund.owner().keypair, ONE_USDC, &target);
+        assert_error(result, ErrorCode::InvalidDestinationOwner);
+    }
+}
+
+// ============== Drain the whole program (cross-fund) ==============
+
+#[test]
+fn cannot_drain_another_funds_vault_by_swapping_in_a_foreign_multisig_ata() {
+    let mut env = TestEnv::new();
+    let fund_a = env.make_fund("hk_drainA", 1);
+    let fund_b = env.make_fund("hk_drainB", 0);
+    env.fund_vault(&fund_a.pda, 5);
+    env.fund_vault(&fund_b.pda, 5);
+
+    let proposed_to = env.wallet(2);
+    env.create_proposal(
+        &fund_a.pda,
+        &fund_a.owner().keypair,
+        2 * ONE_USDC,
+        &proposed_to.pubkey(),
+    )
+    .unwrap();
+
+    // Deciding vote on fund A, but point the source vault at fund B.
+    let member_a = &fund_a.members[1];
+    let fund_b_vault = vault_pda(&fund_b.pda);
+    let mut ix = env.submit_and_execute_ix(&fund_a.pda, &member_a.pubkey(), true);
+    replace_account(&mut ix, &vault_pda(&fund_a.pda), &fund_b_vault);
+    let fund_b_before = env.token_balance(&fund_b_vault);
+
+    let result = env.send(&[ix], &[&member_a.keypair]);
+
+    assert_error(result, AnchorErrorCode::ConstraintSeeds); // multisig_ata seeds bound to fund A
+    assert_eq!(env.token_balance(&fund_b_vault), fund_b_before);
+}
+
+// ============== Corrupt the setup ==============
+
+#[test]
+fn cannot_create_a_fund_on_a_non_usdc_mint() {
+    let mut env = TestEnv::new();
+    let fake_mint = Pubkey::new_unique();
+    env.create_mint(fake_mint);
+
+    let owner = Keypair::new();
+    let ix = initialize_ix(
+        &env.fee_payer.pubkey(),
+        &owner.pubkey(),
+        "hk_fakeMint",
+        &fake_mint,
+        JOIN_AMOUNT,
+        vec![],
+        0,
+    );
+    let result = env.send(&[ix], &[&owner]);
+
+    assert_error(result, ErrorCode::InvalidMint);
+}
+
+#[test]
+fn cannot_escrow_a_join_request_with_a_non_usdc_mint() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_fakeJoin", 0);
+
+    // The attacker holds a worthless token and offers it as the join deposit.
+    let fake_mint = Pubkey::new_unique();
+    env.create_mint(fake_mint);
+    let attacker = env.wallet(2);
+    let fake_ata = get_associated_token_address(&attacker.pubkey(), &fake_mint);
+    env.set_token_account(fake_ata, fake_mint, attacker.pubkey(), 2 * ONE_USDC);
+
+    let join_custodial_account = join_request_pda(&fund.pda, &attacker.pubkey());
+    let ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts::CreateJoinRequestProposal {
+            proposing_joiner: attacker.pubkey(),
+            multisig: fund.pda,
+            fee_payer: env.fee_payer.pubkey(),
+            config: squad_mint_client::pda::config(),
+            mint: fake_mint,
+            multisig_ata: vault_pda(&fund.pda),
+            join_custodial_account,
+            join_custodial_account_ata: join_escrow_pda(&join_custodial_account),
+            proposing_joiner_ata: fake_ata,
+            instructions_sysvar: anchor_lang::solana_program::sysvar::instructions::ID,
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::InitiateJoinRequest {
+            join_amount: JOIN_AMOUNT,
+            invite: None,
+        }
+        .data(),
+    };
+    let result = env.send(&[ix], &[&attacker.keypair]);
+
+    assert_error(result, ErrorCode::InvalidMint);
+}
+
+// Both mints are allowed, but the fund holds the first: a deposit in the
+// second could never be moved into its vault.
+#[test]
+fn cannot_escrow_a_join_request_in_another_allowed_mint() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_otherMint", 0);
+    let other_mint = Pubkey::new_unique();
+    env.create_mint(other_mint);
+    env.set_config(&[USDC_MINT, other_mint]);
+    let joiner = env.wallet(0);
+    let other = squad_mint_client::TokenMint::spl_token(other_mint);
+    env.set_token_account(
+        other.ata(&joiner.pubkey()),
+        other_mint,
+        joiner.pubkey(),
+        2 * ONE_USDC,
+    );
+
+    let ix = squad_mint_client::instructions::initiate_join_request(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &joiner.pubkey(),
+        &other,
+        JOIN_AMOUNT,
+        None,
+    );
+    let result = env.send(&[ix], &[&joiner.keypair]);
+
+    assert_error(result, AnchorErrorCode::ConstraintTokenMint);
+}
+
+#[test]
+fn only_the_config_admin_can_allow_a_mint() {
+    let mut env = TestEnv::new();
+    let attacker = Keypair::new();
+    let fake_mint = Pubkey::new_unique();
+
+    let ix = squad_mint_client::instructions::set_allowed_mints(
+        &attacker.pubkey(),
+        vec![USDC_MINT, fake_mint],
+    );
+    let result = env.send(&[ix], &[&attacker]);
+    assert_error(result, ErrorCode::NotConfigAdmin);
+    assert_eq!(env.config().allowed_mints(), &[USDC_MINT]);
+
+    let admin = env.config_admin.insecure_clone();
+    let ix = squad_mint_client::instructions::set_allowed_mints(
+        &admin.pubkey(),
+        vec![USDC_MINT, fake_mint],
+    );
+    env.send(&[ix], &[&admin]).unwrap();
+    assert!(env.config().allows(&fake_mint));
+}
+
+// ============== Ownership takeover ==============
+
+#[test]
+fn a_non_guardian_cannot_open_a_recovery_to_seize_owner_privileges() {
+    let mut env = TestEnv::new();
+    let guardian = Keypair::new();
+    let owner = env.wallet(2);
+    let ix = initialize_ix(
+        &env.fee_payer.pubkey(),
+        &owner.pubkey(),
+        "hk_recovery",
+        &USDC_MINT,
+        JOIN_AMOUNT,
+        vec![guardian.pubkey()],
+        1,
+    );
+    env.send(&[ix], &[&owner.keypair]).unwrap();
+    let fund = fund_pda("hk_recovery", &owner.pubkey());
+
+    let attacker = Keypair::new();
+    let ix = initiate_recovery_ix(
+        &env.fee_payer.pubkey(),
+        &fund,
+        &attacker.pubkey(),
+        &attacker.pubkey(),
+    );
+    let result = env.send(&[ix], &[&attacker]);
+
+    assert_error(result, ErrorCode::NotAGuardian);
+    assert!(!env.exists(&recovery_pda(&fund)));
+    assert_eq!(env.fund(&fund).effective_owner(), owner.pubkey());
+}
+
+// ============== Invites ==============
+
+#[test]
+fn a_member_cannot_sign_invites_to_an_invite_only_fund() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_invite", 1);
+    let member_a = &fund.members[1];
+    env.set_invite_policy(&fund.pda, &fund.owner().keypair, true, None)
+        .unwrap();
+
+    // memberA's signature verifies, but memberA is not the owner.
+    let stranger = env.wallet(2);
+    let invite = signed_invite_ix(&member_a.keypair, &fund.pda, &stranger.pubkey(), i64::MAX);
+    let join = invited_join_request_ix(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &stranger,
+        Invite::Signed {
+            expires_at: i64::MAX,
+        },
+    );
+    let result = env.send(&[invite, join], &[&stranger.keypair]);
+
+    assert_error(result, ErrorCode::InvalidInvite);
+    assert!(!env.exists(&join_request_pda(&fund.pda, &stranger.pubkey())));
+}
+
+#[test]
+fn an_invite_admits_only_its_invitee_and_only_until_it_expires() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_inviteFwd", 0);
+    let owner = &fund.owner().keypair;
+    env.set_invite_policy(&fund.pda, owner, true, None).unwrap();
+    let (invitee, stranger) = (env.wallet(2), env.wallet(2));
+    let signed = |expires_at| Invite::Signed { expires_at };
+
+    // The invitee's invite, presented by someone else.
+    let invite = signed_invite_ix(owner, &fund.pda, &invitee.pubkey(), i64::MAX);
+    let join = invited_join_request_ix(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &stranger,
+        signed(i64::MAX),
+    );
+    let result = env.send(&[invite, join], &[&stranger.keypair]);
+    assert_error(result, ErrorCode::InvalidInvite);
+
+    // The invitee's own invite, past its expiry.
+    let mut clock = env.svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = 2_000;
+    env.svm.set_sysvar(&clock);
+    let invite = signed_invite_ix(owner, &fund.pda, &invitee.pubkey(), 1_000);
+    let join = invited_join_request_ix(&env.fee_payer.pubkey(), &fund.pda, &invitee, signed(1_000));
+    let result = env.send(&[invite, join], &[&invitee.keypair]);
+    assert_error(result, ErrorCode::InviteExpired);
+
+    // A signed invite with no signature instruction in front of it.
+    let join = invited_join_request_ix(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &invitee,
+        signed(i64::MAX),
+    );
+    let result = env.send(&[join], &[&invitee.keypair]);
+    assert_error(result, ErrorCode::InvalidInvite);
+}
+
+#[test]
+fn a_member_cannot_open_the_fund_to_admit_colluders() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_open", 1);
+    let member_a = &fund.members[1];
+
+    let result = env.set_open_admission(&fund.pda, &member_a.keypair, true);
+    assert_error(result, ErrorCode::CannotAddMember);
+    let colluder = env.wallet(2);
+    assert_error(
+        env.join_open_fund(&fund.pda, &colluder),
+        ErrorCode::FundNotOpen,
+    );
+}
+
+#[test]
+fn an_open_invite_only_fund_still_needs_an_invite() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_openInvite", 0);
+    let owner = &fund.owner().keypair;
+    env.set_invite_policy(&fund.pda, owner, true, None).unwrap();
+    env.set_open_admission(&fund.pda, owner, true).unwrap();
+
+    let stranger = env.wallet(2);
+    assert_error(
+        env.join_open_fund(&fund.pda, &stranger),
+        ErrorCode::InviteRequired,
+    );
+    assert_eq!(env.fund(&fund.pda).members().len(), 1);
+}
+
+// ============== Handle registry ==============
+
+#[test]
+fn a_member_cannot_claim_the_funds_handle_for_it() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_squat", 1);
+    let member_a = &fund.members[1];
+
+    let handle = encode_handle("hk_squat");
+    let ix = squad_mint_client::instructions::claim_handle(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &member_a.pubkey(),
+        &handle,
+    );
+    let result = env.send(&[ix], &[&member_a.keypair]);
+
+    assert_error(result, ErrorCode::CannotAddMember);
+    assert!(!env.exists(&handle_registry_pda("hk_squat")));
+}
+
+// ============== Dissolution ==============
+
+#[test]
+fn cannot_siphon_a_dissolution_payout_by_substituting_a_members_token_account() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_dissolve", 1); // owner + memberA
+    env.fund_vault(&fund.pda, 5);
+    let owner = fund.owner();
+    let member_a = &fund.members[1];
+
+    env.propose_dissolution(
+        &fund.pda,
+        &owner.keypair,
+        Distribution::Equal,
+        &owner.pubkey(),
+    )
+    .unwrap();
+    env.vote(&fund.pda, &member_a.keypair, true).unwrap();
+
+    // The owner cranks the approved dissolution but routes memberA's share to
+    // a token account they control.
+    let attacker_sink = env.wallet(1);
+    let ix = dissolve_fund_ix(&fund.pda, &owner.pubkey(), &[owner.ata, attacker_sink.ata]);
+    let result = env.send(&[ix], &[]);
+
+    assert_error(result, ErrorCode::InvalidMemberTokenAccount);
+    assert!(env.token_balance(&vault_pda(&fund.pda)) > 0);
+}
+
+// ============== Dues ==============
+
+#[test]
+fn a_member_cannot_set_dues_that_pull_from_the_others() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_dues", 1);
+    let member_a = &fund.members[1];
+
+    let result = env.set_dues(&fund.pda, &member_a.keypair, ONE_USDC, 24 * 60 * 60);
+
+    assert_error(result, ErrorCode::CannotAddMember);
+    assert!(!env.exists(&dues_pda(&fund.pda)));
+}
+
+#[test]
+fn dues_cannot_be_pulled_from_an_account_that_is_not_the_members() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_duesSwap", 1);
+    let owner = fund.owner();
+    env.set_dues(&fund.pda, &owner.keypair, ONE_USDC / 5, 24 * 60 * 60)
+        .unwrap();
+
+    // A former member's allowance is still standing; the crank substitutes
+    // their account for memberA's to charge them on memberA's behalf.
+    let victim = env.wallet(2);
+    env.approve_dues(&fund.pda, &victim, ONE_USDC);
+    let mut ix = squad_mint_client::instructions::collect_dues(
+        &fund.pda,
+        &MINT,
+        &[owner.pubkey(), fund.members[1].pubkey()],
+    );
+    replace_account(&mut ix, &fund.members[1].ata, &victim.ata);
+    let result = env.send(&[ix], &[]);
+
+    assert_error(result, ErrorCode::InvalidMemberTokenAccount);
+    assert_eq!(env.token_balance(&victim.ata), 2 * ONE_USDC);
+}
+
+// ============== Rotating savings ==============
+
+#[test]
+fn a_member_cannot_take_someone_elses_rosca_round() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_roscaTurn", 1);
+    let owner = fund.owner();
+    let member_a = &fund.members[1];
+    for member in &fund.members {
+        env.approve_dues(&fund.pda, member, ONE_USDC);
+    }
+    env.set_dues(&fund.pda, &owner.keypair, ONE_USDC / 5, 24 * 60 * 60)
+        .unwrap();
+    env.start_rosca(&fund.pda, &owner.keypair, false).unwrap();
+    env.collect_dues(&fund.pda).unwrap();
+
+    // Round one is the owner's. memberA names themselves as the recipient...
+    let result = env.pay_rosca_round(&fund.pda, &member_a.pubkey());
+    assert_error(result, ErrorCode::NotRoscaRecipient);
+
+    // ...or names the owner but swaps in their own token account.
+    let mut ix = squad_mint_client::instructions::pay_rosca_round(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &owner.pubkey(),
+        &MINT,
+        &env.treasury(),
+    );
+    replace_account(&mut ix, &owner.ata, &member_a.ata);
+    let before = env.token_balance(&member_a.ata);
+    let result = env.send(&[ix], &[]);
+
+    assert_error(result, ErrorCode::NotRoscaRecipient);
+    assert_eq!(env.token_balance(&member_a.ata), before);
+    assert_eq!(env.rosca(&fund.pda).round, 0);
+}
+
+#[test]
+fn a_member_cannot_start_a_rosca_in_an_order_of_their_choosing() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_roscaOrder", 1);
+    let owner = fund.owner();
+    env.set_dues(&fund.pda, &owner.keypair, ONE_USDC / 5, 24 * 60 * 60)
+        .unwrap();
+
+    let result = env.start_rosca(&fund.pda, &fund.members[1].keypair, true);
+
+    assert_error(result, ErrorCode::CannotAddMember);
+    assert!(!env.exists(&rosca_pda(&fund.pda)));
+}
+
+// ============== Streams ==============
+
+// A funded 30-day stream from `fund` to a fresh contributor.
+fn funded_stream(env: &mut TestEnv, fund: &Fund) -> (Pubkey, Wallet) {
+    env.fund_vault(&fund.pda, 10);
+    let contributor = env.wallet(0);
+    let start = env.now();
+    let terms = StreamTerms {
+        recipient: contributor.pubkey(),
+        amount: 3 * ONE_USDC,
+        start,
+        cliff: start,
+        end: start + 30 * 24 * 60 * 60,
+    };
+    let stream = stream_pda(&fund.pda, env.fund(&fund.pda).master_nonce());
+    env.propose_stream(&fund.pda, &fund.owner().keypair, terms)
+        .unwrap();
+    env.vote(&fund.pda, &fund.members[1].keypair, true).unwrap();
+    (stream, contributor)
+}
+
+#[test]
+fn only_the_recipient_can_claim_a_stream() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_streamClaim", 1);
+    let (stream, _) = funded_stream(&mut env, &fund);
+    env.advance_clock(15 * 24 * 60 * 60);
+
+    let attacker = env.wallet(0);
+    let result = env.claim_stream(&stream, &attacker.keypair);
+
+    assert_error(result, ErrorCode::InvalidDestinationOwner);
+    assert_eq!(env.token_balance(&MINT.ata(&stream)), 3 * ONE_USDC);
+}
+
+#[test]
+fn a_funded_stream_cannot_be_closed_to_strand_its_escrow() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_streamClose", 1);
+    let (stream, _) = funded_stream(&mut env, &fund);
+
+    let result = env.close_stream(&stream);
+
+    assert_error(result, ErrorCode::StreamNotSettled);
+    assert!(env.exists(&stream));
+}
+
+#[test]
+fn the_deciding_voter_cannot_swallow_a_stream_cancel_by_leaving_out_the_stream() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_streamCancel", 1);
+    let (stream, _) = funded_stream(&mut env, &fund);
+    env.propose_stream_cancel(&fund.pda, &fund.owner().keypair, &stream)
+        .unwrap();
+
+    // memberA sides with the recipient: votes YES so the proposal resolves,
+    // but passes "no stream" so nothing would be cancelled.
+    let member_a = &fund.members[1].keypair;
+    let mut ix = env.submit_and_execute_ix(&fund.pda, &member_a.pubkey(), true);
+    let meta = ix.accounts.last_mut().unwrap();
+    assert_eq!(meta.pubkey, stream);
+    meta.pubkey = PROGRAM_ID;
+    meta.is_writable = false;
+    let result = env.send(&[ix], &[member_a]);
+
+    assert_error(result, ErrorCode::StreamMismatch);
+    assert!(env.stream(&stream).cancelled_at.is_none());
+    assert!(env.fund(&fund.pda).has_active_vote());
+}
+
+// ============== Protocol fee ==============
+
+#[test]
+fn only_the_config_admin_can_set_fees_and_only_up_to_the_cap() {
+    let mut env = TestEnv::new();
+    let attacker = Keypair::new();
+
+    let ix = squad_mint_client::instructions::set_protocol_fee(
+        &attacker.pubkey(),
+        &attacker.pubkey(),
+        0,
+        200,
+        0,
+    );
+    let result = env.send(&[ix], &[&attacker]);
+    assert_error(result, ErrorCode::NotConfigAdmin);
+
+    // Not even the admin can take more than the hard-coded maximums.
+    let treasury = env.treasury();
+    let result = env.set_protocol_fee(&treasury, 0, 201, 0);
+    assert_error(result, ErrorCode::InvalidProtocolFee);
+    let result = env.set_protocol_fee(&treasury, 0, 0, ONE_USDC + 1);
+    assert_error(result, ErrorCode::InvalidProtocolFee);
+    assert_eq!(env.config().payout_fee_bps(), 0);
+}
+
+#[test]
+fn cannot_redirect_the_protocol_fee_to_another_treasury() {
+    let mut env = TestEnv::new();
+    let treasury = env.treasury();
+    env.set_protocol_fee(&treasury, 0, 200, 0).unwrap();
+    let fund = env.make_fund("hk_feeGrab", 1);
+    env.fund_vault(&fund.pda, 5);
+    let member = &fund.members[1];
+    env.create_proposal(&fund.pda, &member.keypair, ONE_USDC, &member.pubkey())
+        .unwrap();
+
+    let attacker = env.wallet(0);
+    let mut ix = env.submit_and_execute_ix(&fund.pda, &fund.owner().pubkey(), true);
+    replace_account(&mut ix, &treasury, &attacker.pubkey());
+    replace_account(&mut ix, &env.treasury_ata(), &attacker.ata);
+    let result = env.send(&[ix], &[&fund.owner().keypair]);
+
+    assert_error(result, ErrorCode::InvalidTreasury);
+    assert_eq!(env.token_balance(&attacker.ata), 0);
+}
+
+// ============== Rent harvesting (audit L-1) ==============
+
+#[test]
+fn the_closing_fee_payer_cannot_redirect_a_join_requests_rent_to_themselves() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("hk_rentGrab", 0);
+    let joiner = env.wallet(2);
+    env.initiate_join_request(&fund.pda, &joiner, JOIN_AMOUNT)
+        .unwrap();
+
+    let harvester = Keypair::new();
+    let ix = reject_member_ix(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &joiner.pubkey(),
+        &fund.owner().pubkey(),
+        &harvester.pubkey(),
+        &env.treasury(),
+    );
+    let result = env.send(&[ix], &[&fund.owner().keypair]);
+
+    assert_error(result, ErrorCode::RentPayerMismatch);
+    assert_eq!(env.lamports(&harvester.pubkey()), 0);
+}
+
+// Swaps one account key in an instruction, keeping its signer/writable flags.
+fn replace_account(ix: &mut Instruction, from: &Pubkey, to: &Pubkey) {
+    let meta = ix
+        .accounts
+        .iter_mut()
+        .find(|m| m.pubkey == *from)
+        .unwrap_or_else(|| panic!("{from} is not in the instruction"));
+    meta.pubkey = *to;
+}
@@ -0,0 +1,742 @@
+//! Happy-path lifecycle: join, accept/reject, propose, vote, pay out.
+
+use anchor_lang::prelude::Pubkey;
+use solana_signer::Signer;
+use squad_mint_client::{Distribution, SquadMintFund, StreamTerms};
+use squad_mint_litesvm_tests::*;
+
+#[test]
+fn accepted_member_deposit_lands_in_the_vault() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("lc_join", 1);
+
+    assert_eq!(env.token_balance(&vault_pda(&fund.pda)), JOIN_AMOUNT);
+    let state = env.fund(&fund.pda);
+    assert_eq!(
+        state.members(),
+        &[fund.owner().pubkey(), fund.members[1].pubkey()]
+    );
+    assert_eq!(state.contribution_of(1), JOIN_AMOUNT);
+    assert!(!env.exists(&join_request_pda(&fund.pda, &fund.members[1].pubkey())));
+}
+
+#[test]
+fn rejected_join_request_is_refunded_in_full() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("lc_reject", 0);
+    let joiner = env.wallet(2);
+    env.initiate_join_request(&fund.pda, &joiner, JOIN_AMOUNT)
+        .unwrap();
+    assert_eq!(env.token_balance(&joiner.ata), 2 * ONE_USDC - JOIN_AMOUNT);
+
+    let ix = reject_member_ix(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &joiner.pubkey(),
+        &fund.owner().pubkey(),
+        &env.fee_payer.pubkey(),
+        &env.treasury(),
+    );
+    env.send(&[ix], &[&fund.owner().keypair]).unwrap();
+
+    assert_eq!(env.token_balance(&joiner.ata), 2 * ONE_USDC);
+    assert_eq!(env.fund(&fund.pda).members().len(), 1);
+}
+
+#[test]
+fn majority_yes_pays_out_and_closes_the_proposal() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("lc_payout", 2); // 3 members
+    env.fund_vault(&fund.pda, 5);
+    let vault = vault_pda(&fund.pda);
+    let vault_before = env.token_balance(&vault);
+
+    // A recipient with no token account yet: the ATA is created on execution.
+    let recipient = solana_keypair::Keypair::new().pubkey();
+    env.create_proposal(&fund.pda, &fund.owner().keypair, 2 * ONE_USDC, &recipient)
+        .unwrap();
+    let proposal = proposal_pda(&fund.pda, 0);
+    let recipient_ata =
+        anchor_spl::associated_token::get_associated_token_address(&recipient, &USDC_MINT);
+    assert!(!env.exists(&recipient_ata));
+
+    env.vote(&fund.pda, &fund.members[1].keypair, true).unwrap(); // 2/3 >= 51%
+
+    assert_eq!(env.token_balance(&recipient_ata), 2 * ONE_USDC);
+    assert_eq!(env.token_balance(&vault), vault_before - 2 * ONE_USDC);
+    assert!(!env.exists(&proposal));
+    let state = env.fund(&fund.pda);
+    assert!(!state.has_active_vote());
+    assert_eq!(state.master_nonce(), 1);
+}
+
+#[test]
+fn half_no_rejects_without_moving_funds() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("lc_rejectVote", 1); // 2 members
+    env.fund_vault(&fund.pda, 5);
+    let vault = vault_pda(&fund.pda);
+    let vault_before = env.token_balance(&vault);
+
+    let recipient = env.wallet(0);
+    env.create_proposal(
+        &fund.pda,
+        &fund.owner().keypair,
+        ONE_USDC,
+        &recipient.pubkey(),
+    )
+    .unwrap();
+    env.vote(&fund.pda, &fund.members[1].keypair, false)
+        .unwrap(); // 1-1 split rejects
+
+    assert_eq!(env.token_balance(&vault), vault_before);
+    assert_eq!(env.token_balance(&recipient.ata), 0);
+    assert!(!env.fund(&fund.pda).has_active_vote());
+}
+
+#[test]
+fn protocol_fees_are_taken_out_of_the_amount_moved() {
+    let mut env = TestEnv::new();
+    let treasury = solana_keypair::Keypair::new().pubkey();
+    env.set_protocol_fee(&treasury, 100, 50, 100_000).unwrap(); // 1%, 0.5%, 0.1 USDC
+    let treasury_ata = env.treasury_ata();
+
+    // Join: 1% of the deposit, the member is credited with the rest.
+    let fund = env.make_fund("lc_fees", 1);
+    let join_fee = JOIN_AMOUNT / 100;
+    assert_eq!(env.token_balance(&treasury_ata), join_fee);
+    assert_eq!(
+        env.token_balance(&vault_pda(&fund.pda)),
+        JOIN_AMOUNT - join_fee
+    );
+    assert_eq!(
+        env.fund(&fund.pda).contribution_of(1),
+        JOIN_AMOUNT - join_fee
+    );
+
+    // Reject: the flat fee is kept, the rest refunded.
+    let joiner = env.wallet(2);
+    env.initiate_join_request(&fund.pda, &joiner, JOIN_AMOUNT)
+        .unwrap();
+    let ix = reject_member_ix(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &joiner.pubkey(),
+        &fund.owner().pubkey(),
+        &env.fee_payer.pubkey(),
+        &env.treasury(),
+    );
+    env.send(&[ix], &[&fund.owner().keypair]).unwrap();
+    assert_eq!(env.token_balance(&joiner.ata), 2 * ONE_USDC - 100_000);
+    assert_eq!(env.token_balance(&treasury_ata), join_fee + 100_000);
+
+    // Payout: the vault pays the amount voted, the recipient gets it less 0.5%.
+    env.fund_vault(&fund.pda, 5);
+    let vault_before = env.token_balance(&vault_pda(&fund.pda));
+    let recipient = env.wallet(0);
+    env.create_proposal(
+        &fund.pda,
+        &fund.owner().keypair,
+        2 * ONE_USDC,
+        &recipient.pubkey(),
+    )
+    .unwrap();
+    env.vote(&fund.pda, &fund.members[1].keypair, true).unwrap();
+    assert_eq!(env.token_balance(&recipient.ata), 2 * ONE_USDC - 10_000);
+    assert_eq!(
+        env.token_balance(&vault_pda(&fund.pda)),
+        vault_before - 2 * ONE_USDC
+    );
+    assert_eq!(
+        env.token_balance(&treasury_ata),
+        join_fee + 100_000 + 10_000
+    );
+}
+
+#[test]
+fn memberships_index_a_wallets_funds_until_it_leaves() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("lc_index", 1);
+    let owner = fund.owner();
+    let member = &fund.members[1];
+
+    for wallet in [owner, member] {
+        let membership = env.membership(&membership_pda(&fund.pda, &wallet.pubkey()));
+        assert_eq!(membership.member, wallet.pubkey());
+        assert_eq!(membership.fund, fund.pda);
+        assert_eq!(membership.rent_payer, env.fee_payer.pubkey());
+        assert_eq!(membership.trailer, AccountTrailer::CURRENT);
+    }
+
+    // A member without an entry (admitted before the index) can be backfilled.
+    let member_membership = membership_pda(&fund.pda, &member.pubkey());
+    env.svm
+        .set_account(member_membership, Default::default())
+        .unwrap();
+    let ix = squad_mint_client::instructions::register_membership(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &member.pubkey(),
+    );
+    env.send(&[ix], &[]).unwrap();
+    assert_eq!(env.membership(&member_membership).member, member.pubkey());
+
+    // Only outsiders can be registered, and only leavers' entries closed.
+    let outsider = env.wallet(1);
+    let ix = squad_mint_client::instructions::register_membership(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &outsider.pubkey(),
+    );
+    assert_error(env.send(&[ix], &[]), ErrorCode::MemberNotPartOfFund);
+    let close = squad_mint_client::instructions::close_membership(
+        &fund.pda,
+        &member.pubkey(),
+        &env.fee_payer.pubkey(),
+    );
+    assert_error(
+        env.send(&[close.clone()], &[]),
+        ErrorCode::MembershipStillActive,
+    );
+
+    // Once the fund is dissolved, anyone can close the entries.
+    env.propose_dissolution(
+        &fund.pda,
+        &owner.keypair,
+        Distribution::Equal,
+        &owner.pubkey(),
+    )
+    .unwrap();
+    env.vote(&fund.pda, &member.keypair, true).unwrap();
+    let ix = dissolve_fund_ix(&fund.pda, &owner.pubkey(), &[owner.ata, member.ata]);
+    env.send(&[ix], &[]).unwrap();
+    assert!(!env.exists(&fund.pda));
+
+    env.send(&[close], &[]).unwrap();
+    assert!(!env.exists(&member_membership));
+}
+
+#[test]
+fn a_claimed_handle_names_one_fund_until_that_fund_closes() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("lc_name", 1);
+    let owner = fund.owner();
+    let member = &fund.members[1];
+    let ix = claim_handle_ix(&env.fee_payer.pubkey(), &owner.pubkey(), "lc_name");
+    env.send(&[ix], &[&owner.keypair]).unwrap();
+    assert_eq!(env.handle_registry("lc_name").fund, fund.pda);
+
+    // The same handle under another owner is still a fund, but not the name.
+    let rival = env.wallet(2);
+    let rival_fund = env.initialize(&rival, "lc_name");
+    let claim = claim_handle_ix(&env.fee_payer.pubkey(), &rival.pubkey(), "lc_name");
+    assert!(env.send(&[claim.clone()], &[&rival.keypair]).is_err());
+    let release = release_handle_ix("lc_name", &fund.pda, &env.fee_payer.pubkey());
+    assert_error(
+        env.send(&[release.clone()], &[]),
+        ErrorCode::HandleStillClaimed,
+    );
+
+    // Dissolving the fund lets anyone release the name for the next claimant.
+    env.propose_dissolution(
+        &fund.pda,
+        &owner.keypair,
+        Distribution::Equal,
+        &owner.pubkey(),
+    )
+    .unwrap();
+    env.vote(&fund.pda, &member.keypair, true).unwrap();
+    let ix = dissolve_fund_ix(&fund.pda, &owner.pubkey(), &[owner.ata, member.ata]);
+    env.send(&[ix], &[]).unwrap();
+    env.send(&[release], &[]).unwrap();
+    assert!(!env.exists(&handle_registry_pda("lc_name")));
+
+    env.send(&[claim], &[&rival.keypair]).unwrap();
+    assert_eq!(env.handle_registry("lc_name").fund, rival_fund);
+}
+
+#[test]
+fn an_invite_only_fund_takes_signed_invites_and_allowlisted_wallets() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("lc_invite", 0);
+    let owner = &fund.owner().keypair;
+    let (invitee, listed, stranger) = (env.wallet(2), env.wallet(2), env.wallet(2));
+    let allowlist = [listed.pubkey(), Pubkey::new_unique(), Pubkey::new_unique()]
+        .map(|wallet| squad_mint_client::invite::leaf(&wallet));
+    let root = squad_mint_client::invite::root(&allowlist);
+    env.set_invite_policy(&fund.pda, owner, true, Some(root))
+        .unwrap();
+    assert!(env.fund(&fund.pda).requires_invite());
+    assert_eq!(env.fund(&fund.pda).invite_root(), Some(root));
+
+    let result = env.initiate_join_request(&fund.pda, &stranger, JOIN_AMOUNT);
+    assert_error(result, ErrorCode::InviteRequired);
+
+    // Signed by the owner, for this invitee.
+    let invite = signed_invite_ix(owner, &fund.pda, &invitee.pubkey(), i64::MAX);
+    let join = invited_join_request_ix(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &invitee,
+        Invite::Signed {
+            expires_at: i64::MAX,
+        },
+    );
+    env.send(&[invite, join], &[&invitee.keypair]).unwrap();
+
+    // On the allowlist: the proof is built off-chain from the same list.
+    let proof = squad_mint_client::invite::proof(&allowlist, 0);
+    let join = invited_join_request_ix(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &listed,
+        Invite::Allowlisted { proof },
+    );
+    env.send(&[join], &[&listed.keypair]).unwrap();
+
+    // Off the list, whatever proof is presented.
+    let proof = squad_mint_client::invite::proof(&allowlist, 2);
+    let join = invited_join_request_ix(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &stranger,
+        Invite::Allowlisted { proof },
+    );
+    assert_error(
+        env.send(&[join], &[&stranger.keypair]),
+        ErrorCode::InvalidInvite,
+    );
+
+    // Opening the fund again drops the requirement.
+    env.set_invite_policy(&fund.pda, owner, false, None)
+        .unwrap();
+    env.initiate_join_request(&fund.pda, &stranger, JOIN_AMOUNT)
+        .unwrap();
+}
+
+#[test]
+fn an_open_fund_admits_joiners_in_one_instruction() {
+    let mut env = TestEnv::new();
+    let treasury = solana_keypair::Keypair::new().pubkey();
+    env.set_protocol_fee(&treasury, 100, 0, 0).unwrap(); // 1% join fee
+    let fund = env.make_fund("lc_open", 0);
+    let owner = &fund.owner().keypair;
+    env.set_open_admission(&fund.pda, owner, true).unwrap();
+    assert!(env.fund(&fund.pda).open_admission());
+
+    // No escrow: the deposit, less the join fee, is in the vault at once.
+    let joiner = env.wallet(2);
+    env.join_open_fund(&fund.pda, &joiner).unwrap();
+    let join_fee = JOIN_AMOUNT / 100;
+    assert_eq!(env.token_balance(&env.treasury_ata()), join_fee);
+    assert_eq!(
+        env.token_balance(&vault_pda(&fund.pda)),
+        JOIN_AMOUNT - join_fee
+    );
+    assert_eq!(env.token_balance(&joiner.ata), 2 * ONE_USDC - JOIN_AMOUNT);
+    let state = env.fund(&fund.pda);
+    assert_eq!(state.members(), &[fund.owner().pubkey(), joiner.pubkey()]);
+    assert_eq!(state.contribution_of(1), JOIN_AMOUNT - join_fee);
+    assert!(!env.exists(&join_request_pda(&fund.pda, &joiner.pubkey())));
+    let membership = env.membership(&membership_pda(&fund.pda, &joiner.pubkey()));
+    assert_eq!(membership.member, joiner.pubkey());
+
+    // Escrowed requests are refused while the fund is open, and instant
+    // joins once it is closed again.
+    let late = env.wallet(2);
+    let result = env.initiate_join_request(&fund.pda, &late, JOIN_AMOUNT);
+    assert_error(result, ErrorCode::FundIsOpen);
+    env.set_open_admission(&fund.pda, owner, false).unwrap();
+    assert_error(env.join_open_fund(&fund.pda, &late), ErrorCode::FundNotOpen);
+    env.initiate_join_request(&fund.pda, &late, JOIN_AMOUNT)
+        .unwrap();
+}
+
+#[test]
+fn an_open_fund_stops_admitting_when_full() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("lc_open_full", 0);
+    env.set_open_admission(&fund.pda, &fund.owner().keypair, true)
+        .unwrap();
+    for _ in 1..SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE {
+        let joiner = env.wallet(2);
+        env.join_open_fund(&fund.pda, &joiner).unwrap();
+    }
+    let joiner = env.wallet(2);
+    assert_error(
+        env.join_open_fund(&fund.pda, &joiner),
+        ErrorCode::MaxMembersReached,
+    );
+    // Nor can a member join twice.
+    let member = &fund.members[0];
+    assert_error(
+        env.join_open_fund(&fund.pda, member),
+        ErrorCode::DuplicateMember,
+    );
+}
+
+#[test]
+fn joiners_contribute_any_amount_in_the_funds_join_range() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("lc_range", 0);
+    let owner = &fund.owner().keypair;
+    let max = 3 * ONE_USDC;
+    env.set_join_range(&fund.pda, owner, JOIN_AMOUNT, max)
+        .unwrap();
+    assert_eq!(env.fund(&fund.pda).max_join_amount(), max);
+
+    let (small, large, greedy) = (env.wallet(5), env.wallet(5), env.wallet(5));
+    for (joiner, amount) in [(&small, JOIN_AMOUNT), (&large, max)] {
+        env.initiate_join_request(&fund.pda, joiner, amount)
+            .unwrap();
+        env.add_member(&fund.pda, &joiner.pubkey(), owner).unwrap();
+    }
+    let result = env.initiate_join_request(&fund.pda, &greedy, max + 1);
+    assert_error(result, ErrorCode::JoiningAmountShouldMatchTargetWallet);
+    let result = env.initiate_join_request(&fund.pda, &greedy, JOIN_AMOUNT - 1);
+    assert_error(result, ErrorCode::JoiningAmountShouldMatchTargetWallet);
+
+    // Each member is credited with what they put in.
+    let state = env.fund(&fund.pda);
+    assert_eq!(state.contribution_of(1), JOIN_AMOUNT);
+    assert_eq!(state.contribution_of(2), max);
+    assert_eq!(env.token_balance(&vault_pda(&fund.pda)), JOIN_AMOUNT + max);
+
+    // A request left outside a narrowed range can't be accepted, and anyone
+    // can return it.
+    env.initiate_join_request(&fund.pda, &greedy, max).unwrap();
+    env.set_join_range(&fund.pda, owner, JOIN_AMOUNT, JOIN_AMOUNT)
+        .unwrap();
+    assert_error(
+        env.add_member(&fund.pda, &greedy.pubkey(), owner),
+        ErrorCode::JoinAmountMismatch,
+    );
+    let ix = squad_mint_client::instructions::cancel_stale_join_request(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &greedy.pubkey(),
+        &env.fee_payer.pubkey(),
+        &MINT,
+    );
+    env.send(&[ix], &[]).unwrap();
+    assert_eq!(env.token_balance(&greedy.ata), 5 * ONE_USDC);
+}
+
+#[test]
+fn dues_are_pulled_each_period_and_shortfalls_are_arrears() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("lc_dues", 2);
+    let (owner, member_a, member_b) = (&fund.members[0], &fund.members[1], &fund.members[2]);
+    let dues = ONE_USDC / 5;
+    let month = 30 * 24 * 60 * 60;
+    env.approve_dues(&fund.pda, owner, ONE_USDC);
+    env.approve_dues(&fund.pda, member_a, 2 * dues);
+    env.set_dues(&fund.pda, &owner.keypair, dues, month)
+        .unwrap();
+    let vault = vault_pda(&fund.pda);
+    let deposits = 2 * JOIN_AMOUNT;
+
+    // The first period is due at once; memberB approved nothing.
+    env.collect_dues(&fund.pda).unwrap();
+    assert_eq!(env.token_balance(&vault), deposits + 2 * dues);
+    assert_eq!(env.dues(&fund.pda).periods_paid, [1, 1, 0]);
+    assert_eq!(
+        env.dues(&fund.pda).arrears,
+        squad_mint_client::vote::member_bit(2)
+    );
+
+    // Cranking again within the period takes nothing more.
+    env.collect_dues(&fund.pda).unwrap();
+    assert_eq!(env.token_balance(&vault), deposits + 2 * dues);
+
+    // memberB catches up on both periods; memberA's allowance runs out.
+    env.advance_clock(month);
+    env.approve_dues(&fund.pda, member_b, ONE_USDC);
+    env.collect_dues(&fund.pda).unwrap();
+    assert_eq!(env.dues(&fund.pda).periods_paid, [2, 2, 2]);
+    assert_eq!(env.dues(&fund.pda).arrears, 0);
+
+    env.advance_clock(month);
+    env.collect_dues(&fund.pda).unwrap();
+    let schedule = env.dues(&fund.pda);
+    assert_eq!(schedule.periods_paid, [3, 2, 3]);
+    assert!(schedule.in_arrears(1));
+    assert_eq!(env.token_balance(&vault), deposits + 8 * dues);
+
+    // Dues count toward each member's contribution.
+    let state = env.fund(&fund.pda);
+    assert_eq!(state.contribution_of(0), 3 * dues);
+    assert_eq!(state.contribution_of(1), JOIN_AMOUNT + 2 * dues);
+    assert_eq!(state.contribution_of(2), JOIN_AMOUNT + 3 * dues);
+
+    // Closing the schedule refunds its rent.
+    let before = env.lamports(&env.fee_payer.pubkey());
+    let ix = squad_mint_client::instructions::close_dues(
+        &fund.pda,
+        &owner.pubkey(),
+        &env.fee_payer.pubkey(),
+    );
+    env.send(&[ix], &[&owner.keypair]).unwrap();
+    assert!(!env.exists(&dues_pda(&fund.pda)));
+    assert!(env.lamports(&env.fee_payer.pubkey()) > before);
+}
+
+#[test]
+fn members_admitted_after_dues_start_owe_from_the_current_period() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("lc_dues_late", 0);
+    let owner = &fund.owner().keypair;
+    let dues = ONE_USDC / 5;
+    let month = 30 * 24 * 60 * 60;
+    env.set_dues(&fund.pda, owner, dues, month).unwrap();
+    env.advance_clock(2 * month);
+
+    let joiner = env.wallet(2);
+    env.initiate_join_request(&fund.pda, &joiner, JOIN_AMOUNT)
+        .unwrap();
+    env.add_member(&fund.pda, &joiner.pubkey(), owner).unwrap();
+    env.approve_dues(&fund.pda, &joiner, ONE_USDC);
+    env.collect_dues(&fund.pda).unwrap();
+
+    // Three periods have begun; the joiner pays only the third.
+    assert_eq!(env.dues(&fund.pda).periods_paid, [0, 3]);
+    assert_eq!(
+        env.token_balance(&joiner.ata),
+        2 * ONE_USDC - JOIN_AMOUNT - dues
+    );
+    assert!(env.dues(&fund.pda).in_arrears(0));
+    assert!(!env.dues(&fund.pda).in_arrears(1));
+}
+
+#[test]
+fn dissolving_a_fund_closes_its_dues_schedule() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("lc_dues_end", 1);
+    let (owner, member) = (fund.owner(), &fund.members[1]);
+    env.set_dues(&fund.pda, &owner.keypair, ONE_USDC / 5, 30 * 24 * 60 * 60)
+        .unwrap();
+    env.propose_dissolution(
+        &fund.pda,
+        &owner.keypair,
+        Distribution::Equal,
+        &owner.pubkey(),
+    )
+    .unwrap();
+    env.vote(&fund.pda, &member.keypair, true).unwrap();
+
+    // The schedule's rent goes back to whoever paid it, not to anyone named.
+    let members = [owner.pubkey(), member.pubkey()];
+    let ix = squad_mint_client::instructions::dissolve_fund(
+        &fund.pda,
+        &owner.pubkey(),
+        None,
+        &MINT,
+        &members,
+    );
+    assert_error(env.send(&[ix], &[]), ErrorCode::RentPayerMismatch);
+
+    let schedule = env.dues(&fund.pda);
+    let before = env.lamports(&env.fee_payer.pubkey());
+    let ix = squad_mint_client::instructions::dissolve_fund(
+        &fund.pda,
+        &owner.pubkey(),
+        Some(&schedule),
+        &MINT,
+        &members,
+    );
+    env.send(&[ix], &[]).unwrap();
+    assert!(!env.exists(&fund.pda));
+    assert!(!env.exists(&dues_pda(&fund.pda)));
+    assert!(env.lamports(&env.fee_payer.pubkey()) > before);
+}
+
+#[test]
+fn a_rosca_pays_each_member_the_pot_in_turn_without_a_vote() {
+    let mut env = TestEnv::new();
+    let treasury = env.treasury();
+    env.set_protocol_fee(&treasury, 0, 100, 0).unwrap(); // 1% payout fee
+    let fund = env.make_fund("lc_rosca", 2);
+    let owner = &fund.owner().keypair;
+    let dues = ONE_USDC / 5;
+    let month = 30 * 24 * 60 * 60;
+    for member in &fund.members {
+        env.approve_dues(&fund.pda, member, ONE_USDC);
+    }
+    env.set_dues(&fund.pda, owner, dues, month).unwrap();
+    env.start_rosca(&fund.pda, owner, false).unwrap();
+    let rotation = env.rosca(&fund.pda);
+    assert_eq!(rotation.order, [0, 1, 2]);
+    assert_eq!(rotation.first_period, 1);
+
+    // Nothing is collected yet, so the first round isn't funded.
+    let owner_key = fund.owner().pubkey();
+    let result = env.pay_rosca_round(&fund.pda, &owner_key);
+    assert_error(result, ErrorCode::RoundDuesOutstanding);
+
+    let pot = 3 * dues;
+    let fee = pot / 100;
+    for (round, member) in fund.members.iter().enumerate() {
+        env.collect_dues(&fund.pda).unwrap();
+        assert_eq!(env.rosca(&fund.pda).pots[round], pot);
+        let before = env.token_balance(&member.ata);
+        env.pay_rosca_round(&fund.pda, &member.pubkey()).unwrap();
+        assert_eq!(env.token_balance(&member.ata), before + pot - fee);
+        assert_eq!(env.rosca(&fund.pda).round, round as u32 + 1);
+
+        // One round per period: the next waits for its dues.
+        if round + 1 < fund.members.len() {
+            let next = fund.members[round + 1].pubkey();
+            let result = env.pay_rosca_round(&fund.pda, &next);
+            assert_error(result, ErrorCode::RoundDuesOutstanding);
+        }
+        env.advance_clock(month);
+    }
+    assert_eq!(env.token_balance(&env.treasury_ata()), 3 * fee);
+    // The join deposits are untouched.
+    assert_eq!(env.token_balance(&vault_pda(&fund.pda)), 2 * JOIN_AMOUNT);
+
+    let result = env.pay_rosca_round(&fund.pda, &owner_key);
+    assert_error(result, ErrorCode::RoscaComplete);
+
+    // The next rotation starts from the current period.
+    env.start_rosca(&fund.pda, owner, true).unwrap();
+    let rotation = env.rosca(&fund.pda);
+    assert_eq!(rotation.first_period, 4);
+    let mut order = rotation.order.clone();
+    order.sort();
+    assert_eq!(order, [0, 1, 2]);
+    let result = env.start_rosca(&fund.pda, owner, false);
+    assert_error(result, ErrorCode::RoscaInProgress);
+
+    let ix = squad_mint_client::instructions::close_rosca(
+        &fund.pda,
+        &owner.pubkey(),
+        &env.fee_payer.pubkey(),
+    );
+    env.send(&[ix], &[owner]).unwrap();
+    assert!(!env.exists(&rosca_pda(&fund.pda)));
+}
+
+#[test]
+fn restarting_dues_strands_a_rotation_until_a_new_one_starts() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("lc_roscaReset", 1);
+    let owner = &fund.owner().keypair;
+    let day = 24 * 60 * 60;
+    for member in &fund.members {
+        env.approve_dues(&fund.pda, member, ONE_USDC);
+    }
+    env.set_dues(&fund.pda, owner, ONE_USDC / 5, day).unwrap();
+    env.start_rosca(&fund.pda, owner, false).unwrap();
+
+    env.advance_clock(day);
+    env.set_dues(&fund.pda, owner, ONE_USDC / 5, day).unwrap();
+    env.collect_dues(&fund.pda).unwrap();
+    let result = env.pay_rosca_round(&fund.pda, &fund.owner().pubkey());
+    assert_error(result, ErrorCode::RoscaScheduleChanged);
+
+    // The period just collected was paid before this rotation, so it isn't in
+    // a pot: the rotation starts from the next one.
+    env.start_rosca(&fund.pda, owner, false).unwrap();
+    assert_eq!(env.rosca(&fund.pda).first_period, 2);
+    let result = env.pay_rosca_round(&fund.pda, &fund.owner().pubkey());
+    assert_error(result, ErrorCode::RoundDuesOutstanding);
+
+    env.advance_clock(day);
+    env.collect_dues(&fund.pda).unwrap();
+    assert_eq!(env.rosca(&fund.pda).pots, [2 * ONE_USDC / 5, 0]);
+    env.pay_rosca_round(&fund.pda, &fund.owner().pubkey())
+        .unwrap();
+}
+
+#[test]
+fn a_stream_vests_to_its_recipient_and_a_cancel_returns_the_rest() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("lc_stream", 1);
+    let (owner, member_a) = (&fund.members[0].keypair, &fund.members[1].keypair);
+    env.fund_vault(&fund.pda, 10);
+    let vault = vault_pda(&fund.pda);
+    let contributor = env.wallet(0);
+    let day = 24 * 60 * 60;
+    let start = env.now();
+    let terms = StreamTerms {
+        recipient: contributor.pubkey(),
+        amount: 4 * ONE_USDC,
+        start,
+        cliff: start + 30 * day,
+        end: start + 120 * day,
+    };
+
+    let stream = stream_pda(&fund.pda, env.fund(&fund.pda).master_nonce());
+    env.propose_stream(&fund.pda, owner, terms).unwrap();
+    let escrow = MINT.ata(&stream);
+    assert_eq!(env.token_balance(&escrow), 0);
+    let result = env.claim_stream(&stream, &contributor.keypair);
+    assert_error(result, ErrorCode::NothingToClaim);
+
+    // Approval funds the escrow; nothing is claimable before the cliff.
+    let vault_before = env.token_balance(&vault);
+    env.vote(&fund.pda, member_a, true).unwrap();
+    assert_eq!(env.token_balance(&escrow), 4 * ONE_USDC);
+    assert_eq!(env.token_balance(&vault), vault_before - 4 * ONE_USDC);
+    let result = env.claim_stream(&stream, &contributor.keypair);
+    assert_error(result, ErrorCode::NothingToClaim);
+
+    // Halfway through, half has vested, the cliff's share included.
+    env.advance_clock(60 * day);
+    env.claim_stream(&stream, &contributor.keypair).unwrap();
+    assert_eq!(env.token_balance(&contributor.ata), 2 * ONE_USDC);
+    assert_eq!(env.stream(&stream).claimed, 2 * ONE_USDC);
+
+    // Cancelled at three quarters: the last quarter goes back to the vault,
+    // and the recipient keeps what had vested.
+    env.propose_stream_cancel(&fund.pda, member_a, &stream)
+        .unwrap();
+    env.advance_clock(30 * day);
+    let vault_before = env.token_balance(&vault);
+    env.vote(&fund.pda, owner, true).unwrap();
+    assert_eq!(env.token_balance(&vault), vault_before + ONE_USDC);
+    assert!(env.stream(&stream).cancelled_at.is_some());
+
+    env.advance_clock(30 * day);
+    env.claim_stream(&stream, &contributor.keypair).unwrap();
+    assert_eq!(env.token_balance(&contributor.ata), 3 * ONE_USDC);
+    let result = env.claim_stream(&stream, &contributor.keypair);
+    assert_error(result, ErrorCode::NothingToClaim);
+
+    // Settled: anyone can close it, refunding the rent of both accounts.
+    let before = env.lamports(&env.fee_payer.pubkey());
+    env.close_stream(&stream).unwrap();
+    assert!(!env.exists(&stream));
+    assert!(!env.exists(&escrow));
+    assert!(env.lamports(&env.fee_payer.pubkey()) > before);
+}
+
+#[test]
+fn a_rejected_stream_is_never_funded_and_can_be_closed() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("lc_streamNo", 1);
+    env.fund_vault(&fund.pda, 10);
+    let contributor = env.wallet(0);
+    let start = env.now();
+    let terms = StreamTerms {
+        recipient: contributor.pubkey(),
+        amount: ONE_USDC,
+        start,
+        cliff: start,
+        end: start + 24 * 60 * 60,
+    };
+    let stream = stream_pda(&fund.pda, env.fund(&fund.pda).master_nonce());
+    env.propose_stream(&fund.pda, &fund.owner().keypair, terms)
+        .unwrap();
+
+    // Open proposals keep their stream.
+    let result = env.close_stream(&stream);
+    assert_error(result, ErrorCode::StreamNotSettled);
+
+    env.vote(&fund.pda, &fund.members[1].keypair, false)
+        .unwrap();
+    assert_eq!(env.token_balance(&MINT.ata(&stream)), 0);
+    env.close_stream(&stream).unwrap();
+    assert!(!env.exists(&stream));
+}
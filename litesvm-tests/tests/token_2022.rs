@@ -0,0 +1,322 @@
+//! Funds on Token-2022 mints: a transfer-fee mint end to end, and the
+//! extensions `initialize` refuses.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        default_account_state::DefaultAccountState, non_transferable::NonTransferable,
+        permanent_delegate::PermanentDelegate, transfer_hook::TransferHook,
+        BaseStateWithExtensionsMut, ExtensionType, StateWithExtensionsMut,
+    },
+    state::{AccountState, Mint},
+};
+use litesvm::types::TransactionResult;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use squad_mint_client::{instructions, TokenMint};
+use squad_mint_litesvm_tests::*;
+
+// 1.5% of every transfer, uncapped.
+const FEE_BPS: u64 = 150;
+
+// What the mint withholds from a transfer of `amount` (rounded up).
+fn transfer_fee(amount: u64) -> u64 {
+    (amount * FEE_BPS).div_ceil(10_000)
+}
+
+// A fee mint the config allows, alongside the test mint.
+fn fee_mint(env: &mut TestEnv) -> TokenMint {
+    let mint = env.create_fee_mint(Pubkey::new_unique(), FEE_BPS as u16, u64::MAX);
+    env.set_config(&[USDC_MINT, mint.address]);
+    mint
+}
+
+fn initialize(
+    env: &mut TestEnv,
+    mint: &TokenMint,
+    owner: &Wallet,
+    handle: &str,
+) -> TransactionResult {
+    let ix = instructions::initialize(
+        &env.fee_payer.pubkey(),
+        &owner.pubkey(),
+        &encode_handle(handle),
+        mint,
+        JOIN_AMOUNT,
+        vec![],
+        0,
+    );
+    env.send(&[ix], &[&owner.keypair])
+}
+
+fn initiate_join_request(env: &mut TestEnv, mint: &TokenMint, fund: &Pubkey, joiner: &Wallet) {
+    let ix = instructions::initiate_join_request(
+        &env.fee_payer.pubkey(),
+        fund,
+        &joiner.pubkey(),
+        mint,
+        JOIN_AMOUNT,
+        None,
+    );
+    env.send(&[ix], &[&joiner.keypair]).unwrap();
+}
+
+fn add_member(env: &mut TestEnv, mint: &TokenMint, fund: &Fund, joiner: &Pubkey) {
+    let ix = instructions::add_member(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &fund.owner().pubkey(),
+        joiner,
+        &env.fee_payer.pubkey(),
+        mint,
+        &env.treasury(),
+    );
+    env.send(&[ix], &[&fund.owner().keypair]).unwrap();
+}
+
+// make_fund on a Token-2022 mint.
+fn make_fund(env: &mut TestEnv, mint: &TokenMint, handle: &str, extra_members: usize) -> Fund {
+    let owner = env.wallet_for(mint, 2);
+    initialize(env, mint, &owner, handle).unwrap();
+    let mut fund = Fund {
+        pda: fund_pda(handle, &owner.pubkey()),
+        members: vec![owner],
+    };
+    for _ in 0..extra_members {
+        let joiner = env.wallet_for(mint, 2);
+        initiate_join_request(env, mint, &fund.pda, &joiner);
+        add_member(env, mint, &fund, &joiner.pubkey());
+        fund.members.push(joiner);
+    }
+    fund
+}
+
+#[test]
+fn a_fund_on_a_transfer_fee_mint_gets_a_vault_that_can_take_fees() {
+    let mut env = TestEnv::new();
+    let mint = fee_mint(&mut env);
+    let fund = make_fund(&mut env, &mint, "t22_init", 0);
+
+    let vault = vault_pda(&fund.pda);
+    assert_eq!(
+        env.svm.get_account(&vault).unwrap().owner,
+        spl_token_2022::ID
+    );
+    assert_eq!(env.token_balance(&vault), 0);
+    assert_eq!(env.withheld_on_account(&vault), 0);
+    assert_eq!(env.fund(&fund.pda).members(), &[fund.owner().pubkey()]);
+}
+
+// Both hops into the vault are fee-charged, so the joiner pays the join amount
+// grossed up twice and exactly the join amount is left in the vault. Every
+// token the joiner sent is accounted for: in the vault, withheld on it, or
+// harvested to the mint when the escrow closed.
+#[test]
+fn transfer_fee_deposits_leave_exactly_the_join_amount_in_the_vault() {
+    let mut env = TestEnv::new();
+    let mint = fee_mint(&mut env);
+    let fund = make_fund(&mut env, &mint, "t22_join", 0);
+    let vault = vault_pda(&fund.pda);
+
+    let joiner = env.wallet_for(&mint, 2);
+    initiate_join_request(&mut env, &mint, &fund.pda, &joiner);
+    let sent = 2 * ONE_USDC - env.token_balance(&joiner.ata);
+    let escrow = join_escrow_pda(&join_request_pda(&fund.pda, &joiner.pubkey()));
+    let escrowed = env.token_balance(&escrow);
+    assert_eq!(sent - escrowed, transfer_fee(sent));
+    assert_eq!(env.withheld_on_account(&escrow), transfer_fee(sent));
+    assert_eq!(escrowed - transfer_fee(escrowed), JOIN_AMOUNT);
+
+    add_member(&mut env, &mint, &fund, &joiner.pubkey());
+    assert!(!env.exists(&escrow));
+    assert_eq!(env.token_balance(&vault), JOIN_AMOUNT);
+    assert_eq!(env.fund(&fund.pda).contribution_of(1), JOIN_AMOUNT);
+    assert_eq!(env.withheld_on_account(&vault), transfer_fee(escrowed));
+    assert_eq!(env.withheld_on_mint(&mint.address), transfer_fee(sent));
+    assert_eq!(
+        env.token_balance(&vault)
+            + env.withheld_on_account(&vault)
+            + env.withheld_on_mint(&mint.address),
+        sent
+    );
+
+    // A second member: the vault is still the sum of the deposits credited.
+    let joiner = env.wallet_for(&mint, 2);
+    initiate_join_request(&mut env, &mint, &fund.pda, &joiner);
+    add_member(&mut env, &mint, &fund, &joiner.pubkey());
+    let state = env.fund(&fund.pda);
+    assert_eq!(state.contribution_of(2), JOIN_AMOUNT);
+    assert_eq!(env.token_balance(&vault), 2 * JOIN_AMOUNT);
+}
+
+// The protocol's join fee comes out of the escrow first, then the rest pays
+// the mint's fee on its way to the vault. The member is credited with what
+// arrived, so the vault still equals the deposits credited.
+#[test]
+fn transfer_fee_deposits_are_credited_net_of_both_fees() {
+    let mut env = TestEnv::new();
+    let mint = fee_mint(&mut env);
+    let treasury = Keypair::new().pubkey();
+    env.set_protocol_fee(&treasury, 100, 0, 0).unwrap(); // 1%
+    let fund = make_fund(&mut env, &mint, "t22_fees", 0);
+    let vault = vault_pda(&fund.pda);
+
+    let joiner = env.wallet_for(&mint, 2);
+    initiate_join_request(&mut env, &mint, &fund.pda, &joiner);
+    let escrow = join_escrow_pda(&join_request_pda(&fund.pda, &joiner.pubkey()));
+    let escrowed = env.token_balance(&escrow);
+    add_member(&mut env, &mint, &fund, &joiner.pubkey());
+
+    let join_fee = JOIN_AMOUNT / 100;
+    let moved = escrowed - join_fee;
+    let credited = moved - transfer_fee(moved);
+    assert_eq!(env.fund(&fund.pda).contribution_of(1), credited);
+    assert_eq!(env.token_balance(&vault), credited);
+    assert_eq!(
+        env.token_balance(&mint.ata(&treasury)),
+        join_fee - transfer_fee(join_fee)
+    );
+}
+
+// The vault pays exactly the amount voted; the mint's fee comes out of what
+// the recipient receives, and is withheld on the recipient's account.
+#[test]
+fn transfer_fee_payouts_debit_the_vault_by_exactly_the_amount_voted() {
+    let mut env = TestEnv::new();
+    let mint = fee_mint(&mut env);
+    let fund = make_fund(&mut env, &mint, "t22_payout", 2); // 3 members
+    let vault = vault_pda(&fund.pda);
+    assert_eq!(env.token_balance(&vault), 2 * JOIN_AMOUNT);
+
+    // No token account yet: the Token-2022 ATA is created on execution.
+    let recipient = Keypair::new().pubkey();
+    let amount = 2 * ONE_USDC;
+    let ix = instructions::create_proposal(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        0,
+        &fund.owner().pubkey(),
+        &mint,
+        amount,
+        &recipient,
+    );
+    env.send(&[ix], &[&fund.owner().keypair]).unwrap();
+    let proposal = env.proposal(&proposal_pda(&fund.pda, 0));
+    let ix = instructions::submit_and_execute(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &fund.members[1].pubkey(),
+        &proposal,
+        &mint,
+        &env.treasury(),
+        true,
+    );
+    env.send(&[ix], &[&fund.members[1].keypair]).unwrap();
+
+    let recipient_ata = mint.ata(&recipient);
+    assert_eq!(env.token_balance(&vault), 2 * JOIN_AMOUNT - amount);
+    assert_eq!(env.token_balance(&recipient_ata), amount - 30_000); // 1.5%
+    assert_eq!(env.withheld_on_account(&recipient_ata), 30_000);
+    assert!(!env.fund(&fund.pda).has_active_vote());
+}
+
+// Dues lose the mint's fee on the way into the vault, and a round pays out
+// what arrived for it: never the join deposits sitting next to it.
+#[test]
+fn a_rosca_on_a_transfer_fee_mint_pays_out_what_its_dues_brought_in() {
+    let mut env = TestEnv::new();
+    let mint = fee_mint(&mut env);
+    let fund = make_fund(&mut env, &mint, "t22_rosca", 1);
+    let owner = fund.owner();
+    let vault = vault_pda(&fund.pda);
+    let dues = ONE_USDC / 5;
+    for member in &fund.members {
+        let ix = instructions::approve_dues(&member.pubkey(), &fund.pda, &mint, DECIMALS, ONE_USDC);
+        env.send(&[ix], &[&member.keypair]).unwrap();
+    }
+    let ix = instructions::set_dues(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &owner.pubkey(),
+        &mint,
+        dues,
+        24 * 60 * 60,
+    );
+    env.send(&[ix], &[&owner.keypair]).unwrap();
+    let ix = instructions::start_rosca(&env.fee_payer.pubkey(), &fund.pda, &owner.pubkey(), false);
+    env.send(&[ix], &[&owner.keypair]).unwrap();
+
+    let deposits = env.token_balance(&vault);
+    let members = [owner.pubkey(), fund.members[1].pubkey()];
+    let ix = instructions::collect_dues(&fund.pda, &mint, &members);
+    env.send(&[ix], &[]).unwrap();
+    let pot = 2 * (dues - transfer_fee(dues));
+    assert_eq!(env.token_balance(&vault), deposits + pot);
+    assert_eq!(env.rosca(&fund.pda).pots[0], pot);
+
+    let before = env.token_balance(&owner.ata);
+    let ix = instructions::pay_rosca_round(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &owner.pubkey(),
+        &mint,
+        &env.treasury(),
+    );
+    env.send(&[ix], &[]).unwrap();
+    assert_eq!(env.token_balance(&vault), deposits);
+    assert_eq!(
+        env.token_balance(&owner.ata),
+        before + pot - transfer_fee(pot)
+    );
+}
+
+// Each of the extensions with a dedicated error is refused when the fund is
+// created, whatever it is configured to; so is one outside the allowlist.
+#[test]
+fn initialize_rejects_mints_with_unsafe_extensions() {
+    type Configure = fn(&mut StateWithExtensionsMut<Mint>);
+    let cases: [(ExtensionType, Configure, ErrorCode); 4] = [
+        (
+            ExtensionType::TransferHook,
+            |state| {
+                state.init_extension::<TransferHook>(true).unwrap();
+            },
+            ErrorCode::MintHasTransferHook,
+        ),
+        (
+            ExtensionType::PermanentDelegate,
+            |state| {
+                let extension = state.init_extension::<PermanentDelegate>(true).unwrap();
+                extension.delegate = Some(Pubkey::new_unique()).try_into().unwrap();
+            },
+            ErrorCode::MintHasPermanentDelegate,
+        ),
+        (
+            ExtensionType::NonTransferable,
+            |state| {
+                state.init_extension::<NonTransferable>(true).unwrap();
+            },
+            ErrorCode::MintIsNonTransferable,
+        ),
+        (
+            ExtensionType::DefaultAccountState,
+            |state| {
+                let extension = state.init_extension::<DefaultAccountState>(true).unwrap();
+                extension.state = AccountState::Initialized as u8;
+            },
+            ErrorCode::UnsupportedMintExtension,
+        ),
+    ];
+
+    let mut env = TestEnv::new();
+    for (i, (extension, configure, expected)) in cases.into_iter().enumerate() {
+        let mint = env.create_token_2022_mint(Pubkey::new_unique(), &[extension], configure);
+        env.set_config(&[USDC_MINT, mint.address]);
+        let owner = env.wallet_for(&mint, 2);
+        let handle = format!("t22_bad_{i}");
+        let result = initialize(&mut env, &mint, &owner, &handle);
+        assert_error(result, expected);
+        assert!(!env.exists(&fund_pda(&handle, &owner.pubkey())));
+    }
+}
@@ -0,0 +1,244 @@
+//! Accounts the program's first release left on chain, byte for byte, and the
+//! permissionless migrations that carry them across the upgrade.
+
+use anchor_lang::{error::ErrorCode as AnchorErrorCode, prelude::Pubkey};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use squad_mint_client::{instructions, ProposalKind, SquadMintFund, Transaction as Proposal};
+use squad_mint_litesvm_tests::*;
+
+// A third party with SOL for the extra rent.
+fn migration_payer(env: &mut TestEnv) -> Keypair {
+    let payer = Keypair::new();
+    env.svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
+    payer
+}
+
+#[test]
+fn a_first_release_fund_with_free_slots_keeps_working_until_it_is_migrated() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("up_slots", 1);
+    env.rewrite_fund_as_baseline(&fund.pda);
+    assert_eq!(env.data_len(&fund.pda), 8 + BASELINE_FUND_SIZE);
+    assert_eq!(env.fund(&fund.pda).version(), AccountVersion::V1);
+
+    // The appended fields fit in the zeroed slack after the second member, and
+    // the fund is written back in its v1 layout.
+    let joiner = env.wallet(2);
+    env.initiate_join_request(&fund.pda, &joiner, JOIN_AMOUNT)
+        .unwrap();
+    env.add_member(&fund.pda, &joiner.pubkey(), &fund.owner().keypair)
+        .unwrap();
+    assert_eq!(env.data_len(&fund.pda), 8 + BASELINE_FUND_SIZE);
+    assert_eq!(env.fund(&fund.pda).version(), AccountVersion::V1);
+
+    // Anyone can migrate; the payer covers the extra rent.
+    let payer = migration_payer(&mut env);
+    let lamports_before = env.lamports(&fund.pda);
+    let ix = instructions::migrate_fund(&payer.pubkey(), &fund.pda);
+    env.send(&[ix], &[&payer]).unwrap();
+
+    let state = env.fund(&fund.pda);
+    assert_eq!(state.version(), AccountVersion::CURRENT);
+    assert_eq!(state.members().len(), 3);
+    assert_eq!(state.contribution_of(1), JOIN_AMOUNT);
+    assert_eq!(state.contribution_of(2), JOIN_AMOUNT);
+    assert_eq!(env.data_len(&fund.pda), 8 + SquadMintFund::MAX_SIZE);
+    assert!(env.lamports(&fund.pda) > lamports_before);
+
+    // Idempotent.
+    let ix = instructions::migrate_fund(&payer.pubkey(), &fund.pda);
+    env.send(&[ix], &[&payer]).unwrap();
+    assert_eq!(env.data_len(&fund.pda), 8 + SquadMintFund::MAX_SIZE);
+}
+
+// The first release's worst case: a fund with every member slot taken (its
+// data runs to the last byte of its allocation), an open proposal and a
+// pending join escrow. None of them decodes until migrated; once they are,
+// the vote executes and the escrow is refunded.
+#[test]
+fn a_full_first_release_fund_with_a_vote_and_an_escrow_in_flight_is_migrated() {
+    let mut env = TestEnv::new();
+    let max = SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE;
+    let mut fund = env.make_fund("up_full", max - 2);
+    // The stranded joiner asks while a slot is free; the last slot is taken
+    // before the owner gets to them.
+    let stranded = env.wallet(2);
+    env.initiate_join_request(&fund.pda, &stranded, JOIN_AMOUNT)
+        .unwrap();
+    let last = env.wallet(2);
+    env.initiate_join_request(&fund.pda, &last, JOIN_AMOUNT)
+        .unwrap();
+    env.add_member(&fund.pda, &last.pubkey(), &fund.owner().keypair)
+        .unwrap();
+    fund.members.push(last);
+    assert_eq!(env.fund(&fund.pda).members().len(), max);
+
+    env.fund_vault(&fund.pda, 10);
+    let recipient = env.wallet(0);
+    env.create_proposal(
+        &fund.pda,
+        &fund.members[1].keypair,
+        2 * ONE_USDC,
+        &recipient.pubkey(),
+    )
+    .unwrap();
+    let proposal = proposal_pda(&fund.pda, 0);
+    let request = join_request_pda(&fund.pda, &stranded.pubkey());
+
+    env.rewrite_fund_as_baseline(&fund.pda);
+    env.rewrite_proposal_as_baseline(&proposal);
+    env.rewrite_join_request_as_baseline(&request);
+    // 32 + 15 + 1 + (4 + 8 * 32) + 8 + 8: no slack at all.
+    assert_eq!(env.data_len(&fund.pda), 8 + BASELINE_FUND_SIZE);
+
+    // A migrated proposal isn't enough: the fund itself doesn't decode.
+    let payer = migration_payer(&mut env);
+    let ix = instructions::migrate_proposal(&payer.pubkey(), &fund.pda, 0);
+    env.send(&[ix], &[&payer]).unwrap();
+    let result = env.vote(&fund.pda, &fund.members[2].keypair, true);
+    assert_error(result, AnchorErrorCode::AccountDidNotDeserialize);
+
+    let ix = instructions::migrate_fund(&payer.pubkey(), &fund.pda);
+    env.send(&[ix], &[&payer]).unwrap();
+    let state = env.fund(&fund.pda);
+    assert_eq!(state.version(), AccountVersion::CURRENT);
+    assert_eq!(state.members().len(), max);
+    assert!(state.has_active_vote());
+    assert_eq!(state.master_nonce(), 0);
+    assert!(state.guardians().is_empty());
+    assert_eq!(state.contribution_of(0), 0);
+    assert_eq!(state.contribution_of(max - 1), JOIN_AMOUNT);
+    assert_eq!(env.data_len(&fund.pda), 8 + SquadMintFund::MAX_SIZE);
+
+    // The proposer's YES plus four more is a majority of eight.
+    for member in &fund.members[2..6] {
+        env.vote(&fund.pda, &member.keypair, true).unwrap();
+    }
+    assert_eq!(env.token_balance(&recipient.ata), 2 * ONE_USDC);
+    assert!(!env.exists(&proposal));
+    assert!(!env.fund(&fund.pda).has_active_vote());
+
+    // The escrow is stale now that the fund is full; anyone can refund it.
+    let ix = instructions::migrate_join_request(&payer.pubkey(), &fund.pda, &stranded.pubkey());
+    env.send(&[ix], &[&payer]).unwrap();
+    let ix = instructions::cancel_stale_join_request(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &stranded.pubkey(),
+        &stranded.pubkey(),
+        &MINT,
+    );
+    env.send(&[ix], &[]).unwrap();
+    assert_eq!(env.token_balance(&stranded.ata), 2 * ONE_USDC);
+    assert!(!env.exists(&request));
+}
+
+#[test]
+fn an_open_first_release_proposal_is_migrated_then_refunds_its_proposer() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("up_proposal", 2);
+    env.fund_vault(&fund.pda, 10);
+    let proposer = &fund.members[1];
+    let recipient = env.wallet(0);
+    env.create_proposal(
+        &fund.pda,
+        &proposer.keypair,
+        2 * ONE_USDC,
+        &recipient.pubkey(),
+    )
+    .unwrap();
+    let proposal = proposal_pda(&fund.pda, 0);
+    env.rewrite_proposal_as_baseline(&proposal);
+    assert_eq!(env.data_len(&proposal), 8 + BASELINE_PROPOSAL_SIZE);
+
+    // Too short for the current layout: no vote gets through until it is migrated.
+    let result = env.vote(&fund.pda, &fund.members[2].keypair, true);
+    assert_error(result, AnchorErrorCode::AccountDidNotDeserialize);
+
+    let payer = migration_payer(&mut env);
+    let ix = instructions::migrate_proposal(&payer.pubkey(), &fund.pda, 0);
+    env.send(&[ix], &[&payer]).unwrap();
+
+    let state = env.proposal(&proposal);
+    assert_eq!(state.trailer.version, AccountVersion::CURRENT);
+    assert_eq!(state.kind, ProposalKind::Payout);
+    assert_eq!(state.rent_payer, proposer.pubkey());
+    assert_eq!(state.votes, 1 << 1); // the proposer's YES survives
+    assert_eq!(state.message_data.amount, 2 * ONE_USDC);
+    assert_eq!(env.data_len(&proposal), 8 + Proposal::MAX_SIZE);
+
+    // Idempotent.
+    let ix = instructions::migrate_proposal(&payer.pubkey(), &fund.pda, 0);
+    env.send(&[ix], &[&payer]).unwrap();
+    assert_eq!(env.proposal(&proposal).rent_payer, proposer.pubkey());
+
+    let rent = env.lamports(&proposal);
+    let proposer_lamports = env.lamports(&proposer.pubkey());
+    env.vote(&fund.pda, &fund.members[2].keypair, true).unwrap();
+    assert_eq!(env.token_balance(&recipient.ata), 2 * ONE_USDC);
+    assert!(!env.exists(&proposal));
+    assert_eq!(env.lamports(&proposer.pubkey()), proposer_lamports + rent);
+}
+
+#[test]
+fn a_pending_first_release_join_request_is_migrated_then_refunds_the_joiner() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("up_join", 0);
+    let joiner = env.wallet(2);
+    env.initiate_join_request(&fund.pda, &joiner, JOIN_AMOUNT)
+        .unwrap();
+    let request = join_request_pda(&fund.pda, &joiner.pubkey());
+    let escrow = join_escrow_pda(&request);
+    env.rewrite_join_request_as_baseline(&request);
+    assert_eq!(env.data_len(&request), 8 + BASELINE_JOIN_REQUEST_SIZE);
+
+    let result = env.add_member(&fund.pda, &joiner.pubkey(), &fund.owner().keypair);
+    assert_error(result, AnchorErrorCode::AccountDidNotDeserialize);
+
+    let payer = migration_payer(&mut env);
+    let ix = instructions::migrate_join_request(&payer.pubkey(), &fund.pda, &joiner.pubkey());
+    env.send(&[ix], &[&payer]).unwrap();
+
+    let state = env.join_request(&request);
+    assert_eq!(state.trailer.version, AccountVersion::CURRENT);
+    assert_eq!(state.rent_payer, joiner.pubkey());
+    assert_eq!(state.join_amount(), JOIN_AMOUNT);
+    assert_eq!(env.token_balance(&escrow), JOIN_AMOUNT);
+
+    // Both rents go back to the joiner, not to whoever pays for the admission.
+    let rent = env.lamports(&request) + env.lamports(&escrow);
+    let joiner_lamports = env.lamports(&joiner.pubkey());
+    let ix = add_member_ix(
+        &env.fee_payer.pubkey(),
+        &fund.pda,
+        &joiner.pubkey(),
+        &fund.owner().pubkey(),
+        &joiner.pubkey(),
+        &env.treasury(),
+    );
+    env.send(&[ix], &[&fund.owner().keypair]).unwrap();
+    assert!(env.fund(&fund.pda).members().contains(&joiner.pubkey()));
+    assert_eq!(env.token_balance(&vault_pda(&fund.pda)), JOIN_AMOUNT);
+    assert_eq!(env.lamports(&joiner.pubkey()), joiner_lamports + rent);
+}
+
+#[test]
+fn a_migration_only_accepts_its_own_account_type() {
+    let mut env = TestEnv::new();
+    let fund = env.make_fund("up_wrong", 0);
+    let fund_len = env.data_len(&fund.pda);
+
+    let payer = migration_payer(&mut env);
+    let mut ix = instructions::migrate_proposal(&payer.pubkey(), &fund.pda, 0);
+    ix.accounts[0].pubkey = fund.pda;
+    let result = env.send(&[ix], &[&payer]);
+    assert_error(result, AnchorErrorCode::AccountDiscriminatorMismatch);
+    assert_eq!(env.data_len(&fund.pda), fund_len);
+
+    // Nor anything another program owns.
+    let mut ix = instructions::migrate_join_request(&payer.pubkey(), &fund.pda, &Pubkey::default());
+    ix.accounts[0].pubkey = vault_pda(&fund.pda);
+    let result = env.send(&[ix], &[&payer]);
+    assert_error(result, AnchorErrorCode::ConstraintOwner);
+}
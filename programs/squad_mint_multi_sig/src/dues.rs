@@ -0,0 +1,65 @@
+// Recurring dues (set_dues / collect_dues). A schedule splits time into
+// periods of `period_seconds` from `start`, and every member owes `amount` per
+// period, due as soon as the period begins. Members approve the fund's dues
+// PDA as a delegate on their token account; the permissionless crank pulls
+// whole periods only, oldest first, as far as both the balance and the
+// remaining allowance go. Whoever is still behind is in arrears.
+
+// Short periods would let a crank drain a large allowance in a few days.
+pub const MIN_PERIOD_SECONDS: i64 = 24 * 60 * 60;
+
+// Dues of at least the fund-wide minimum amount, at most once a day.
+pub fn is_valid(amount: u64, period_seconds: i64, min_amount: u64) -> bool {
+    amount >= min_amount && period_seconds >= MIN_PERIOD_SECONDS
+}
+
+// Periods begun by `now`: 0 before `start`, 1 from `start` on (the first
+// period is due at once), and one more every `period_seconds`.
+pub fn periods_due(start: i64, period_seconds: i64, now: i64) -> u32 {
+    if now < start {
+        return 0;
+    }
+    let elapsed = (now - start) / period_seconds;
+    u32::try_from(elapsed).map_or(u32::MAX, |periods| periods.saturating_add(1))
+}
+
+// Whole periods the crank can take out of `available` (the smaller of the
+// balance and the remaining allowance) toward `owed` periods.
+pub fn collectable(owed: u32, amount: u64, available: u64) -> u32 {
+    let affordable = available / amount;
+    owed.min(u32::try_from(affordable).unwrap_or(u32::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = MIN_PERIOD_SECONDS;
+
+    #[test]
+    fn a_period_is_due_from_its_first_second() {
+        let start = 1_700_000_000;
+        assert_eq!(periods_due(start, 30 * DAY, start - 1), 0);
+        assert_eq!(periods_due(start, 30 * DAY, start), 1);
+        assert_eq!(periods_due(start, 30 * DAY, start + 30 * DAY - 1), 1);
+        assert_eq!(periods_due(start, 30 * DAY, start + 30 * DAY), 2);
+        assert_eq!(periods_due(0, DAY, i64::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn only_whole_periods_are_collected() {
+        assert_eq!(collectable(3, 100, 250), 2);
+        assert_eq!(collectable(3, 100, 1_000), 3);
+        assert_eq!(collectable(3, 100, 99), 0);
+        assert_eq!(collectable(0, 100, 1_000), 0);
+        assert_eq!(collectable(u32::MAX, 1, u64::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn schedules_need_the_minimum_amount_and_a_day() {
+        assert!(is_valid(100_000, DAY, 100_000));
+        assert!(!is_valid(99_999, DAY, 100_000));
+        assert!(!is_valid(100_000, DAY - 1, 100_000));
+        assert!(!is_valid(100_000, -DAY, 100_000));
+    }
+}
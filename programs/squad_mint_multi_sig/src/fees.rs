@@ -0,0 +1,106 @@
+// Protocol fees: SquadMint's cut of the money moving through funds, paid to the
+// config's treasury. The admin sets the rates, but only up to the hard-coded
+// maximums here, so not even a compromised admin key can take more.
+//
+// Fees come out of the amount being moved, never on top of it:
+// - join: `join_fee_bps` of the join amount, when add_member moves the deposit
+//   into the vault (the member is credited with the rest);
+// - payout: `payout_fee_bps` of an executed payout (the recipient gets the
+//   rest, the vault pays exactly the amount voted);
+// - reject: a flat `reject_fee` kept from a rejected join deposit, so spamming
+//   a fund with join requests isn't free.
+//
+// Flat amounts the program holds, here and elsewhere, are in millionths of a
+// token (USDC's base unit) whatever the mint: `in_mint_units` turns them into
+// the same number of whole tokens of a fund's mint.
+
+use anchor_lang::prelude::*;
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const MAX_FEE_BPS: u16 = 200; // 2%
+
+pub const REFERENCE_DECIMALS: u8 = 6; // USDC's
+
+// 1 token.
+pub const MAX_REJECT_FEE: u64 = 1_000_000;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FeeKind {
+    Join,
+    Payout,
+    Reject,
+}
+
+// One per fee actually paid (zero fees are not emitted).
+#[event]
+pub struct ProtocolFeeCharged {
+    pub fund: Pubkey,
+    pub kind: FeeKind,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub treasury: Pubkey,
+}
+
+// `bps` of `amount`, rounded down. Never more than `amount` for a valid rate.
+pub fn bps_of(amount: u64, bps: u16) -> u64 {
+    (amount as u128 * bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+// `amount` millionths of a token in the base units of a mint with `decimals`,
+// rounded down; u64::MAX if it doesn't fit.
+pub fn in_mint_units(amount: u64, decimals: u8) -> u64 {
+    if decimals < REFERENCE_DECIMALS {
+        return amount / 10u64.pow((REFERENCE_DECIMALS - decimals) as u32);
+    }
+    match 10u64.checked_pow((decimals - REFERENCE_DECIMALS) as u32) {
+        Some(scale) => amount.saturating_mul(scale),
+        None if amount == 0 => 0,
+        None => u64::MAX,
+    }
+}
+
+pub fn is_valid(join_fee_bps: u16, payout_fee_bps: u16, reject_fee: u64) -> bool {
+    join_fee_bps <= MAX_FEE_BPS && payout_fee_bps <= MAX_FEE_BPS && reject_fee <= MAX_REJECT_FEE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn rounds_down_to_whole_base_units() {
+        assert_eq!(bps_of(1_000_000, 150), 15_000);
+        assert_eq!(bps_of(66, 150), 0); // 0.99 of a base unit
+        assert_eq!(bps_of(1_110_000, 0), 0);
+        assert_eq!(bps_of(u64::MAX, BPS_DENOMINATOR as u16), u64::MAX);
+    }
+
+    #[test]
+    fn rates_are_capped() {
+        assert!(is_valid(MAX_FEE_BPS, MAX_FEE_BPS, MAX_REJECT_FEE));
+        assert!(is_valid(0, 0, 0));
+        assert!(!is_valid(MAX_FEE_BPS + 1, 0, 0));
+        assert!(!is_valid(0, MAX_FEE_BPS + 1, 0));
+        assert!(!is_valid(0, 0, MAX_REJECT_FEE + 1));
+    }
+
+    #[test]
+    fn flat_amounts_are_the_same_tokens_at_any_decimals() {
+        assert_eq!(in_mint_units(MAX_REJECT_FEE, 6), 1_000_000);
+        assert_eq!(in_mint_units(MAX_REJECT_FEE, 9), 1_000_000_000);
+        assert_eq!(in_mint_units(MAX_REJECT_FEE, 2), 100);
+        assert_eq!(in_mint_units(100_000, 0), 0); // 0.1 of a whole-unit token
+        assert_eq!(in_mint_units(2, 25), u64::MAX);
+        assert_eq!(in_mint_units(0, 255), 0);
+    }
+
+    proptest! {
+        // At any allowed rate the fee is at most 2% of what's moving, so the
+        // rest never underflows.
+        #[test]
+        fn allowed_rates_take_at_most_two_percent(amount: u64, bps in 0..=MAX_FEE_BPS) {
+            prop_assert!(bps_of(amount, bps) <= amount / 50);
+        }
+    }
+}
@@ -0,0 +1,204 @@
+// Invite-only admission (set_invite_policy). A fund that requires invites
+// takes a join request only with one of:
+//
+// - a signed invite: an Ed25519 program instruction placed right before
+//   initiate_join_request, verifying the fund owner's signature over
+//   `message(fund, invitee, expires_at)`. The runtime checks the signature;
+//   the program checks that instruction is there and says the right thing.
+// - an allowlist proof: a Merkle path from the joiner's `leaf` to the fund's
+//   invite root.
+//
+// Everything here is pure; the handler does the sysvar lookup and the clock.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub enum Invite {
+    // The owner's signature rides in the preceding Ed25519 instruction.
+    Signed { expires_at: i64 },
+    // Sibling hashes from the joiner's leaf up to the root.
+    Allowlisted { proof: Vec<[u8; 32]> },
+}
+
+// Domain separation: an invite signature can't be replayed as anything else
+// the owner's key signs.
+pub const MESSAGE_PREFIX: &[u8] = b"squadmint invite v1";
+const LEAF_PREFIX: &[u8] = b"squadmint allowlist v1";
+
+// What the owner signs: prefix + fund + invitee + expires_at (LE).
+pub fn message(fund: &Pubkey, invitee: &Pubkey, expires_at: i64) -> Vec<u8> {
+    [
+        MESSAGE_PREFIX,
+        fund.as_ref(),
+        invitee.as_ref(),
+        &expires_at.to_le_bytes(),
+    ]
+    .concat()
+}
+
+// Ed25519 program instruction data: a signature count and a padding byte, then
+// per signature seven u16 offsets (signature, its instruction index, public
+// key, its instruction index, message, message length, its instruction index),
+// then the data the offsets point at. An index of u16::MAX means "this
+// instruction".
+const HEADER: usize = 2;
+const OFFSETS: usize = 14;
+const THIS_INSTRUCTION: u16 = u16::MAX;
+
+// Single-signature instruction data carrying its own key, signature and message.
+pub fn ed25519_instruction_data(signer: &Pubkey, signature: &[u8; 64], message: &[u8]) -> Vec<u8> {
+    let public_key = HEADER + OFFSETS;
+    let signature_at = public_key + 32;
+    let message_at = signature_at + 64;
+    let offsets = [
+        signature_at as u16,
+        THIS_INSTRUCTION,
+        public_key as u16,
+        THIS_INSTRUCTION,
+        message_at as u16,
+        message.len() as u16,
+        THIS_INSTRUCTION,
+    ];
+    let mut data = vec![1, 0];
+    for offset in offsets {
+        data.extend_from_slice(&offset.to_le_bytes());
+    }
+    data.extend_from_slice(signer.as_ref());
+    data.extend_from_slice(signature);
+    data.extend_from_slice(message);
+    data
+}
+
+// The signer and message an Ed25519 instruction verified, if it verified
+// exactly one signature over data inside itself. Offsets into other
+// instructions are refused: they could point at bytes the program never sees.
+pub fn ed25519_signed(data: &[u8]) -> Option<(Pubkey, &[u8])> {
+    if data.len() < HEADER + OFFSETS || data[0] != 1 {
+        return None;
+    }
+    let offset = |i: usize| {
+        let at = HEADER + 2 * i;
+        u16::from_le_bytes([data[at], data[at + 1]])
+    };
+    if [offset(1), offset(3), offset(6)] != [THIS_INSTRUCTION; 3] {
+        return None;
+    }
+    let (public_key, message, len) = (offset(2) as usize, offset(4) as usize, offset(5) as usize);
+    let signer = data.get(public_key..public_key + 32)?;
+    Some((
+        Pubkey::try_from(signer).ok()?,
+        data.get(message..message + len)?,
+    ))
+}
+
+pub fn leaf(member: &Pubkey) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, member.as_ref()]).to_bytes()
+}
+
+// Pairs are hashed in sorted order, so a proof needs no left/right bits.
+fn parent(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[low, high]).to_bytes()
+}
+
+pub fn verify(root: &[u8; 32], leaf: &[u8; 32], proof: &[[u8; 32]]) -> bool {
+    proof
+        .iter()
+        .fold(*leaf, |node, sibling| parent(&node, sibling))
+        == *root
+}
+
+// Off-chain side, for whoever maintains the allowlist: the root over `leaves`
+// and the proof for `leaves[index]`. An odd node out moves up a level as is.
+pub fn root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level.first().copied().unwrap_or_default()
+}
+
+pub fn proof(leaves: &[[u8; 32]], mut index: usize) -> Vec<[u8; 32]> {
+    let mut level = leaves.to_vec();
+    let mut proof = Vec::new();
+    while level.len() > 1 {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        level = next_level(&level);
+        index /= 2;
+    }
+    proof
+}
+
+fn next_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [a, b] => parent(a, b),
+            [a] => *a,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    #[test]
+    fn every_member_proves_against_the_root_and_outsiders_do_not() {
+        for size in 1..=9u8 {
+            let leaves: Vec<_> = (0..size).map(|i| leaf(&key(i))).collect();
+            let root = root(&leaves);
+            for (index, member) in leaves.iter().enumerate() {
+                assert!(
+                    verify(&root, member, &proof(&leaves, index)),
+                    "{size}/{index}"
+                );
+            }
+            let outsider = leaf(&key(100));
+            assert!(!verify(&root, &outsider, &proof(&leaves, 0)));
+        }
+    }
+
+    #[test]
+    fn a_proof_for_one_member_does_not_admit_another() {
+        let leaves: Vec<_> = (0..4).map(|i| leaf(&key(i))).collect();
+        let root = root(&leaves);
+        assert!(!verify(&root, &leaves[1], &proof(&leaves, 2)));
+        // Nor does an inner node pass as a leaf.
+        let inner = parent(&leaves[0], &leaves[1]);
+        assert!(!verify(&root, &inner, &[]));
+    }
+
+    #[test]
+    fn ed25519_data_round_trips_its_signer_and_message() {
+        let msg = message(&key(1), &key(2), 1_700_000_000);
+        assert_eq!(msg.len(), MESSAGE_PREFIX.len() + 32 + 32 + 8);
+        let data = ed25519_instruction_data(&key(3), &[7; 64], &msg);
+        assert_eq!(ed25519_signed(&data), Some((key(3), msg.as_slice())));
+    }
+
+    #[test]
+    fn ed25519_data_pointing_elsewhere_is_refused() {
+        let msg = message(&key(1), &key(2), 0);
+        let data = ed25519_instruction_data(&key(3), &[7; 64], &msg);
+        // Each instruction index in turn points at instruction 0 instead.
+        for index_field in [1, 3, 6] {
+            let mut tampered = data.clone();
+            tampered[HEADER + 2 * index_field..HEADER + 2 * index_field + 2]
+                .copy_from_slice(&0u16.to_le_bytes());
+            assert_eq!(ed25519_signed(&tampered), None);
+        }
+        let mut two = data.clone();
+        two[0] = 2;
+        assert_eq!(ed25519_signed(&two), None);
+        assert_eq!(ed25519_signed(&data[..HEADER + OFFSETS - 1]), None);
+    }
+}
@@ -0,0 +1,466 @@
+// Byte offsets of account fields in raw account data, the 8-byte discriminator
+// included: what clients put in getProgramAccounts `memcmp` filters.
+//
+// Borsh packs fields back to back, so only the fields ahead of an account's
+// first variable-length one (Vec, Option, or an enum with data) sit at a fixed
+// offset; those are the constants here. The rest move with the contents before
+// them, and the tests below pin them for representative contents. Fields are
+// never reordered or resized, only appended or carved out of the trailer's
+// reserved bytes (version.rs), so a failing test here is a breaking change for
+// every indexer.
+
+pub const DISCRIMINATOR: usize = 0;
+
+pub mod fund {
+    pub const OWNER: usize = 8;
+    pub const ACCOUNT_HANDLE: usize = 40;
+    pub const HAS_ACTIVE_VOTE: usize = 55;
+    pub const MEMBERS: usize = 56; // u32 length, then the pubkeys
+}
+
+pub mod proposal {
+    pub const FUND: usize = 8;
+    pub const VOTED_MASK: usize = 40;
+    pub const VOTES: usize = 42;
+    pub const AMOUNT: usize = 44;
+    pub const PROPOSER: usize = 52;
+    pub const PROPOSED_TO: usize = 84;
+    pub const NONCE: usize = 116;
+    pub const DID_MEET_THRESHOLD: usize = 124;
+    // Enum tag: Payout 0, Dissolve 1, Stream 2, CancelStream 3. Dissolve adds
+    // its distribution byte.
+    pub const KIND: usize = 125;
+}
+
+pub mod join_request {
+    pub const FUND: usize = 8;
+    pub const USER: usize = 40;
+    pub const JOIN_AMOUNT: usize = 72;
+    pub const RENT_PAYER: usize = 80;
+    pub const TRAILER: usize = 112;
+}
+
+pub mod recovery_request {
+    pub const FUND: usize = 8;
+    pub const NEW_OWNER: usize = 40;
+    pub const APPROVALS: usize = 72;
+    pub const INITIATED_AT: usize = 73;
+    pub const RENT_PAYER: usize = 81;
+    pub const TRAILER: usize = 113;
+}
+
+pub mod config {
+    pub const ADMIN: usize = 8;
+    pub const ALLOWED_MINTS: usize = 40; // u32 length, then the pubkeys
+}
+
+pub mod membership {
+    pub const MEMBER: usize = 8;
+    pub const FUND: usize = 40;
+    pub const RENT_PAYER: usize = 72;
+    pub const TRAILER: usize = 104;
+}
+
+pub mod handle_registry {
+    pub const FUND: usize = 8;
+    pub const HANDLE: usize = 40;
+    pub const RENT_PAYER: usize = 55;
+    pub const TRAILER: usize = 87;
+}
+
+pub mod dues_schedule {
+    pub const FUND: usize = 8;
+    pub const RENT_PAYER: usize = 40;
+    pub const AMOUNT: usize = 72;
+    pub const PERIOD_SECONDS: usize = 80;
+    pub const START: usize = 88;
+    pub const ARREARS: usize = 96;
+    pub const PERIODS_PAID: usize = 98; // u32 length, then a u32 per member
+}
+
+pub mod rotation {
+    pub const FUND: usize = 8;
+    pub const RENT_PAYER: usize = 40;
+    pub const DUES_START: usize = 72;
+    pub const FIRST_PERIOD: usize = 80;
+    pub const ROUND: usize = 84;
+    pub const ORDER: usize = 88; // u32 length, then a u8 per round
+}
+
+pub mod stream {
+    pub const FUND: usize = 8;
+    pub const RECIPIENT: usize = 40;
+    pub const RENT_PAYER: usize = 72;
+    pub const NONCE: usize = 104;
+    pub const START: usize = 112;
+    pub const CLIFF: usize = 120;
+    pub const END: usize = 128;
+    pub const CLAIMED: usize = 136;
+    pub const CANCELLED_AT: usize = 144; // Option tag, then the i64 if set
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::{AccountTrailer, AccountVersion};
+    use crate::*;
+
+    fn serialize<T: AccountSerialize>(account: &T) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    fn key(seed: u8) -> Pubkey {
+        Pubkey::new_from_array([seed; 32])
+    }
+
+    // Asserts `bytes` at `offset` in `data`.
+    fn at(data: &[u8], offset: usize, bytes: &[u8]) {
+        assert_eq!(
+            &data[offset..offset + bytes.len()],
+            bytes,
+            "at offset {offset}"
+        );
+    }
+
+    fn trailer_with_flags(flags: u32) -> AccountTrailer {
+        AccountTrailer {
+            flags,
+            ..AccountTrailer::CURRENT
+        }
+    }
+
+    fn at_trailer(data: &[u8], offset: usize, flags: u32) {
+        at(data, offset, &[AccountVersion::CURRENT.get()]);
+        at(data, offset + 1, &flags.to_le_bytes());
+        at(data, offset + 5, &[0; AccountTrailer::RESERVED]);
+        assert_eq!(data.len(), offset + AccountTrailer::SIZE);
+    }
+
+    #[test]
+    fn fund_fields_sit_at_their_pinned_offsets() {
+        let fund = SquadMintFund {
+            owner: key(1),
+            account_handle: *b"squad\0\0\0\0\0\0\0\0\0\0",
+            has_active_vote: true,
+            members: vec![key(1), key(2)],
+            join_amount: 1_110_000,
+            master_nonce: 9,
+            guardians: vec![key(3)],
+            guardian_threshold: 1,
+            has_pending_recovery: true,
+            recovered_owner: Some(key(4)),
+            contributions: vec![0, 1_110_000],
+            dissolution: Some(DissolutionTerms {
+                distribution: Distribution::ProRata,
+                rent_recipient: key(5),
+            }),
+            trailer: trailer_with_flags(0b101),
+        };
+        let data = serialize(&fund);
+
+        at(&data, DISCRIMINATOR, SquadMintFund::DISCRIMINATOR);
+        at(&data, fund::OWNER, key(1).as_ref());
+        at(&data, fund::ACCOUNT_HANDLE, b"squad\0\0\0\0\0\0\0\0\0\0");
+        at(&data, fund::HAS_ACTIVE_VOTE, &[1]);
+        at(&data, fund::MEMBERS, &2u32.to_le_bytes());
+        at(&data, 60, key(1).as_ref());
+        at(&data, 92, key(2).as_ref());
+        // From here on offsets move with the member count (32 bytes each).
+        at(&data, 124, &1_110_000u64.to_le_bytes()); // join_amount
+        at(&data, 132, &9u64.to_le_bytes()); // master_nonce
+        at(&data, 140, &1u32.to_le_bytes()); // guardians
+        at(&data, 144, key(3).as_ref());
+        at(&data, 176, &[1]); // guardian_threshold
+        at(&data, 177, &[1]); // has_pending_recovery
+        at(&data, 178, &[1]); // recovered_owner
+        at(&data, 179, key(4).as_ref());
+        at(&data, 211, &2u32.to_le_bytes()); // contributions
+        at(&data, 215, &0u64.to_le_bytes());
+        at(&data, 223, &1_110_000u64.to_le_bytes());
+        at(&data, 231, &[1, 1]); // dissolution: Some, ProRata
+        at(&data, 233, key(5).as_ref());
+        at_trailer(&data, 265, 0b101);
+    }
+
+    #[test]
+    fn a_full_fund_fills_its_allocation_exactly() {
+        let max_members = SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE;
+        let fund = SquadMintFund {
+            members: vec![key(1); max_members],
+            guardians: vec![key(2); SquadMintFund::SQUAD_MINT_MAX_GUARDIANS],
+            recovered_owner: Some(key(3)),
+            contributions: vec![1; max_members],
+            dissolution: Some(DissolutionTerms {
+                distribution: Distribution::Equal,
+                rent_recipient: key(4),
+            }),
+            trailer: AccountTrailer::CURRENT,
+            ..Default::default()
+        };
+        assert_eq!(serialize(&fund).len(), 8 + SquadMintFund::MAX_SIZE);
+    }
+
+    #[test]
+    fn proposal_fields_sit_at_their_pinned_offsets() {
+        let mut proposal = Transaction {
+            belongs_to_squad_mint_fund: key(1),
+            voted_mask: 0x0103,
+            votes: 0x0001,
+            message_data: TransactionMessage {
+                amount: 2_000_000,
+                proposer_account: key(2),
+                proposed_to_account: key(3),
+                nonce: 7,
+            },
+            did_meet_threshold: true,
+            kind: ProposalKind::Payout,
+            rent_payer: key(4),
+            trailer: trailer_with_flags(0),
+        };
+        let data = serialize(&proposal);
+
+        at(&data, DISCRIMINATOR, Transaction::DISCRIMINATOR);
+        at(&data, proposal::FUND, key(1).as_ref());
+        at(&data, proposal::VOTED_MASK, &0x0103u16.to_le_bytes());
+        at(&data, proposal::VOTES, &0x0001u16.to_le_bytes());
+        at(&data, proposal::AMOUNT, &2_000_000u64.to_le_bytes());
+        at(&data, proposal::PROPOSER, key(2).as_ref());
+        at(&data, proposal::PROPOSED_TO, key(3).as_ref());
+        at(&data, proposal::NONCE, &7u64.to_le_bytes());
+        at(&data, proposal::DID_MEET_THRESHOLD, &[1]);
+        at(&data, proposal::KIND, &[0]);
+        at(&data, 126, key(4).as_ref()); // rent_payer
+        at_trailer(&data, 158, 0);
+
+        // A dissolution's kind is a byte longer, and fills the allocation.
+        proposal.kind = ProposalKind::Dissolve {
+            distribution: Distribution::ProRata,
+        };
+        let data = serialize(&proposal);
+        at(&data, proposal::KIND, &[1, 1]);
+        at(&data, 127, key(4).as_ref()); // rent_payer
+        at_trailer(&data, 159, 0);
+        assert_eq!(data.len(), 8 + Transaction::MAX_SIZE);
+
+        // Stream kinds are bare tags, like Payout.
+        proposal.kind = ProposalKind::Stream;
+        at(&serialize(&proposal), proposal::KIND, &[2]);
+        proposal.kind = ProposalKind::CancelStream;
+        at(&serialize(&proposal), proposal::KIND, &[3]);
+    }
+
+    #[test]
+    fn join_request_fields_sit_at_their_pinned_offsets() {
+        let request = JoinRequestCustodialWallet {
+            request_to_join_squad_mint_fund: key(1),
+            request_to_join_user: key(2),
+            join_amount: 1_110_000,
+            rent_payer: key(3),
+            trailer: trailer_with_flags(0),
+        };
+        let data = serialize(&request);
+
+        at(
+            &data,
+            DISCRIMINATOR,
+            JoinRequestCustodialWallet::DISCRIMINATOR,
+        );
+        at(&data, join_request::FUND, key(1).as_ref());
+        at(&data, join_request::USER, key(2).as_ref());
+        at(
+            &data,
+            join_request::JOIN_AMOUNT,
+            &1_110_000u64.to_le_bytes(),
+        );
+        at(&data, join_request::RENT_PAYER, key(3).as_ref());
+        at_trailer(&data, join_request::TRAILER, 0);
+        assert_eq!(data.len(), 8 + JoinRequestCustodialWallet::MAX_SIZE);
+    }
+
+    #[test]
+    fn recovery_request_fields_sit_at_their_pinned_offsets() {
+        let request = RecoveryRequest {
+            belongs_to_squad_mint_fund: key(1),
+            new_owner: key(2),
+            approvals: 0b11,
+            initiated_at: 1_700_000_000,
+            rent_payer: key(3),
+            trailer: trailer_with_flags(0),
+        };
+        let data = serialize(&request);
+
+        at(&data, DISCRIMINATOR, RecoveryRequest::DISCRIMINATOR);
+        at(&data, recovery_request::FUND, key(1).as_ref());
+        at(&data, recovery_request::NEW_OWNER, key(2).as_ref());
+        at(&data, recovery_request::APPROVALS, &[0b11]);
+        at(
+            &data,
+            recovery_request::INITIATED_AT,
+            &1_700_000_000i64.to_le_bytes(),
+        );
+        at(&data, recovery_request::RENT_PAYER, key(3).as_ref());
+        at_trailer(&data, recovery_request::TRAILER, 0);
+        assert_eq!(data.len(), 8 + RecoveryRequest::MAX_SIZE);
+    }
+
+    #[test]
+    fn config_fields_sit_at_their_pinned_offsets() {
+        let config = Config {
+            admin: key(1),
+            allowed_mints: vec![key(2)],
+            treasury: key(3),
+            join_fee_bps: 50,
+            payout_fee_bps: 25,
+            reject_fee: 100_000,
+            trailer: trailer_with_flags(0),
+        };
+        let data = serialize(&config);
+
+        at(&data, DISCRIMINATOR, Config::DISCRIMINATOR);
+        at(&data, config::ADMIN, key(1).as_ref());
+        at(&data, config::ALLOWED_MINTS, &1u32.to_le_bytes());
+        at(&data, 44, key(2).as_ref());
+        // From here on offsets move with the mint count (32 bytes each).
+        at(&data, 76, key(3).as_ref()); // treasury
+        at(&data, 108, &50u16.to_le_bytes()); // join_fee_bps
+        at(&data, 110, &25u16.to_le_bytes()); // payout_fee_bps
+        at(&data, 112, &100_000u64.to_le_bytes()); // reject_fee
+        at_trailer(&data, 120, 0);
+    }
+
+    #[test]
+    fn membership_fields_sit_at_their_pinned_offsets() {
+        let membership = Membership {
+            member: key(1),
+            fund: key(2),
+            rent_payer: key(3),
+            trailer: trailer_with_flags(0),
+        };
+        let data = serialize(&membership);
+
+        at(&data, DISCRIMINATOR, Membership::DISCRIMINATOR);
+        at(&data, membership::MEMBER, key(1).as_ref());
+        at(&data, membership::FUND, key(2).as_ref());
+        at(&data, membership::RENT_PAYER, key(3).as_ref());
+        at_trailer(&data, membership::TRAILER, 0);
+        assert_eq!(data.len(), 8 + Membership::MAX_SIZE);
+    }
+
+    #[test]
+    fn handle_registry_fields_sit_at_their_pinned_offsets() {
+        let registry = HandleRegistry {
+            fund: key(1),
+            handle: *b"squad\0\0\0\0\0\0\0\0\0\0",
+            rent_payer: key(2),
+            trailer: trailer_with_flags(0),
+        };
+        let data = serialize(&registry);
+
+        at(&data, DISCRIMINATOR, HandleRegistry::DISCRIMINATOR);
+        at(&data, handle_registry::FUND, key(1).as_ref());
+        at(&data, handle_registry::HANDLE, b"squad\0\0\0\0\0\0\0\0\0\0");
+        at(&data, handle_registry::RENT_PAYER, key(2).as_ref());
+        at_trailer(&data, handle_registry::TRAILER, 0);
+        assert_eq!(data.len(), 8 + HandleRegistry::MAX_SIZE);
+    }
+
+    #[test]
+    fn dues_schedule_fields_sit_at_their_pinned_offsets() {
+        let schedule = DuesSchedule {
+            fund: key(1),
+            rent_payer: key(2),
+            amount: 5_000_000,
+            period_seconds: 30 * 24 * 60 * 60,
+            start: 1_700_000_000,
+            arrears: 0b101,
+            periods_paid: vec![3; SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE],
+            trailer: trailer_with_flags(0),
+        };
+        let data = serialize(&schedule);
+
+        at(&data, DISCRIMINATOR, DuesSchedule::DISCRIMINATOR);
+        at(&data, dues_schedule::FUND, key(1).as_ref());
+        at(&data, dues_schedule::RENT_PAYER, key(2).as_ref());
+        at(&data, dues_schedule::AMOUNT, &5_000_000u64.to_le_bytes());
+        at(
+            &data,
+            dues_schedule::PERIOD_SECONDS,
+            &(30i64 * 24 * 60 * 60).to_le_bytes(),
+        );
+        at(&data, dues_schedule::START, &1_700_000_000i64.to_le_bytes());
+        at(&data, dues_schedule::ARREARS, &0b101u16.to_le_bytes());
+        at(&data, dues_schedule::PERIODS_PAID, &8u32.to_le_bytes());
+        at_trailer(&data, dues_schedule::PERIODS_PAID + 4 + 8 * 4, 0);
+        assert_eq!(data.len(), 8 + DuesSchedule::MAX_SIZE);
+    }
+
+    #[test]
+    fn rosca_fields_sit_at_their_pinned_offsets() {
+        let rosca = Rosca {
+            fund: key(1),
+            rent_payer: key(2),
+            dues_start: 1_700_000_000,
+            first_period: 3,
+            round: 2,
+            order: (0..SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE as u8).collect(),
+            pots: vec![400_000; SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE],
+            trailer: trailer_with_flags(0),
+        };
+        let data = serialize(&rosca);
+
+        at(&data, DISCRIMINATOR, Rosca::DISCRIMINATOR);
+        at(&data, rotation::FUND, key(1).as_ref());
+        at(&data, rotation::RENT_PAYER, key(2).as_ref());
+        at(&data, rotation::DUES_START, &1_700_000_000i64.to_le_bytes());
+        at(&data, rotation::FIRST_PERIOD, &3u32.to_le_bytes());
+        at(&data, rotation::ROUND, &2u32.to_le_bytes());
+        at(&data, rotation::ORDER, &8u32.to_le_bytes());
+        at(&data, rotation::ORDER + 4 + 8, &8u32.to_le_bytes()); // pots
+        at(&data, rotation::ORDER + 16, &400_000u64.to_le_bytes());
+        at_trailer(&data, rotation::ORDER + 16 + 8 * 8, 0);
+        assert_eq!(data.len(), 8 + Rosca::MAX_SIZE);
+    }
+
+    #[test]
+    fn stream_fields_sit_at_their_pinned_offsets() {
+        let mut account = Stream {
+            fund: key(1),
+            recipient: key(2),
+            rent_payer: key(3),
+            nonce: 7,
+            start: 1_700_000_000,
+            cliff: 1_700_100_000,
+            end: 1_800_000_000,
+            claimed: 250_000,
+            cancelled_at: None,
+            trailer: trailer_with_flags(0),
+        };
+        let data = serialize(&account);
+
+        at(&data, DISCRIMINATOR, Stream::DISCRIMINATOR);
+        at(&data, stream::FUND, key(1).as_ref());
+        at(&data, stream::RECIPIENT, key(2).as_ref());
+        at(&data, stream::RENT_PAYER, key(3).as_ref());
+        at(&data, stream::NONCE, &7u64.to_le_bytes());
+        at(&data, stream::START, &1_700_000_000i64.to_le_bytes());
+        at(&data, stream::CLIFF, &1_700_100_000i64.to_le_bytes());
+        at(&data, stream::END, &1_800_000_000i64.to_le_bytes());
+        at(&data, stream::CLAIMED, &250_000u64.to_le_bytes());
+        at(&data, stream::CANCELLED_AT, &[0]);
+        at_trailer(&data, stream::CANCELLED_AT + 1, 0);
+
+        // A cancelled stream fills the allocation.
+        account.cancelled_at = Some(1_750_000_000);
+        let data = serialize(&account);
+        at(&data, stream::CANCELLED_AT, &[1]);
+        at(
+            &data,
+            stream::CANCELLED_AT + 1,
+            &1_750_000_000i64.to_le_bytes(),
+        );
+        at_trailer(&data, stream::CANCELLED_AT + 9, 0);
+        assert_eq!(data.len(), 8 + Stream::MAX_SIZE);
+    }
+}
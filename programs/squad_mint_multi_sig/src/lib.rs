@@ -4,27 +4,42 @@
 #![allow(deprecated)]
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::system_program;
 use anchor_lang::AccountsClose;
 
 use anchor_spl::{
-    associated_token::{get_associated_token_address, AssociatedToken},
-    token::{close_account, transfer_checked, CloseAccount, TransferChecked},
-    token_interface::{Mint, TokenAccount, TokenInterface},
+    associated_token::{
+        create_idempotent, get_associated_token_address_with_program_id, AssociatedToken, Create,
+    },
+    token_interface::{
+        close_account, harvest_withheld_tokens_to_mint, transfer_checked, CloseAccount,
+        HarvestWithheldTokensToMint, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
 };
+use solana_instructions_sysvar::{load_current_index_checked, load_instruction_at_checked};
 
-declare_id!("BW1dtKfuqUPZxyYKfFCgUwo8tzqnGfw9of5L4yfAzuRz");
+pub mod dues;
+pub mod fees;
+pub mod invite;
+pub mod layout;
+pub mod rosca;
+pub mod token_extensions;
+pub mod version;
+pub mod vesting;
+pub mod vote;
+
+#[cfg(kani)]
+mod proofs;
 
-#[cfg(feature = "mainnet")]
-pub const USDC_MINT: Pubkey = Pubkey::from_str_const(env!(
-    "SQUADMINT_USDC_MINT",
-    "mainnet builds must set SQUADMINT_USDC_MINT (e.g. EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v)"
-));
+use fees::{FeeKind, ProtocolFeeCharged};
+use invite::Invite;
+use version::{AccountTrailer, AccountVersion};
+use vote::{Outcome, Thresholds};
 
-#[cfg(not(feature = "mainnet"))]
-pub const USDC_MINT: Pubkey = Pubkey::from_str_const(match option_env!("SQUADMINT_USDC_MINT") {
-    Some(value) => value,
-    None => "37KQMrbBtkNFYJvDKW3tGxEs1WuvqcEeu44JGrjPkYsz",
-});
+declare_id!("BW1dtKfuqUPZxyYKfFCgUwo8tzqnGfw9of5L4yfAzuRz");
 
 // TODO: check is we need emit certain events as well to capture off app actions (FUTURE)
 #[program]
@@ -35,6 +50,8 @@ pub mod squad_mint_multi_sig {
         ctx: Context<Initialize>,
         account_handle: [u8; SquadMintFund::SQUAD_MINT_MAX_HANDLE_SIZE],
         join_amount: u64,
+        guardians: Vec<Pubkey>,
+        guardian_threshold: u8,
     ) -> Result<()> {
         msg!("Greetings from: {:?}", ctx.program_id);
         // The handle is a fixed [u8; 15] (the UTF-8 string left-aligned, NUL-padded
@@ -44,17 +61,28 @@ pub mod squad_mint_multi_sig {
             ErrorCode::HandleLenNotValid
         );
         require!(
-            join_amount >= SquadMintFund::SQUAD_MINT_MIN_AMOUNT,
+            join_amount >= SquadMintFund::min_amount(ctx.accounts.mint.decimals),
             ErrorCode::InsufficientJoiningAmount
         );
+        token_extensions::check_mint(&ctx.accounts.mint.to_account_info().try_borrow_data()?)?;
         let fund = &mut ctx.accounts.multisig;
         msg!("Account address: {} ", fund.key());
         fund.owner = *ctx.accounts.multisig_owner.key;
         fund.members.push(*ctx.accounts.multisig_owner.key); // This is possibly waste of space, needs a better design (maybe), user exist in two places
+        fund.contributions.push(0); // the owner pays no join deposit
         fund.has_active_vote = false;
         fund.master_nonce = 0;
         fund.join_amount = join_amount;
         fund.account_handle = account_handle;
+        // Guardians are optional: an empty list with a 0 threshold opts out of
+        // social recovery (it can be enabled later via set_guardians).
+        validate_guardians(&fund.owner, &guardians, guardian_threshold)?;
+        fund.guardians = guardians;
+        fund.guardian_threshold = guardian_threshold;
+        fund.trailer = AccountTrailer::CURRENT;
+        ctx.accounts
+            .membership
+            .record(fund.key(), fund.owner, ctx.accounts.fee_payer.key());
 
         Ok(())
     }
@@ -67,12 +95,13 @@ pub mod squad_mint_multi_sig {
         let multisig = &mut ctx.accounts.multisig;
         let join_custodial_account = &mut ctx.accounts.join_custodial_account;
 
+        require!(multisig.dissolution.is_none(), ErrorCode::FundDissolving);
         require!(
             multisig.members.len() < SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE,
             ErrorCode::MaxMembersReached
         );
         require_keys_eq!(
-            multisig.owner.key(),
+            multisig.effective_owner(),
             *ctx.accounts.multisig_owner.key,
             ErrorCode::CannotAddMember
         );
@@ -96,7 +125,7 @@ pub mod squad_mint_multi_sig {
             ErrorCode::JoinRequestFundMismatch
         );
         require!(
-            join_custodial_account.join_amount == multisig.join_amount,
+            multisig.accepts_join_amount(join_custodial_account.join_amount),
             ErrorCode::JoinAmountMismatch
         );
 
@@ -116,19 +145,56 @@ pub mod squad_mint_multi_sig {
 
         let signer_seeds = &[&join_custodial_account_seeds[..]];
 
+        let config = &ctx.accounts.config;
+        let join_fee = fees::bps_of(join_custodial_account.join_amount, config.join_fee_bps)
+            .min(ctx.accounts.join_custodial_account_ata.amount);
+        pay_protocol_fee(
+            &ctx.accounts.token_program,
+            &ctx.accounts.mint,
+            &ctx.accounts.join_custodial_account_ata,
+            join_custodial_account.to_account_info(),
+            &ctx.accounts.treasury_ata,
+            signer_seeds,
+            ProtocolFeeCharged {
+                fund: multisig_key,
+                kind: FeeKind::Join,
+                mint: ctx.accounts.mint.key(),
+                amount: join_fee,
+                treasury: config.treasury,
+            },
+        )?;
+        ctx.accounts.join_custodial_account_ata.reload()?;
+
+        // The rest of the escrow moves, not `join_amount`: with a transfer-fee
+        // mint it holds the deposit grossed up for this hop. The member is
+        // credited with what actually reached the vault.
+        let vault_before = ctx.accounts.multisig_ata.amount;
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, transfer_cpi, signer_seeds);
         transfer_checked(
             cpi_ctx,
-            join_custodial_account.join_amount,
+            ctx.accounts.join_custodial_account_ata.amount,
             ctx.accounts.mint.decimals,
         )?;
+        ctx.accounts.multisig_ata.reload()?;
+        let deposited = ctx.accounts.multisig_ata.amount - vault_before;
 
+        multisig.backfill_contributions();
         multisig.members.push(new_member);
+        multisig.contributions.push(deposited);
+        ctx.accounts
+            .membership
+            .record(multisig_key, new_member, ctx.accounts.fee_payer.key());
+
+        harvest_withheld_fees(
+            &ctx.accounts.token_program,
+            &ctx.accounts.mint,
+            &ctx.accounts.join_custodial_account_ata,
+        )?;
 
         let close_ata_cpi = CloseAccount {
             account: ctx.accounts.join_custodial_account_ata.to_account_info(),
-            destination: ctx.accounts.fee_payer.to_account_info(),
+            destination: ctx.accounts.rent_payer.to_account_info(),
             authority: join_custodial_account.to_account_info(),
         };
         close_account(CpiContext::new_with_signer(
@@ -141,7 +207,7 @@ pub mod squad_mint_multi_sig {
             new_member.key(),
             multisig.key() ,
             multisig.members.len(),
-            join_custodial_account.join_amount,
+            deposited,
             ctx.accounts.multisig_ata.key(),
             join_custodial_account.key(),
             ctx.accounts.join_custodial_account_ata.key());
@@ -159,7 +225,7 @@ pub mod squad_mint_multi_sig {
         let join_custodial_account = &mut ctx.accounts.join_custodial_account;
 
         require_keys_eq!(
-            multisig.owner.key(),
+            multisig.effective_owner(),
             *ctx.accounts.multisig_owner.key,
             ErrorCode::CannotAddMember
         );
@@ -182,17 +248,15 @@ pub mod squad_mint_multi_sig {
             multisig_key,
             ErrorCode::JoinRequestFundMismatch
         );
-        require!(
-            join_custodial_account.join_amount == multisig.join_amount,
-            ErrorCode::JoinAmountMismatch
-        );
+        // No amount check: a request escrowed under an earlier join range
+        // must still be refundable.
 
         let transfer_cpi = TransferChecked {
             from: ctx.accounts.join_custodial_account_ata.to_account_info(),
             to: ctx.accounts.proposing_joiner_ata.to_account_info(),
             authority: join_custodial_account.to_account_info(),
             mint: ctx.accounts.mint.to_account_info(),
-        }; // TODO: To avoid abuse we need to take a small fee.
+        };
 
         let join_custodial_account_seeds = &[
             b"join_custodial_account",
@@ -203,17 +267,41 @@ pub mod squad_mint_multi_sig {
 
         let signer_seeds = &[&join_custodial_account_seeds[..]];
 
+        // The flat reject fee makes spamming a fund with join requests cost
+        // something; the rest of the escrow is refunded.
+        let config = &ctx.accounts.config;
+        let reject_fee = fees::in_mint_units(config.reject_fee, ctx.accounts.mint.decimals)
+            .min(ctx.accounts.join_custodial_account_ata.amount);
+        pay_protocol_fee(
+            &ctx.accounts.token_program,
+            &ctx.accounts.mint,
+            &ctx.accounts.join_custodial_account_ata,
+            join_custodial_account.to_account_info(),
+            &ctx.accounts.treasury_ata,
+            signer_seeds,
+            ProtocolFeeCharged {
+                fund: multisig_key,
+                kind: FeeKind::Reject,
+                mint: ctx.accounts.mint.key(),
+                amount: reject_fee,
+                treasury: config.treasury,
+            },
+        )?;
+        ctx.accounts.join_custodial_account_ata.reload()?;
+        let refunded = ctx.accounts.join_custodial_account_ata.amount;
+
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, transfer_cpi, signer_seeds);
-        transfer_checked(
-            cpi_ctx,
-            join_custodial_account.join_amount,
-            ctx.accounts.mint.decimals,
+        transfer_checked(cpi_ctx, refunded, ctx.accounts.mint.decimals)?;
+        harvest_withheld_fees(
+            &ctx.accounts.token_program,
+            &ctx.accounts.mint,
+            &ctx.accounts.join_custodial_account_ata,
         )?;
 
         let close_ata_cpi = CloseAccount {
             account: ctx.accounts.join_custodial_account_ata.to_account_info(),
-            destination: ctx.accounts.fee_payer.to_account_info(),
+            destination: ctx.accounts.rent_payer.to_account_info(),
             authority: join_custodial_account.to_account_info(),
         };
         close_account(CpiContext::new_with_signer(
@@ -226,7 +314,7 @@ pub mod squad_mint_multi_sig {
             new_member.key(),
             multisig.key() ,
             multisig.members.len(),
-            join_custodial_account.join_amount,
+            refunded,
             ctx.accounts.proposing_joiner.key(),
             join_custodial_account.key(),
             ctx.accounts.join_custodial_account_ata.key());
@@ -234,9 +322,11 @@ pub mod squad_mint_multi_sig {
         Ok(())
     }
 
+    // `invite` is only looked at when the fund requires one (set_invite_policy).
     pub fn initiate_join_request(
         ctx: Context<CreateJoinRequestProposal>,
         join_amount: u64,
+        invite: Option<Invite>,
     ) -> Result<()> {
         msg!(
             "Create join request proposal, called from: {:?} amount {:?}",
@@ -246,16 +336,21 @@ pub mod squad_mint_multi_sig {
         let multisig = &mut ctx.accounts.multisig;
         let join_custodial_account = &mut ctx.accounts.join_custodial_account;
         let proposing_joiner = &mut ctx.accounts.proposing_joiner;
-        let proposing_joiner_ata: Pubkey =
-            get_associated_token_address(&proposing_joiner.key(), &ctx.accounts.mint.key());
+        let proposing_joiner_ata: Pubkey = get_associated_token_address_with_program_id(
+            &proposing_joiner.key(),
+            &ctx.accounts.mint.key(),
+            ctx.accounts.token_program.key,
+        );
         require_keys_eq!(
             ctx.accounts.proposing_joiner_ata.key(),
             proposing_joiner_ata,
             ErrorCode::InvalidDestinationOwner
         );
 
+        require!(multisig.dissolution.is_none(), ErrorCode::FundDissolving);
+        require!(!multisig.open_admission(), ErrorCode::FundIsOpen);
         require!(
-            join_amount == multisig.join_amount,
+            multisig.accepts_join_amount(join_amount),
             ErrorCode::JoiningAmountShouldMatchTargetWallet
         );
         require!(
@@ -266,6 +361,14 @@ pub mod squad_mint_multi_sig {
             multisig.members.len() < SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE,
             ErrorCode::MaxMembersReached
         );
+        if multisig.requires_invite() {
+            check_invite(
+                multisig,
+                proposing_joiner.key,
+                invite.ok_or(ErrorCode::InviteRequired)?,
+                &ctx.accounts.instructions_sysvar,
+            )?;
+        }
 
         let transfer_cpi = TransferChecked {
             from: ctx.accounts.proposing_joiner_ata.to_account_info(),
@@ -274,13 +377,218 @@ pub mod squad_mint_multi_sig {
             mint: ctx.accounts.mint.to_account_info(),
         };
 
+        // With a transfer-fee mint both hops (joiner -> escrow now, escrow ->
+        // vault on add_member) are charged, so the joiner sends enough that
+        // `join_amount` is what reaches the vault at the current fee.
+        let epoch = Clock::get()?.epoch;
+        let (escrowed, sent) = {
+            let mint_data = ctx.accounts.mint.to_account_info();
+            let mint_data = mint_data.try_borrow_data()?;
+            let escrowed = token_extensions::gross_amount(&mint_data, epoch, join_amount)?;
+            let sent = token_extensions::gross_amount(&mint_data, epoch, escrowed)?;
+            (escrowed, sent)
+        };
+
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new(cpi_program, transfer_cpi);
-        transfer_checked(cpi_ctx, join_amount, ctx.accounts.mint.decimals)?;
+        transfer_checked(cpi_ctx, sent, ctx.accounts.mint.decimals)?;
+        msg!(
+            "Escrowed {} for join amount {} (sent {})",
+            escrowed,
+            join_amount,
+            sent
+        );
         join_custodial_account.request_to_join_user = proposing_joiner.key();
         join_custodial_account.join_amount = join_amount;
         join_custodial_account.request_to_join_squad_mint_fund = multisig.key();
+        join_custodial_account.rent_payer = ctx.accounts.fee_payer.key();
+        join_custodial_account.trailer = AccountTrailer::CURRENT;
+
+        Ok(())
+    }
+
+    // initiate_join_request for a fund with open admission: the same checks,
+    // but the deposit goes straight into the vault and the joiner becomes a
+    // member in this instruction, with no escrow and no add_member. A separate
+    // instruction because the escrow accounts there are created unconditionally.
+    pub fn join_open_fund(
+        ctx: Context<JoinOpenFund>,
+        join_amount: u64,
+        invite: Option<Invite>,
+    ) -> Result<()> {
+        let multisig_key = ctx.accounts.multisig.key();
+        let joiner = ctx.accounts.joiner.key();
+        let multisig = &mut ctx.accounts.multisig;
+
+        require!(multisig.open_admission(), ErrorCode::FundNotOpen);
+        require!(multisig.dissolution.is_none(), ErrorCode::FundDissolving);
+        require!(
+            multisig.accepts_join_amount(join_amount),
+            ErrorCode::JoiningAmountShouldMatchTargetWallet
+        );
+        require!(
+            !multisig.members.contains(&joiner),
+            ErrorCode::DuplicateMember
+        );
+        require!(
+            multisig.members.len() < SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE,
+            ErrorCode::MaxMembersReached
+        );
+        if multisig.requires_invite() {
+            check_invite(
+                multisig,
+                &joiner,
+                invite.ok_or(ErrorCode::InviteRequired)?,
+                &ctx.accounts.instructions_sysvar,
+            )?;
+        }
+
+        // The join fee comes out of `join_amount` as it would from the escrow.
+        let config = &ctx.accounts.config;
+        let join_fee = fees::bps_of(join_amount, config.join_fee_bps);
+        pay_protocol_fee(
+            &ctx.accounts.token_program,
+            &ctx.accounts.mint,
+            &ctx.accounts.joiner_ata,
+            ctx.accounts.joiner.to_account_info(),
+            &ctx.accounts.treasury_ata,
+            &[],
+            ProtocolFeeCharged {
+                fund: multisig_key,
+                kind: FeeKind::Join,
+                mint: ctx.accounts.mint.key(),
+                amount: join_fee,
+                treasury: config.treasury,
+            },
+        )?;
+
+        // One hop instead of two, so the rest is grossed up once. The member
+        // is credited with what actually reached the vault.
+        let sent = {
+            let mint_data = ctx.accounts.mint.to_account_info();
+            let mint_data = mint_data.try_borrow_data()?;
+            token_extensions::gross_amount(&mint_data, Clock::get()?.epoch, join_amount - join_fee)?
+        };
+        let vault_before = ctx.accounts.multisig_ata.amount;
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.joiner_ata.to_account_info(),
+                    to: ctx.accounts.multisig_ata.to_account_info(),
+                    authority: ctx.accounts.joiner.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+            ),
+            sent,
+            ctx.accounts.mint.decimals,
+        )?;
+        ctx.accounts.multisig_ata.reload()?;
+        let deposited = ctx.accounts.multisig_ata.amount - vault_before;
+
+        multisig.backfill_contributions();
+        multisig.members.push(joiner);
+        multisig.contributions.push(deposited);
+        ctx.accounts
+            .membership
+            .record(multisig_key, joiner, ctx.accounts.fee_payer.key());
+
+        msg!(
+            "Joined open fund: {} | fund {}. Total members: {} | Deposited {} (sent {})",
+            joiner,
+            multisig_key,
+            multisig.members.len(),
+            deposited,
+            sent
+        );
+        Ok(())
+    }
+
+    // Permissionless refund of a join escrow the fund can no longer accept:
+    // the fund filled up after the request, moved its join range away from the
+    // escrowed amount, is winding down, or has already been dissolved
+    // (closed). While the fund can still accept the request, release stays
+    // the owner's call (add_member / reject_member).
+    pub fn cancel_stale_join_request(ctx: Context<CancelStaleJoinRequest>) -> Result<()> {
+        let multisig_key = ctx.accounts.multisig.key();
+        let joiner_key = ctx.accounts.proposing_joiner.key();
+        let join_custodial_account = &ctx.accounts.join_custodial_account;
+
+        require_keys_eq!(
+            join_custodial_account.request_to_join_user,
+            joiner_key,
+            ErrorCode::JoinRequestUserMismatch
+        );
+        require_keys_eq!(
+            join_custodial_account.request_to_join_squad_mint_fund,
+            multisig_key,
+            ErrorCode::JoinRequestFundMismatch
+        );
+
+        let multisig_info = ctx.accounts.multisig.to_account_info();
+        let reason = if multisig_info.data_is_empty() || *multisig_info.owner != crate::ID {
+            "fund dissolved"
+        } else {
+            let fund = SquadMintFund::try_deserialize(&mut &multisig_info.try_borrow_data()?[..])?;
+            if fund.dissolution.is_some() {
+                "fund dissolving"
+            } else if fund.members.len() >= SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE {
+                "fund full"
+            } else if !fund.accepts_join_amount(join_custodial_account.join_amount) {
+                "join range changed"
+            } else {
+                return err!(ErrorCode::JoinRequestNotStale);
+            }
+        };
+
+        let join_custodial_account_seeds = &[
+            b"join_custodial_account",
+            multisig_key.as_ref(),
+            joiner_key.as_ref(),
+            &[ctx.bumps.join_custodial_account],
+        ];
+        let signer_seeds = &[&join_custodial_account_seeds[..]];
+
+        let transfer_cpi = TransferChecked {
+            from: ctx.accounts.join_custodial_account_ata.to_account_info(),
+            to: ctx.accounts.proposing_joiner_ata.to_account_info(),
+            authority: join_custodial_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+        };
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                transfer_cpi,
+                signer_seeds,
+            ),
+            ctx.accounts.join_custodial_account_ata.amount,
+            ctx.accounts.mint.decimals,
+        )?;
+        harvest_withheld_fees(
+            &ctx.accounts.token_program,
+            &ctx.accounts.mint,
+            &ctx.accounts.join_custodial_account_ata,
+        )?;
 
+        let close_ata_cpi = CloseAccount {
+            account: ctx.accounts.join_custodial_account_ata.to_account_info(),
+            destination: ctx.accounts.rent_payer.to_account_info(),
+            authority: join_custodial_account.to_account_info(),
+        };
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            close_ata_cpi,
+            signer_seeds,
+        ))?;
+
+        msg!(
+            "Cancelled stale join request ({}) | joiner: {} | fund {} | Refunded {} | Closing {}",
+            reason,
+            joiner_key,
+            multisig_key,
+            join_custodial_account.join_amount,
+            join_custodial_account.key()
+        );
         Ok(())
     }
 
@@ -300,21 +608,27 @@ pub mod squad_mint_multi_sig {
         let transaction = &mut ctx.accounts.transaction;
         let multisig = &mut ctx.accounts.multisig;
         let proposer = ctx.accounts.proposer.key();
-        require_keys_eq!(
-            ctx.accounts.proposed_to_owner.key(),
-            proposed_to_account,
+        // L-2: only the recipient *owner* is recorded here; its ATA is derived
+        // and created at execution, so proposing costs no recipient rent. The
+        // fund and its vault can't be recipients: an ATA they own would be
+        // unreachable by any instruction.
+        require!(
+            proposed_to_account != Pubkey::default()
+                && proposed_to_account != multisig.key()
+                && proposed_to_account != ctx.accounts.multisig_ata.key(),
             ErrorCode::InvalidDestinationOwner
         );
         require!(
             !multisig.has_active_vote,
             ErrorCode::CanOnlyInitOneVoteAtATime
         );
+        require!(multisig.dissolution.is_none(), ErrorCode::FundDissolving);
         require!(
             multisig.members.contains(&proposer),
             ErrorCode::MemberNotPartOfFund
         );
         require!(
-            amount >= SquadMintFund::SQUAD_MINT_MIN_AMOUNT,
+            amount >= SquadMintFund::min_amount(ctx.accounts.mint.decimals),
             ErrorCode::InvalidProposalAmount
         );
         require!(
@@ -337,10 +651,13 @@ pub mod squad_mint_multi_sig {
             .iter()
             .position(|m| m == &proposer)
             .ok_or(ErrorCode::MemberNotPartOfFund)?;
-        let proposer_bit = 1u16 << proposer_index;
+        let proposer_bit = vote::member_bit(proposer_index);
         transaction.voted_mask = proposer_bit; // proposer has voted
         transaction.votes = proposer_bit; // ...and the vote is YES
         transaction.did_meet_threshold = false;
+        transaction.kind = ProposalKind::Payout;
+        transaction.rent_payer = ctx.accounts.fee_payer.key();
+        transaction.trailer = AccountTrailer::CURRENT;
         multisig.has_active_vote = true;
         // This Transaction's rent is auto-reclaimed in submit_and_execute when
         // the proposal is decided (no separate client-side close needed).
@@ -377,9 +694,10 @@ pub mod squad_mint_multi_sig {
             transaction.message_data.proposed_to_account,
             ErrorCode::InvalidDestinationOwner
         );
-        let proposed_to_ata: Pubkey = get_associated_token_address(
+        let proposed_to_ata: Pubkey = get_associated_token_address_with_program_id(
             &transaction.message_data.proposed_to_account,
             &ctx.accounts.mint.key(),
+            ctx.accounts.token_program.key,
         );
         require_keys_eq!(
             proposed_to_ata,
@@ -395,7 +713,7 @@ pub mod squad_mint_multi_sig {
             .iter()
             .position(|m| m == &ctx.accounts.submitter.key())
             .ok_or(ErrorCode::MemberNotPartOfFund)?;
-        let bit = 1u16 << member_index;
+        let bit = vote::member_bit(member_index);
 
         let submitter_has_voted = transaction.voted_mask & bit != 0;
         if !submitter_has_voted {
@@ -413,24 +731,60 @@ pub mod squad_mint_multi_sig {
         }
 
         // YES = set bits in `votes`. NO = voted but not YES (voted_mask & !votes).
-        // Plain count_ones() is correct because members can't be deactivated while
-        // a proposal is open, so every set bit belongs to a currently-active member.
-        let yes_votes = transaction.votes.count_ones() as u64;
-        let no_votes = (transaction.voted_mask & !transaction.votes).count_ones() as u64;
-        let total_members = multisig.members.len() as u64;
-        let yes_threshold = SquadMintFund::SQUAD_MINT_YES_THRESHOLD_PERCENTAGE;
-        let no_threshold = SquadMintFund::SQUAD_MINT_NO_THRESHOLD_PERCENTAGE;
-        let yes_meets = yes_votes * 100 >= yes_threshold * total_members;
-        let no_meets = no_votes * 100 >= no_threshold * total_members;
-        if yes_meets || no_meets {
+        let outcome = vote::tally(
+            transaction.voted_mask,
+            transaction.votes,
+            multisig.members.len() as u64,
+            Thresholds::SQUAD_MINT,
+        );
+        let yes_meets = outcome == Outcome::Approved;
+        if outcome != Outcome::Pending {
             msg!("threshold met closing proposal on exit");
             transaction.did_meet_threshold = yes_meets;
             multisig.has_active_vote = false;
             multisig.master_nonce = multisig
-                .master_nonce
-                .checked_add(1)
+                .next_master_nonce()
                 .ok_or(ErrorCode::NonceOverflow)?;
-            if yes_meets {
+            if let (true, ProposalKind::Dissolve { distribution }) = (yes_meets, transaction.kind) {
+                // Approval only locks the fund into winding down; the payout
+                // and account closes happen in the permissionless dissolve_fund.
+                multisig.dissolution = Some(DissolutionTerms {
+                    distribution,
+                    rent_recipient: transaction.message_data.proposed_to_account,
+                });
+                msg!(
+                    "Dissolution approved for fund {}. Rent recipient: {}",
+                    multisig.key(),
+                    transaction.message_data.proposed_to_account
+                );
+            } else if let (true, ProposalKind::CancelStream) = (yes_meets, transaction.kind) {
+                // proposed_to_owner is the stream and proposed_to_ata its
+                // escrow (both checked above). A stream closed since the
+                // proposal has nothing left to cancel; an open one must be
+                // passed, or the last voter could swallow the cancel.
+                match ctx.accounts.stream.as_mut() {
+                    Some(stream) => {
+                        require_keys_eq!(
+                            stream.key(),
+                            transaction.message_data.proposed_to_account,
+                            ErrorCode::StreamMismatch
+                        );
+                        cancel_stream(
+                            stream,
+                            &ctx.accounts.proposed_to_ata,
+                            &ctx.accounts.multisig_ata,
+                            &ctx.accounts.mint,
+                            &ctx.accounts.token_program,
+                        )?;
+                    }
+                    None => require!(
+                        ctx.accounts.proposed_to_owner.data_is_empty(),
+                        ErrorCode::StreamMismatch
+                    ),
+                }
+            } else if yes_meets {
+                // Payout and Stream alike: a Stream proposal's recipient is
+                // the stream, so this funds its escrow.
                 msg!(
                     "Attempting to send funds to {:?} and multisig Key: {:?}",
                     ctx.accounts.proposed_to_ata.key(),
@@ -441,6 +795,20 @@ pub mod squad_mint_multi_sig {
                     ctx.accounts.multisig_ata.amount >= amount,
                     ErrorCode::InsufficientFunds
                 );
+                // The recipient ATA is only materialised now that a payout is
+                // certain. Owners may be wallets or PDAs (off-curve): the ATA
+                // program derives and creates both the same way.
+                create_idempotent(CpiContext::new(
+                    ctx.accounts.associated_token_program.to_account_info(),
+                    Create {
+                        payer: ctx.accounts.fee_payer.to_account_info(),
+                        associated_token: ctx.accounts.proposed_to_ata.to_account_info(),
+                        authority: ctx.accounts.proposed_to_owner.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                        system_program: ctx.accounts.system_program.to_account_info(),
+                        token_program: ctx.accounts.token_program.to_account_info(),
+                    },
+                ))?;
                 let multisig_owner_key = multisig.owner.key();
                 let multisig_seeds = &[
                     multisig.account_handle.as_ref(),
@@ -461,12 +829,31 @@ pub mod squad_mint_multi_sig {
                     cpi_accounts,
                     signer_seeds,
                 );
-                transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+                // The vault pays exactly the amount voted; the fee comes out
+                // of what the recipient receives.
+                let payout_fee = fees::bps_of(amount, ctx.accounts.config.payout_fee_bps);
+                transfer_checked(cpi_ctx, amount - payout_fee, ctx.accounts.mint.decimals)?;
+                pay_protocol_fee(
+                    &ctx.accounts.token_program,
+                    &ctx.accounts.mint,
+                    &ctx.accounts.multisig_ata,
+                    multisig.to_account_info(),
+                    &ctx.accounts.treasury_ata,
+                    signer_seeds,
+                    ProtocolFeeCharged {
+                        fund: multisig.key(),
+                        kind: FeeKind::Payout,
+                        mint: ctx.accounts.mint.key(),
+                        amount: payout_fee,
+                        treasury: ctx.accounts.config.treasury,
+                    },
+                )?;
 
                 msg!(
-                    "TRANSFERRED {} to {}",
-                    amount,
-                    transaction.message_data.proposed_to_account
+                    "TRANSFERRED {} to {} (protocol fee {})",
+                    amount - payout_fee,
+                    transaction.message_data.proposed_to_account,
+                    payout_fee
                 );
             }
             msg!(
@@ -476,181 +863,2581 @@ pub mod squad_mint_multi_sig {
             );
             ctx.accounts
                 .transaction
-                .close(ctx.accounts.fee_payer.to_account_info())?;
+                .close(ctx.accounts.rent_payer.to_account_info())?;
             return Ok(());
         }
         Ok(())
     }
-}
-
-#[derive(Accounts)]
-#[instruction(account_handle: [u8; SquadMintFund::SQUAD_MINT_MAX_HANDLE_SIZE])]
-pub struct Initialize<'info> {
-    #[account(mut)]
-    pub fee_payer: Signer<'info>,
-    #[account(signer)]
-    pub multisig_owner: Signer<'info>,
-    #[account(
-        init,
-        seeds = [account_handle.as_ref(), multisig_owner.key().as_ref()],
-        bump,
-        payer = fee_payer,
-        space = 8 + SquadMintFund::MAX_SIZE
-    )]
-    pub multisig: Account<'info, SquadMintFund>,
-    #[account(
-        constraint = mint.key() == USDC_MINT @ ErrorCode::InvalidMint
-    )]
-    pub mint: InterfaceAccount<'info, Mint>,
-    #[account(
-        init,
-        payer = fee_payer,
-        seeds = [b"token_vault", multisig.key().as_ref()],
-        bump,
-        token::mint = mint,
-        token::authority = multisig,
-        token::token_program = token_program,
-    )]
-    pub multisig_ata: InterfaceAccount<'info, TokenAccount>,
 
-    // PROGRAMS
-    pub token_program: Interface<'info, TokenInterface>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
-}
+    // ---- Admission ----------------------------------------------------------
+    // Who may open a join request. By default anyone; with `require_invite`
+    // only holders of an owner-signed invite or, when `invite_root` is set, of
+    // a proof that they are on its allowlist (see invite.rs). The policy lives
+    // in the fund's trailer, so v1 funds must be migrated first.
 
-#[account]
-#[derive(Default, Debug)]
-pub struct SquadMintFund {
-    owner: Pubkey, // This is the person that init creating this fund he will soon add contributors
-    // Fixed-width handle: exactly SQUAD_MINT_MAX_HANDLE_SIZE (15) bytes — the UTF-8
-    // handle left-aligned and right-padded with trailing NUL (0) bytes. The client
-    // pads the string before calling `initialize`. The same 15 bytes are used as a
-    // PDA seed here and on every re-derivation, so derivation is always identical.
-    account_handle: [u8; SquadMintFund::SQUAD_MINT_MAX_HANDLE_SIZE],
-    has_active_vote: bool,
-    members: Vec<Pubkey>,
-    join_amount: u64,  // u32
-    master_nonce: u64, // u32
-}
-//
-#[derive(Accounts)]
-pub struct CreateProposal<'info> {
-    // This is a payment proposal
-    #[account(init,
-              payer = fee_payer,
-              seeds = [b"proposal_tx_data", multisig.key().as_ref(), multisig.master_nonce.to_le_bytes().as_ref()],
-              bump,
-              space = 8 + Transaction::MAX_SIZE)]
-    pub transaction: Account<'info, Transaction>,
-    #[account(mut)]
-    pub multisig: Account<'info, SquadMintFund>,
-    #[account(mut)]
-    pub fee_payer: Signer<'info>,
-    #[account(
-        signer,
-        constraint = multisig.members.contains(&proposer.key()) @ ErrorCode::MemberNotPartOfFund
-    )]
-    pub proposer: Signer<'info>,
-    pub mint: InterfaceAccount<'info, Mint>,
-    /// CHECK: Validated via `proposed_to_account` in handler
-    pub proposed_to_owner: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        seeds = [b"token_vault", multisig.key().as_ref()],
-        bump,
-        token::mint = mint,
-        token::authority = multisig,
-        token::token_program = token_program
-    )]
-    pub multisig_ata: InterfaceAccount<'info, TokenAccount>,
-    #[account(
-        init_if_needed,
-        payer = fee_payer,
-        associated_token::mint = mint,
-        associated_token::authority = proposed_to_owner,
-        associated_token::token_program = token_program
-    )]
-    pub proposed_to_ata: InterfaceAccount<'info, TokenAccount>,
+    pub fn set_invite_policy(
+        ctx: Context<SetAdmissionPolicy>,
+        require_invite: bool,
+        invite_root: Option<[u8; 32]>,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        multisig
+            .trailer
+            .set(SquadMintFund::FLAG_REQUIRE_INVITE, require_invite);
+        multisig.set_invite_root(invite_root);
 
-    // Programs
-    pub token_program: Interface<'info, TokenInterface>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
-}
+        msg!(
+            "Invite policy updated | fund {} | require invite: {} | allowlist: {}",
+            multisig.key(),
+            require_invite,
+            invite_root.is_some()
+        );
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct CreateJoinRequestProposal<'info> {
-    pub proposing_joiner: Signer<'info>,
-    #[account(mut)]
-    pub multisig: Account<'info, SquadMintFund>, // the multi sig we are requesting to join
-    #[account(mut)]
-    pub fee_payer: Signer<'info>,
-    #[account(
-        constraint = mint.key() == USDC_MINT @ ErrorCode::InvalidMint
-    )]
-    pub mint: InterfaceAccount<'info, Mint>,
-    #[account(init,
-              payer = fee_payer,
-              seeds = [b"join_custodial_account", multisig.key().as_ref(), proposing_joiner.key().as_ref()],
-              bump,
-              space = 8 + JoinRequestCustodialWallet::MAX_SIZE)]
-    pub join_custodial_account: Account<'info, JoinRequestCustodialWallet>,
-    #[account(
-        init,
-        payer = fee_payer,
-        seeds = [b"join_custodial_account_ata", join_custodial_account.key().as_ref()],
-        bump,
-        token::mint = mint,
-        token::authority = join_custodial_account,
-        token::token_program = token_program
-    )]
-    pub join_custodial_account_ata: InterfaceAccount<'info, TokenAccount>, // we can close this account and get our money back
-    #[account(
-        mut,
-        associated_token::mint = mint,
-        associated_token::authority = proposing_joiner,
-        associated_token::token_program = token_program
-    )]
-    pub proposing_joiner_ata: InterfaceAccount<'info, TokenAccount>,
+    // Join range: each joiner picks their contribution between the two bounds
+    // (equal bounds are a fixed price, the default: `join_amount` from
+    // initialize). Escrowed requests keep their amount; add_member takes
+    // only those still in range. The minimum is stored as `join_amount` and
+    // the maximum in the trailer.
+    pub fn set_join_range(
+        ctx: Context<SetJoinRange>,
+        min_join_amount: u64,
+        max_join_amount: u64,
+    ) -> Result<()> {
+        require!(
+            min_join_amount >= SquadMintFund::min_amount(ctx.accounts.mint.decimals),
+            ErrorCode::InsufficientJoiningAmount
+        );
+        require!(
+            max_join_amount >= min_join_amount,
+            ErrorCode::InvalidJoinRange
+        );
+        let multisig = &mut ctx.accounts.multisig;
+        // Contributions missing from old funds are reconstructed from
+        // `join_amount`, so pin them down before it changes.
+        multisig.backfill_contributions();
+        multisig.join_amount = min_join_amount;
+        multisig.set_max_join_amount(max_join_amount);
+
+        msg!(
+            "Join range updated | fund {} | {}..={}",
+            multisig.key(),
+            min_join_amount,
+            max_join_amount
+        );
+        Ok(())
+    }
+
+    // Open admission: joiners pay and become members in one step through
+    // join_open_fund, and initiate_join_request is refused. Requests already
+    // in escrow are still settled by add_member / reject_member. Combines with
+    // the invite policy.
+    pub fn set_open_admission(ctx: Context<SetAdmissionPolicy>, open: bool) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        multisig
+            .trailer
+            .set(SquadMintFund::FLAG_OPEN_ADMISSION, open);
+
+        msg!(
+            "Open admission updated | fund {} | open: {}",
+            multisig.key(),
+            open
+        );
+        Ok(())
+    }
+
+    // ---- Dues -------------------------------------------------------------
+    // Recurring contributions on top of the join deposit (see dues.rs). The
+    // owner sets the schedule; each member approves the fund's dues PDA as a
+    // delegate on their canonical token account for as much as they are
+    // willing to have pulled, and anyone can crank collect_dues. Allowances
+    // outlive the fund: the same owner reusing the handle recreates it, and
+    // its dues PDA, at the same address, so members revoke them on leaving.
+
+    // Starts (or restarts) the schedule now. A restart forgives arrears: every
+    // member owes from the new first period on.
+    pub fn set_dues(ctx: Context<SetDues>, amount: u64, period_seconds: i64) -> Result<()> {
+        require!(
+            dues::is_valid(
+                amount,
+                period_seconds,
+                SquadMintFund::min_amount(ctx.accounts.mint.decimals)
+            ),
+            ErrorCode::InvalidDuesSchedule
+        );
+        let multisig = &ctx.accounts.multisig;
+        require!(multisig.dissolution.is_none(), ErrorCode::FundDissolving);
+
+        let schedule = &mut ctx.accounts.dues;
+        if schedule.rent_payer == Pubkey::default() {
+            schedule.rent_payer = ctx.accounts.fee_payer.key();
+        }
+        schedule.fund = multisig.key();
+        schedule.amount = amount;
+        schedule.period_seconds = period_seconds;
+        schedule.start = Clock::get()?.unix_timestamp;
+        schedule.arrears = 0;
+        schedule.periods_paid = vec![0; multisig.members.len()];
+        schedule.trailer = AccountTrailer::CURRENT;
+
+        msg!(
+            "Dues set | fund {} | {} every {}s from {}",
+            multisig.key(),
+            amount,
+            period_seconds,
+            schedule.start
+        );
+        Ok(())
+    }
+
+    // Permissionless. remaining_accounts: every member's canonical token
+    // account, in `members` order. A member whose account is gone or whose
+    // allowance doesn't cover a period is skipped and marked in arrears;
+    // partial periods are never taken. What reaches the vault counts toward
+    // the member's contribution (pro-rata dissolution), and toward the pots of
+    // a running rotation.
+    pub fn collect_dues<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CollectDues<'info>>,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let schedule = &mut ctx.accounts.dues;
+        require!(multisig.dissolution.is_none(), ErrorCode::FundDissolving);
+        require!(
+            ctx.remaining_accounts.len() == multisig.members.len(),
+            ErrorCode::InvalidMemberTokenAccount
+        );
+
+        let due = dues::periods_due(
+            schedule.start,
+            schedule.period_seconds,
+            Clock::get()?.unix_timestamp,
+        );
+        // Members admitted since the last collection owe from the current
+        // period on.
+        while schedule.periods_paid.len() < multisig.members.len() {
+            schedule.periods_paid.push(due.saturating_sub(1));
+        }
+        multisig.backfill_contributions();
+
+        // Taken unchecked so a crank can't leave it out: the PDA is empty, or
+        // holds a rotation, stranded or not.
+        let rosca_info = ctx.accounts.rosca.to_account_info();
+        let mut rotation = if rosca_info.data_is_empty() {
+            None
+        } else {
+            Some(Rosca::try_deserialize(
+                &mut &rosca_info.try_borrow_data()?[..],
+            )?)
+        }
+        .filter(|rotation| rotation.dues_start == schedule.start);
+
+        let multisig_key = multisig.key();
+        let dues_seeds = &[b"dues", multisig_key.as_ref(), &[ctx.bumps.dues]];
+        let signer_seeds = &[&dues_seeds[..]];
+
+        let members = multisig.members.clone();
+        let mut arrears = 0;
+        for (i, (member, member_ata)) in members.iter().zip(ctx.remaining_accounts).enumerate() {
+            require_keys_eq!(
+                member_ata.key(),
+                get_associated_token_address_with_program_id(
+                    member,
+                    &ctx.accounts.mint.key(),
+                    ctx.accounts.token_program.key,
+                ),
+                ErrorCode::InvalidMemberTokenAccount
+            );
+            let owed = due.saturating_sub(schedule.periods_paid[i]);
+            let periods = match InterfaceAccount::<TokenAccount>::try_from(member_ata) {
+                Ok(account) if account.delegate == COption::Some(schedule.key()) => {
+                    dues::collectable(
+                        owed,
+                        schedule.amount,
+                        account.amount.min(account.delegated_amount),
+                    )
+                }
+                // Closed, or no allowance for this fund.
+                _ => 0,
+            };
+
+            if periods > 0 {
+                let vault_before = ctx.accounts.multisig_ata.amount;
+                transfer_checked(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        TransferChecked {
+                            from: member_ata.clone(),
+                            to: ctx.accounts.multisig_ata.to_account_info(),
+                            authority: schedule.to_account_info(),
+                            mint: ctx.accounts.mint.to_account_info(),
+                        },
+                        signer_seeds,
+                    ),
+                    schedule.amount * periods as u64,
+                    ctx.accounts.mint.decimals,
+                )?;
+                ctx.accounts.multisig_ata.reload()?;
+                let received = ctx.accounts.multisig_ata.amount - vault_before;
+                if let Some(rotation) = rotation.as_mut().filter(|r| i < r.order.len()) {
+                    rosca::credit(
+                        &mut rotation.pots,
+                        rotation.first_period,
+                        schedule.periods_paid[i],
+                        periods,
+                        received,
+                    );
+                }
+                schedule.periods_paid[i] += periods;
+                multisig.contributions[i] += received;
+                msg!(
+                    "Collected {} period(s) ({}) from {}",
+                    periods,
+                    received,
+                    member
+                );
+            }
+            if schedule.periods_paid[i] < due {
+                arrears |= vote::member_bit(i);
+            }
+        }
+        schedule.arrears = arrears;
+        if let Some(rotation) = rotation {
+            rotation.try_serialize(&mut &mut rosca_info.try_borrow_mut_data()?[..])?;
+        }
+
+        msg!(
+            "Dues collected | fund {} | period {} | arrears mask {:#b}",
+            multisig_key,
+            due,
+            arrears
+        );
+        Ok(())
+    }
+
+    // Ends the schedule and refunds its rent. The owner's call while the fund
+    // exists; anyone's once it is gone (dissolve_fund closes it as well).
+    pub fn close_dues(ctx: Context<CloseDues>) -> Result<()> {
+        let multisig_info = ctx.accounts.multisig.to_account_info();
+        if !multisig_info.data_is_empty() && *multisig_info.owner == crate::ID {
+            let fund = SquadMintFund::try_deserialize(&mut &multisig_info.try_borrow_data()?[..])?;
+            require_keys_eq!(
+                ctx.accounts.authority.key(),
+                fund.effective_owner(),
+                ErrorCode::CannotAddMember
+            );
+        }
+
+        msg!("Closed dues | fund {}", multisig_info.key());
+        // The schedule is closed by the `close = rent_payer` constraint.
+        Ok(())
+    }
+
+    // ---- Rotating savings ---------------------------------------------------
+    // A ROSCA on top of the dues schedule (see rosca.rs): each dues period is
+    // a round, and once every member in the rotation has paid it, anyone can
+    // crank the round's pot out to that round's member. The order is set once
+    // per rotation instead of voted on per round.
+
+    // Starts a rotation over the current members, from the current dues
+    // period or, if any of them has paid ahead, the first one none of them
+    // has paid: dues collected before the rotation aren't in its pots. `draw` shuffles the order with a seed from the slot and clock:
+    // fine for settling who goes first, but the owner picks when to call, so
+    // it is not a fair lottery against an owner who grinds for a good draw.
+    pub fn start_rosca(ctx: Context<StartRosca>, draw: bool) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let schedule = &ctx.accounts.dues;
+        require!(multisig.dissolution.is_none(), ErrorCode::FundDissolving);
+        require!(multisig.members.len() >= 2, ErrorCode::RoscaTooSmall);
+
+        let rotation = &mut ctx.accounts.rosca;
+        // A rotation orphaned by a dues restart can never finish; let it go.
+        require!(
+            rotation.round as usize >= rotation.order.len()
+                || rotation.dues_start != schedule.start,
+            ErrorCode::RoscaInProgress
+        );
+
+        let clock = Clock::get()?;
+        let members = multisig.members.len();
+        let order = if draw {
+            let seed = hashv(&[
+                multisig.key().as_ref(),
+                &clock.slot.to_le_bytes(),
+                &clock.unix_timestamp.to_le_bytes(),
+            ]);
+            rosca::draw(members, &seed.to_bytes())
+        } else {
+            rosca::fixed(members)
+        };
+
+        if rotation.rent_payer == Pubkey::default() {
+            rotation.rent_payer = ctx.accounts.fee_payer.key();
+        }
+        rotation.fund = multisig.key();
+        rotation.dues_start = schedule.start;
+        let paid_ahead = schedule.periods_paid.iter().take(members).max();
+        rotation.first_period = dues::periods_due(
+            schedule.start,
+            schedule.period_seconds,
+            clock.unix_timestamp,
+        )
+        .max(paid_ahead.map_or(0, |&paid| paid + 1));
+        rotation.round = 0;
+        rotation.order = order;
+        rotation.pots = vec![0; members];
+        rotation.trailer = AccountTrailer::CURRENT;
+
+        msg!(
+            "ROSCA started | fund {} | {} rounds from period {} | order {:?}",
+            multisig.key(),
+            members,
+            rotation.first_period,
+            rotation.order
+        );
+        Ok(())
+    }
+
+    // Permissionless. Pays the round's pot, the dues collected for the round's
+    // period from every member in the rotation, to the round's member once
+    // all of them have paid through that period. The payout fee applies as
+    // for a voted payout.
+    pub fn pay_rosca_round(ctx: Context<PayRoscaRound>) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let schedule = &ctx.accounts.dues;
+        let rotation = &mut ctx.accounts.rosca;
+        require!(multisig.dissolution.is_none(), ErrorCode::FundDissolving);
+        require!(
+            rotation.dues_start == schedule.start,
+            ErrorCode::RoscaScheduleChanged
+        );
+        let rounds = rotation.order.len();
+        require!((rotation.round as usize) < rounds, ErrorCode::RoscaComplete);
+        require!(
+            rosca::round_is_funded(
+                &schedule.periods_paid,
+                rounds,
+                rotation.first_period + rotation.round
+            ),
+            ErrorCode::RoundDuesOutstanding
+        );
+
+        let recipient = multisig.members[rotation.order[rotation.round as usize] as usize];
+        require_keys_eq!(
+            ctx.accounts.recipient.key(),
+            recipient,
+            ErrorCode::NotRoscaRecipient
+        );
+        require_keys_eq!(
+            ctx.accounts.recipient_ata.key(),
+            get_associated_token_address_with_program_id(
+                &recipient,
+                &ctx.accounts.mint.key(),
+                ctx.accounts.token_program.key,
+            ),
+            ErrorCode::NotRoscaRecipient
+        );
+
+        let pot = rotation.pots[rotation.round as usize];
+        require!(
+            ctx.accounts.multisig_ata.amount >= pot,
+            ErrorCode::InsufficientFunds
+        );
+        create_idempotent(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            Create {
+                payer: ctx.accounts.fee_payer.to_account_info(),
+                associated_token: ctx.accounts.recipient_ata.to_account_info(),
+                authority: ctx.accounts.recipient.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        ))?;
+
+        let multisig_owner_key = multisig.owner.key();
+        let multisig_seeds = &[
+            multisig.account_handle.as_ref(),
+            multisig_owner_key.as_ref(),
+            &[ctx.bumps.multisig],
+        ];
+        let signer_seeds = &[&multisig_seeds[..]];
+
+        let payout_fee = fees::bps_of(pot, ctx.accounts.config.payout_fee_bps);
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.multisig_ata.to_account_info(),
+                    to: ctx.accounts.recipient_ata.to_account_info(),
+                    authority: multisig.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            pot - payout_fee,
+            ctx.accounts.mint.decimals,
+        )?;
+        pay_protocol_fee(
+            &ctx.accounts.token_program,
+            &ctx.accounts.mint,
+            &ctx.accounts.multisig_ata,
+            multisig.to_account_info(),
+            &ctx.accounts.treasury_ata,
+            signer_seeds,
+            ProtocolFeeCharged {
+                fund: multisig.key(),
+                kind: FeeKind::Payout,
+                mint: ctx.accounts.mint.key(),
+                amount: payout_fee,
+                treasury: ctx.accounts.config.treasury,
+            },
+        )?;
+        rotation.round += 1;
+
+        msg!(
+            "ROSCA round {}/{} paid | fund {} | {} to {} (protocol fee {})",
+            rotation.round,
+            rounds,
+            multisig.key(),
+            pot - payout_fee,
+            recipient,
+            payout_fee
+        );
+        Ok(())
+    }
+
+    // Ends the rotation and refunds its rent. The owner's call while the fund
+    // exists; anyone's once it has been dissolved.
+    pub fn close_rosca(ctx: Context<CloseRosca>) -> Result<()> {
+        let multisig_info = ctx.accounts.multisig.to_account_info();
+        if !multisig_info.data_is_empty() && *multisig_info.owner == crate::ID {
+            let fund = SquadMintFund::try_deserialize(&mut &multisig_info.try_borrow_data()?[..])?;
+            require_keys_eq!(
+                ctx.accounts.authority.key(),
+                fund.effective_owner(),
+                ErrorCode::CannotAddMember
+            );
+        }
+
+        msg!("Closed ROSCA | fund {}", multisig_info.key());
+        // The rotation is closed by the `close = rent_payer` constraint.
+        Ok(())
+    }
+
+    // ---- Streams ------------------------------------------------------------
+    // Salaries instead of lump sums (see vesting.rs). A stream proposal is a
+    // payout whose recipient is the stream PDA: approving it moves `amount`
+    // (less the payout fee) from the vault into the stream's escrow, its ATA,
+    // and the stream's recipient claims from there as it vests. A passed
+    // CancelStream proposal sends the unvested rest back to the vault.
+
+    // Opens a stream to `recipient` and the proposal that funds it. The
+    // stream and its escrow exist from now on, so approval is a plain payout.
+    pub fn propose_stream(ctx: Context<ProposeStream>, terms: StreamTerms) -> Result<()> {
+        let StreamTerms {
+            recipient,
+            amount,
+            start,
+            cliff,
+            end,
+        } = terms;
+        let transaction = &mut ctx.accounts.transaction;
+        let multisig = &mut ctx.accounts.multisig;
+        let proposer = ctx.accounts.proposer.key();
+
+        require!(
+            recipient != Pubkey::default()
+                && recipient != multisig.key()
+                && recipient != ctx.accounts.multisig_ata.key(),
+            ErrorCode::InvalidDestinationOwner
+        );
+        require!(
+            !multisig.has_active_vote,
+            ErrorCode::CanOnlyInitOneVoteAtATime
+        );
+        require!(multisig.dissolution.is_none(), ErrorCode::FundDissolving);
+        require!(
+            amount >= SquadMintFund::min_amount(ctx.accounts.mint.decimals),
+            ErrorCode::InvalidProposalAmount
+        );
+        require!(
+            ctx.accounts.multisig_ata.amount >= amount,
+            ErrorCode::InsufficientFunds
+        );
+        require!(
+            vesting::is_valid(start, cliff, end),
+            ErrorCode::InvalidStreamSchedule
+        );
+        let proposer_index = multisig
+            .members
+            .iter()
+            .position(|m| m == &proposer)
+            .ok_or(ErrorCode::MemberNotPartOfFund)?;
+
+        let stream = &mut ctx.accounts.stream;
+        stream.fund = multisig.key();
+        stream.recipient = recipient;
+        stream.rent_payer = ctx.accounts.fee_payer.key();
+        stream.nonce = multisig.master_nonce;
+        stream.start = start;
+        stream.cliff = cliff;
+        stream.end = end;
+        stream.claimed = 0;
+        stream.cancelled_at = None;
+        stream.trailer = AccountTrailer::CURRENT;
+
+        transaction.belongs_to_squad_mint_fund = multisig.key();
+        transaction.message_data = TransactionMessage {
+            amount,
+            proposer_account: proposer,
+            proposed_to_account: stream.key(),
+            nonce: multisig.master_nonce,
+        };
+        let proposer_bit = vote::member_bit(proposer_index);
+        transaction.voted_mask = proposer_bit;
+        transaction.votes = proposer_bit;
+        transaction.did_meet_threshold = false;
+        transaction.kind = ProposalKind::Stream;
+        transaction.rent_payer = ctx.accounts.fee_payer.key();
+        transaction.trailer = AccountTrailer::CURRENT;
+        multisig.has_active_vote = true;
+
+        msg!(
+            "Created stream TX | proposer: {} | multisig: {} | stream {} to {} | {} from {} (cliff {}) to {}",
+            proposer,
+            multisig.key(),
+            stream.key(),
+            recipient,
+            amount,
+            start,
+            cliff,
+            end
+        );
+        Ok(())
+    }
+
+    // Opens a vote to cancel `stream`. Executed by submit_and_execute, which
+    // then needs the stream account.
+    pub fn propose_stream_cancel(ctx: Context<ProposeStreamCancel>) -> Result<()> {
+        let transaction = &mut ctx.accounts.transaction;
+        let multisig = &mut ctx.accounts.multisig;
+        let stream = &ctx.accounts.stream;
+        let proposer = ctx.accounts.proposer.key();
+
+        require!(
+            !multisig.has_active_vote,
+            ErrorCode::CanOnlyInitOneVoteAtATime
+        );
+        require!(multisig.dissolution.is_none(), ErrorCode::FundDissolving);
+        require!(stream.cancelled_at.is_none(), ErrorCode::StreamCancelled);
+        let proposer_index = multisig
+            .members
+            .iter()
+            .position(|m| m == &proposer)
+            .ok_or(ErrorCode::MemberNotPartOfFund)?;
+
+        transaction.belongs_to_squad_mint_fund = multisig.key();
+        // The stream stands in for the recipient, and its escrow for the
+        // recipient's ATA, so submit_and_execute's checks bind them.
+        transaction.message_data = TransactionMessage {
+            amount: 0,
+            proposer_account: proposer,
+            proposed_to_account: stream.key(),
+            nonce: multisig.master_nonce,
+        };
+        let proposer_bit = vote::member_bit(proposer_index);
+        transaction.voted_mask = proposer_bit;
+        transaction.votes = proposer_bit;
+        transaction.did_meet_threshold = false;
+        transaction.kind = ProposalKind::CancelStream;
+        transaction.rent_payer = ctx.accounts.fee_payer.key();
+        transaction.trailer = AccountTrailer::CURRENT;
+        multisig.has_active_vote = true;
+
+        msg!(
+            "Created stream cancel TX | proposer: {} | multisig: {} | stream: {}",
+            proposer,
+            multisig.key(),
+            stream.key()
+        );
+        Ok(())
+    }
+
+    // The recipient withdraws everything vested and not yet claimed.
+    pub fn claim_stream(ctx: Context<ClaimStream>) -> Result<()> {
+        let stream = &mut ctx.accounts.stream;
+        let claimable = stream.claimable(ctx.accounts.escrow.amount, Clock::get()?.unix_timestamp);
+        require!(claimable > 0, ErrorCode::NothingToClaim);
+
+        let fund = stream.fund;
+        let nonce = stream.nonce.to_le_bytes();
+        let stream_seeds = &[
+            b"stream",
+            fund.as_ref(),
+            nonce.as_ref(),
+            &[ctx.bumps.stream],
+        ];
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.escrow.to_account_info(),
+                    to: ctx.accounts.recipient_ata.to_account_info(),
+                    authority: stream.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                },
+                &[&stream_seeds[..]],
+            ),
+            claimable,
+            ctx.accounts.mint.decimals,
+        )?;
+        stream.claimed += claimable;
+
+        msg!(
+            "Claimed {} from stream {} | {} claimed in total",
+            claimable,
+            stream.key(),
+            stream.claimed
+        );
+        Ok(())
+    }
+
+    // Permissionless. Closes a stream with nothing left to pay, once its
+    // funding vote is over: fully claimed, or never funded. Refunds the
+    // rent of the stream and its escrow.
+    pub fn close_stream(ctx: Context<CloseStream>) -> Result<()> {
+        let stream = &ctx.accounts.stream;
+        require!(ctx.accounts.escrow.amount == 0, ErrorCode::StreamNotSettled);
+        // A closed fund has no vote left to fund the stream.
+        let multisig_info = ctx.accounts.multisig.to_account_info();
+        if !multisig_info.data_is_empty() && *multisig_info.owner == crate::ID {
+            let fund = SquadMintFund::try_deserialize(&mut &multisig_info.try_borrow_data()?[..])?;
+            require!(
+                fund.master_nonce > stream.nonce,
+                ErrorCode::StreamNotSettled
+            );
+        }
+
+        let fund = stream.fund;
+        let nonce = stream.nonce.to_le_bytes();
+        let stream_seeds = &[
+            b"stream",
+            fund.as_ref(),
+            nonce.as_ref(),
+            &[ctx.bumps.stream],
+        ];
+        harvest_withheld_fees(
+            &ctx.accounts.token_program,
+            &ctx.accounts.mint,
+            &ctx.accounts.escrow,
+        )?;
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.escrow.to_account_info(),
+                destination: ctx.accounts.rent_payer.to_account_info(),
+                authority: stream.to_account_info(),
+            },
+            &[&stream_seeds[..]],
+        ))?;
+
+        msg!("Closed stream {} | fund {}", stream.key(), fund);
+        // The stream is closed by the `close = rent_payer` constraint.
+        Ok(())
+    }
+
+    // ---- Social recovery --------------------------------------------------
+    // `owner` is baked into the fund PDA seeds, so it can never change. A lost
+    // owner key is instead recovered by the guardians: M-of-N approvals plus
+    // SQUAD_MINT_RECOVERY_DELAY_SECONDS move owner *privileges* to a new key
+    // (`recovered_owner`). The delay gives a still-live owner time to cancel.
+
+    pub fn set_guardians(
+        ctx: Context<SetGuardians>,
+        guardians: Vec<Pubkey>,
+        guardian_threshold: u8,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        // Approvals are a bitmask over guardian indexes, so the list must not
+        // shift underneath an open recovery.
+        require!(
+            !multisig.has_pending_recovery,
+            ErrorCode::RecoveryAlreadyPending
+        );
+        validate_guardians(&multisig.effective_owner(), &guardians, guardian_threshold)?;
+        multisig.guardians = guardians;
+        multisig.guardian_threshold = guardian_threshold;
+
+        msg!(
+            "Guardians updated | fund {} | guardians: {} | threshold: {}",
+            multisig.key(),
+            multisig.guardians.len(),
+            multisig.guardian_threshold
+        );
+        Ok(())
+    }
+
+    pub fn initiate_recovery(ctx: Context<InitiateRecovery>, new_owner: Pubkey) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let recovery_request = &mut ctx.accounts.recovery_request;
+
+        require!(
+            !multisig.has_pending_recovery,
+            ErrorCode::RecoveryAlreadyPending
+        );
+        require!(
+            new_owner != Pubkey::default() && new_owner != multisig.effective_owner(),
+            ErrorCode::InvalidRecoveryOwner
+        );
+        // .position() doubles as the guardian check and yields the approval bit.
+        let guardian_index = multisig
+            .guardians
+            .iter()
+            .position(|g| g == &ctx.accounts.guardian.key())
+            .ok_or(ErrorCode::NotAGuardian)?;
+
+        recovery_request.belongs_to_squad_mint_fund = multisig.key();
+        recovery_request.new_owner = new_owner;
+        recovery_request.approvals = vote::guardian_bit(guardian_index); // initiator auto-approves
+        recovery_request.initiated_at = Clock::get()?.unix_timestamp;
+        recovery_request.rent_payer = ctx.accounts.fee_payer.key();
+        recovery_request.trailer = AccountTrailer::CURRENT;
+        multisig.has_pending_recovery = true;
+
+        msg!(
+            "Recovery initiated | fund {} | guardian: {} | new owner: {}",
+            multisig.key(),
+            ctx.accounts.guardian.key(),
+            new_owner
+        );
+        Ok(())
+    }
+
+    pub fn approve_recovery(ctx: Context<ApproveRecovery>) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let recovery_request = &mut ctx.accounts.recovery_request;
+
+        let guardian_index = multisig
+            .guardians
+            .iter()
+            .position(|g| g == &ctx.accounts.guardian.key())
+            .ok_or(ErrorCode::NotAGuardian)?;
+        let bit = vote::guardian_bit(guardian_index);
+        require!(
+            recovery_request.approvals & bit == 0,
+            ErrorCode::GuardianAlreadyApproved
+        );
+        recovery_request.approvals |= bit;
+
+        msg!(
+            "Recovery approved | fund {} | guardian: {} | approvals: {}/{}",
+            multisig.key(),
+            ctx.accounts.guardian.key(),
+            recovery_request.approvals.count_ones(),
+            multisig.guardian_threshold
+        );
+        Ok(())
+    }
+
+    pub fn execute_recovery(ctx: Context<ExecuteRecovery>) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let recovery_request = &ctx.accounts.recovery_request;
+
+        require!(
+            multisig.guardian_threshold > 0
+                && recovery_request.approvals.count_ones() >= multisig.guardian_threshold as u32,
+            ErrorCode::RecoveryThresholdNotMet
+        );
+        let unlocks_at = recovery_request
+            .initiated_at
+            .checked_add(SquadMintFund::SQUAD_MINT_RECOVERY_DELAY_SECONDS)
+            .ok_or(ErrorCode::RecoveryDelayNotElapsed)?;
+        require!(
+            Clock::get()?.unix_timestamp >= unlocks_at,
+            ErrorCode::RecoveryDelayNotElapsed
+        );
+
+        let previous_owner = multisig.effective_owner();
+        let new_owner = recovery_request.new_owner;
+        // The lost key also holds a member slot it can never vote with. Swap it
+        // in place so every other member keeps their bit index.
+        if !multisig.members.contains(&new_owner) {
+            if let Some(slot) = multisig.members.iter_mut().find(|m| **m == previous_owner) {
+                *slot = new_owner;
+            }
+        }
+        multisig.recovered_owner = Some(new_owner);
+        multisig.has_pending_recovery = false;
+
+        msg!(
+            "Recovery executed | fund {} | previous owner: {} | new owner: {}",
+            multisig.key(),
+            previous_owner,
+            new_owner
+        );
+        Ok(())
+    }
+
+    pub fn cancel_recovery(ctx: Context<CancelRecovery>) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.has_pending_recovery = false;
+
+        msg!(
+            "Recovery cancelled by owner | fund {} | request {}",
+            multisig.key(),
+            ctx.accounts.recovery_request.key()
+        );
+        Ok(())
+    }
+
+    // ---- Dissolution ------------------------------------------------------
+    // Winding a fund down is a two-step flow: members vote on a Dissolve
+    // proposal through the normal submit_and_execute path, and once approved
+    // anyone can crank dissolve_fund to pay the vault out to the members and
+    // close the vault and the fund.
+
+    pub fn propose_dissolution(
+        ctx: Context<ProposeDissolution>,
+        distribution: Distribution,
+        rent_recipient: Pubkey,
+    ) -> Result<()> {
+        let transaction = &mut ctx.accounts.transaction;
+        let multisig = &mut ctx.accounts.multisig;
+        let proposer = ctx.accounts.proposer.key();
+
+        require!(
+            !multisig.has_active_vote,
+            ErrorCode::CanOnlyInitOneVoteAtATime
+        );
+        require!(multisig.dissolution.is_none(), ErrorCode::FundDissolving);
+        let proposer_index = multisig
+            .members
+            .iter()
+            .position(|m| m == &proposer)
+            .ok_or(ErrorCode::MemberNotPartOfFund)?;
+
+        transaction.belongs_to_squad_mint_fund = multisig.key();
+        // No fixed amount: the whole vault balance at dissolve time is paid out.
+        // proposed_to_account is where the reclaimed rent goes.
+        transaction.message_data = TransactionMessage {
+            amount: 0,
+            proposer_account: proposer,
+            proposed_to_account: rent_recipient,
+            nonce: multisig.master_nonce,
+        };
+        let proposer_bit = vote::member_bit(proposer_index);
+        transaction.voted_mask = proposer_bit;
+        transaction.votes = proposer_bit;
+        transaction.did_meet_threshold = false;
+        transaction.kind = ProposalKind::Dissolve { distribution };
+        transaction.rent_payer = ctx.accounts.fee_payer.key();
+        transaction.trailer = AccountTrailer::CURRENT;
+        multisig.has_active_vote = true;
+
+        msg!(
+            "Created dissolution TX | proposer: {} | multisig: {} | distribution: {:?} | rent to: {}",
+            proposer,
+            multisig.key(),
+            distribution,
+            rent_recipient
+        );
+        Ok(())
+    }
+
+    // remaining_accounts: every member's canonical ATA for the fund mint, in
+    // `multisig.members` order (writable). Closes the dues schedule, if any,
+    // with the fund.
+    pub fn dissolve_fund<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DissolveFund<'info>>,
+    ) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let terms = multisig.dissolution.ok_or(ErrorCode::FundNotDissolving)?;
+
+        // A pending recovery would strand its request account.
+        require!(
+            !multisig.has_pending_recovery,
+            ErrorCode::RecoveryAlreadyPending
+        );
+        require!(
+            ctx.remaining_accounts.len() == multisig.members.len(),
+            ErrorCode::InvalidMemberTokenAccount
+        );
+
+        let weights: Vec<u64> = match terms.distribution {
+            Distribution::Equal => vec![1; multisig.members.len()],
+            Distribution::ProRata => (0..multisig.members.len())
+                .map(|i| multisig.contribution_of(i))
+                .collect(),
+        };
+        let balance = ctx.accounts.multisig_ata.amount;
+        let shares = dissolution_shares(balance, &weights);
+
+        let multisig_owner_key = multisig.owner.key();
+        let multisig_seeds = &[
+            multisig.account_handle.as_ref(),
+            multisig_owner_key.as_ref(),
+            &[ctx.bumps.multisig],
+        ];
+        let signer_seeds = &[&multisig_seeds[..]];
+
+        for ((member, member_ata), share) in multisig
+            .members
+            .iter()
+            .zip(ctx.remaining_accounts.iter())
+            .zip(shares)
+        {
+            require_keys_eq!(
+                member_ata.key(),
+                get_associated_token_address_with_program_id(
+                    member,
+                    &ctx.accounts.mint.key(),
+                    ctx.accounts.token_program.key,
+                ),
+                ErrorCode::InvalidMemberTokenAccount
+            );
+            if share == 0 {
+                continue;
+            }
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.multisig_ata.to_account_info(),
+                to: member_ata.clone(),
+                authority: multisig.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+            };
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    signer_seeds,
+                ),
+                share,
+                ctx.accounts.mint.decimals,
+            )?;
+            msg!("Dissolution paid {} to member {}", share, member);
+        }
+
+        harvest_withheld_fees(
+            &ctx.accounts.token_program,
+            &ctx.accounts.mint,
+            &ctx.accounts.multisig_ata,
+        )?;
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.multisig_ata.to_account_info(),
+                destination: ctx.accounts.rent_recipient.to_account_info(),
+                authority: multisig.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        // The schedule goes with the fund rather than carrying its arrears
+        // over to one recreated at the same address. The allowances members
+        // gave its PDA stay on their token accounts until they revoke them.
+        let dues = ctx.accounts.dues.to_account_info();
+        if !dues.data_is_empty() {
+            let schedule = DuesSchedule::try_deserialize(&mut &dues.try_borrow_data()?[..])?;
+            require_keys_eq!(
+                ctx.accounts.dues_rent_payer.key(),
+                schedule.rent_payer,
+                ErrorCode::RentPayerMismatch
+            );
+            close_unchecked(&dues, &ctx.accounts.dues_rent_payer)?;
+            msg!("Closed dues | fund {}", multisig.key());
+        }
+
+        msg!(
+            "Dissolved fund {} | distributed {} | rent to {}",
+            multisig.key(),
+            balance,
+            terms.rent_recipient
+        );
+        // The fund account itself is closed by the `close = rent_recipient` constraint.
+        Ok(())
+    }
+
+    // ---- Account migration ------------------------------------------------
+    // Brings accounts written by an older program up to the current layout
+    // (see version.rs). Each takes its account unchecked: one allocated for an
+    // older layout can end before the fields appended since and fail to
+    // decode as it is. load_for_migration grows it to the current MAX_SIZE,
+    // `payer` covering the extra rent, so those fields read as zeros; the
+    // handler fills in what the old layout didn't record and stamps the
+    // version. Permissionless and idempotent: none of them moves tokens or
+    // changes membership or votes.
+    pub fn migrate_fund(ctx: Context<MigrateFund>) -> Result<()> {
+        let accounts = &ctx.accounts;
+        let allocated = accounts.multisig.data_len();
+        let mut fund: SquadMintFund = load_for_migration(
+            &accounts.multisig,
+            8 + SquadMintFund::MAX_SIZE,
+            &accounts.payer,
+            &accounts.system_program,
+        )?;
+        let from = fund.migrate(allocated);
+        store_migrated(&accounts.multisig, &fund)?;
+
+        msg!(
+            "Migrated fund {} from v{} to v{}",
+            accounts.multisig.key(),
+            from.get(),
+            fund.trailer.version.get()
+        );
+        Ok(())
+    }
+
+    // Proposals and join requests opened by the first release also lack the
+    // rent payer, which the migration fills in.
+    pub fn migrate_proposal(ctx: Context<MigrateProposal>) -> Result<()> {
+        let accounts = &ctx.accounts;
+        let mut transaction: Transaction = load_for_migration(
+            &accounts.transaction,
+            8 + Transaction::MAX_SIZE,
+            &accounts.payer,
+            &accounts.system_program,
+        )?;
+        let from = transaction.trailer.version;
+        transaction.backfill_rent_payer();
+        transaction.trailer.version = AccountVersion::CURRENT;
+        store_migrated(&accounts.transaction, &transaction)?;
+
+        msg!(
+            "Migrated proposal {} from v{} to v{} | rent payer {}",
+            accounts.transaction.key(),
+            from.get(),
+            transaction.trailer.version.get(),
+            transaction.rent_payer
+        );
+        Ok(())
+    }
+
+    pub fn migrate_join_request(ctx: Context<MigrateJoinRequest>) -> Result<()> {
+        let accounts = &ctx.accounts;
+        let mut request: JoinRequestCustodialWallet = load_for_migration(
+            &accounts.join_custodial_account,
+            8 + JoinRequestCustodialWallet::MAX_SIZE,
+            &accounts.payer,
+            &accounts.system_program,
+        )?;
+        let from = request.trailer.version;
+        request.backfill_rent_payer();
+        request.trailer.version = AccountVersion::CURRENT;
+        store_migrated(&accounts.join_custodial_account, &request)?;
+
+        msg!(
+            "Migrated join request {} from v{} to v{} | rent payer {}",
+            accounts.join_custodial_account.key(),
+            from.get(),
+            request.trailer.version.get(),
+            request.rent_payer
+        );
+        Ok(())
+    }
+
+    // ---- Membership index ---------------------------------------------------
+    // One Membership PDA per (fund, member) lets a client list a wallet's funds
+    // with a single getProgramAccounts memcmp on `member` instead of scanning
+    // every fund. initialize and add_member create it; these two cranks keep it
+    // in step where no instruction can: register_membership for members who
+    // have none (admitted before the index, or the new key after a recovery),
+    // close_membership once the wallet is no longer a member.
+
+    pub fn register_membership(ctx: Context<RegisterMembership>) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let member = ctx.accounts.member.key();
+        require!(
+            multisig.members.contains(&member),
+            ErrorCode::MemberNotPartOfFund
+        );
+        ctx.accounts
+            .membership
+            .record(multisig.key(), member, ctx.accounts.fee_payer.key());
+
+        msg!(
+            "Registered membership | fund {} | member {}",
+            multisig.key(),
+            member
+        );
+        Ok(())
+    }
+
+    // Permissionless: the rent goes back to whoever paid it.
+    pub fn close_membership(ctx: Context<CloseMembership>) -> Result<()> {
+        let multisig_info = ctx.accounts.multisig.to_account_info();
+        let member = ctx.accounts.member.key();
+        if !multisig_info.data_is_empty() && *multisig_info.owner == crate::ID {
+            let fund = SquadMintFund::try_deserialize(&mut &multisig_info.try_borrow_data()?[..])?;
+            require!(
+                !fund.members.contains(&member),
+                ErrorCode::MembershipStillActive
+            );
+        }
+
+        msg!(
+            "Closed membership | fund {} | member {}",
+            multisig_info.key(),
+            member
+        );
+        // The membership account is closed by the `close = rent_payer` constraint.
+        Ok(())
+    }
+
+    // ---- Handle registry ----------------------------------------------------
+    // Fund addresses are seeded by [handle, owner], so a handle alone names
+    // nothing. A fund may claim its handle in the global registry
+    // ([b"handle", handle]), making it a unique name that resolves to the fund.
+    // Claiming is optional and first come, first served; the claim lasts until
+    // the fund is closed, after which anyone can release it.
+
+    pub fn claim_handle(ctx: Context<ClaimHandle>) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let registry = &mut ctx.accounts.handle_registry;
+        registry.fund = multisig.key();
+        registry.handle = multisig.account_handle;
+        registry.rent_payer = ctx.accounts.fee_payer.key();
+        registry.trailer = AccountTrailer::CURRENT;
+
+        msg!(
+            "Handle claimed | fund {} | registry {}",
+            multisig.key(),
+            registry.key()
+        );
+        Ok(())
+    }
+
+    // Permissionless once the claiming fund is gone; the rent goes back to
+    // whoever paid it.
+    pub fn release_handle(ctx: Context<ReleaseHandle>) -> Result<()> {
+        let multisig_info = ctx.accounts.multisig.to_account_info();
+        require!(
+            multisig_info.data_is_empty() || *multisig_info.owner != crate::ID,
+            ErrorCode::HandleStillClaimed
+        );
+
+        msg!(
+            "Handle released | fund {} | registry {}",
+            multisig_info.key(),
+            ctx.accounts.handle_registry.key()
+        );
+        // The registry account is closed by the `close = rent_payer` constraint.
+        Ok(())
+    }
+
+    // ---- Program config ---------------------------------------------------
+    // The mints new funds and join requests may use live in one Config PDA
+    // rather than in the binary, so the same build serves every cluster and a
+    // mint is added without an upgrade. The upgrade authority creates it once
+    // and names the admin (intended: the Squads vault that holds the upgrade
+    // authority); after that only the admin can change it.
+
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        admin: Pubkey,
+        allowed_mints: Vec<Pubkey>,
+    ) -> Result<()> {
+        require_keys_neq!(admin, Pubkey::default(), ErrorCode::InvalidConfigAdmin);
+        validate_allowed_mints(&allowed_mints)?;
+        let config = &mut ctx.accounts.config;
+        config.admin = admin;
+        config.allowed_mints = allowed_mints;
+        // Fees start at zero; set_protocol_fee turns them on.
+        config.treasury = admin;
+        config.trailer = AccountTrailer::CURRENT;
+
+        msg!(
+            "Config initialized | admin {} | allowed mints: {:?}",
+            config.admin,
+            config.allowed_mints
+        );
+        Ok(())
+    }
+
+    // Replaces the whole list. Removing a mint stops new funds and join
+    // requests on it; funds already holding it keep working.
+    pub fn set_allowed_mints(ctx: Context<UpdateConfig>, allowed_mints: Vec<Pubkey>) -> Result<()> {
+        validate_allowed_mints(&allowed_mints)?;
+        let config = &mut ctx.accounts.config;
+        config.allowed_mints = allowed_mints;
+
+        msg!("Allowed mints updated: {:?}", config.allowed_mints);
+        Ok(())
+    }
+
+    // Rates are bounded by fees::MAX_FEE_BPS / MAX_REJECT_FEE. They apply from
+    // the next fee charged, including on join requests already escrowed. The
+    // reject fee is in millionths of a token, scaled to each fund's mint.
+    pub fn set_protocol_fee(
+        ctx: Context<UpdateConfig>,
+        treasury: Pubkey,
+        join_fee_bps: u16,
+        payout_fee_bps: u16,
+        reject_fee: u64,
+    ) -> Result<()> {
+        require_keys_neq!(treasury, Pubkey::default(), ErrorCode::InvalidTreasury);
+        require!(
+            fees::is_valid(join_fee_bps, payout_fee_bps, reject_fee),
+            ErrorCode::InvalidProtocolFee
+        );
+        let config = &mut ctx.accounts.config;
+        config.treasury = treasury;
+        config.join_fee_bps = join_fee_bps;
+        config.payout_fee_bps = payout_fee_bps;
+        config.reject_fee = reject_fee;
+
+        msg!(
+            "Protocol fee updated | treasury {} | join {} bps | payout {} bps | reject {}",
+            treasury,
+            join_fee_bps,
+            payout_fee_bps,
+            reject_fee
+        );
+        Ok(())
+    }
+
+    pub fn set_config_admin(ctx: Context<UpdateConfig>, new_admin: Pubkey) -> Result<()> {
+        require_keys_neq!(new_admin, Pubkey::default(), ErrorCode::InvalidConfigAdmin);
+        let config = &mut ctx.accounts.config;
+        msg!("Config admin {} -> {}", config.admin, new_admin);
+        config.admin = new_admin;
+        Ok(())
+    }
+}
+
+fn validate_allowed_mints(allowed_mints: &[Pubkey]) -> Result<()> {
+    require!(
+        allowed_mints.len() <= Config::MAX_ALLOWED_MINTS,
+        ErrorCode::InvalidAllowedMints
+    );
+    for (i, mint) in allowed_mints.iter().enumerate() {
+        require!(
+            *mint != Pubkey::default() && !allowed_mints[..i].contains(mint),
+            ErrorCode::InvalidAllowedMints
+        );
+    }
+    Ok(())
+}
+
+// Splits `balance` in proportion to `weights` (floor division). Rounding dust
+// goes to index 0, the owner's slot. All-zero weights (e.g. pro-rata in a fund
+// nobody has joined by deposit) fall back to an equal split.
+fn dissolution_shares(balance: u64, weights: &[u64]) -> Vec<u64> {
+    let total: u128 = weights.iter().map(|&w| w as u128).sum();
+    let (weights, total) = if total == 0 {
+        (vec![1u64; weights.len()], weights.len() as u128)
+    } else {
+        (weights.to_vec(), total)
+    };
+    let mut shares: Vec<u64> = weights
+        .iter()
+        .map(|&w| (balance as u128 * w as u128 / total) as u64)
+        .collect();
+    let distributed: u64 = shares.iter().sum();
+    if let Some(first) = shares.first_mut() {
+        *first += balance - distributed;
+    }
+    shares
+}
+
+// Moves a protocol fee out of `from` into the treasury's ATA and emits it.
+// `authority` is a PDA signed for with `signer_seeds`, or a transaction signer
+// with none. Zero fees are a no-op.
+fn pay_protocol_fee<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    from: &InterfaceAccount<'info, TokenAccount>,
+    authority: AccountInfo<'info>,
+    treasury_ata: &InterfaceAccount<'info, TokenAccount>,
+    signer_seeds: &[&[&[u8]]],
+    charge: ProtocolFeeCharged,
+) -> Result<()> {
+    if charge.amount == 0 {
+        return Ok(());
+    }
+    transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: from.to_account_info(),
+                to: treasury_ata.to_account_info(),
+                authority,
+                mint: mint.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        charge.amount,
+        mint.decimals,
+    )?;
+    emit!(charge);
+    Ok(())
+}
+
+// What Anchor's `close` constraint does, for an account the instruction may
+// or may not find open: its rent to `destination`, and back to the system
+// program with no data.
+fn close_unchecked<'info>(
+    info: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    **destination.try_borrow_mut_lamports()? += info.lamports();
+    **info.try_borrow_mut_lamports()? = 0;
+    info.assign(&system_program::ID);
+    info.resize(0)?;
+    Ok(())
+}
+
+// Token-2022 won't close an account still holding withheld transfer fees, so
+// sweep them to the mint first (permissionless). Nothing to do for mints
+// without a transfer fee.
+fn harvest_withheld_fees<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    account: &InterfaceAccount<'info, TokenAccount>,
+) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    if !token_extensions::has_transfer_fee(&mint_info.try_borrow_data()?)? {
+        return Ok(());
+    }
+    harvest_withheld_tokens_to_mint(
+        CpiContext::new(
+            token_program.to_account_info(),
+            HarvestWithheldTokensToMint {
+                token_program_id: token_program.to_account_info(),
+                mint: mint_info,
+            },
+        ),
+        vec![account.to_account_info()],
+    )
+}
+
+// Stops `stream` vesting now and returns what hasn't vested from its escrow
+// to the vault: a passed CancelStream proposal.
+fn cancel_stream<'info>(
+    stream: &mut Account<'info, Stream>,
+    escrow: &UncheckedAccount<'info>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let balance = TokenAccount::try_deserialize(&mut &escrow.try_borrow_data()?[..])?.amount;
+    let now = Clock::get()?.unix_timestamp;
+    let unvested = balance - stream.claimable(balance, now);
+    stream.cancelled_at = Some(now);
+
+    if unvested > 0 {
+        let nonce = stream.nonce.to_le_bytes();
+        let (_, bump) = Pubkey::find_program_address(
+            &[b"stream", stream.fund.as_ref(), nonce.as_ref()],
+            &crate::ID,
+        );
+        let stream_seeds = &[b"stream", stream.fund.as_ref(), nonce.as_ref(), &[bump]];
+        transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: escrow.to_account_info(),
+                    to: vault.to_account_info(),
+                    authority: stream.to_account_info(),
+                    mint: mint.to_account_info(),
+                },
+                &[&stream_seeds[..]],
+            ),
+            unvested,
+            mint.decimals,
+        )?;
+    }
+    msg!(
+        "Stream {} cancelled; returned {} to the vault",
+        stream.key(),
+        unvested
+    );
+    Ok(())
+}
+
+// Decodes an account an older program wrote, after growing it to `space`
+// bytes with `payer` topping up the rent. An account allocated for an older
+// layout can end before the fields appended since; zero-extended, they read
+// as zeros. Owner and discriminator are checked by the caller's constraint
+// and by the decode.
+fn load_for_migration<'info, T: AccountDeserialize>(
+    account: &UncheckedAccount<'info>,
+    space: usize,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<T> {
+    let info = account.to_account_info();
+    if info.data_len() < space {
+        let shortfall = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(info.lamports());
+        if shortfall > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: payer.to_account_info(),
+                        to: info.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+        info.resize(space)?;
+    }
+    let data = info.try_borrow_data()?;
+    T::try_deserialize(&mut &data[..])
+}
+
+// Writes a migrated account back and zeroes the slack after it, so fields a
+// later version appends read as zeros rather than leftover bytes.
+fn store_migrated<T: AccountSerialize>(account: &UncheckedAccount, migrated: &T) -> Result<()> {
+    let mut bytes = Vec::new();
+    migrated.try_serialize(&mut bytes)?;
+    let mut data = account.try_borrow_mut_data()?;
+    require!(
+        bytes.len() <= data.len(),
+        anchor_lang::error::ErrorCode::AccountDidNotSerialize
+    );
+    data[..bytes.len()].copy_from_slice(&bytes);
+    data[bytes.len()..].fill(0);
+    Ok(())
+}
+
+fn check_invite(
+    multisig: &Account<SquadMintFund>,
+    joiner: &Pubkey,
+    invite: Invite,
+    instructions_sysvar: &AccountInfo,
+) -> Result<()> {
+    match invite {
+        Invite::Signed { expires_at } => {
+            require!(
+                Clock::get()?.unix_timestamp <= expires_at,
+                ErrorCode::InviteExpired
+            );
+            // The Ed25519 program instruction right before this one. The
+            // runtime already rejected the transaction if its signature is bad.
+            let current = load_current_index_checked(instructions_sysvar)?;
+            let previous = current.checked_sub(1).ok_or(ErrorCode::InvalidInvite)?;
+            let ix = load_instruction_at_checked(previous as usize, instructions_sysvar)?;
+            require_keys_eq!(ix.program_id, ed25519_program::ID, ErrorCode::InvalidInvite);
+            let (signer, message) =
+                invite::ed25519_signed(&ix.data).ok_or(ErrorCode::InvalidInvite)?;
+            require!(
+                signer == multisig.effective_owner()
+                    && message == invite::message(&multisig.key(), joiner, expires_at),
+                ErrorCode::InvalidInvite
+            );
+        }
+        Invite::Allowlisted { proof } => {
+            let root = multisig.invite_root().ok_or(ErrorCode::InvalidInvite)?;
+            require!(
+                invite::verify(&root, &invite::leaf(joiner), &proof),
+                ErrorCode::InvalidInvite
+            );
+        }
+    }
+    Ok(())
+}
+
+fn validate_guardians(owner: &Pubkey, guardians: &[Pubkey], guardian_threshold: u8) -> Result<()> {
+    require!(
+        guardians.len() <= SquadMintFund::SQUAD_MINT_MAX_GUARDIANS,
+        ErrorCode::InvalidGuardianConfig
+    );
+    if guardians.is_empty() {
+        require!(guardian_threshold == 0, ErrorCode::InvalidGuardianConfig);
+        return Ok(());
+    }
+    require!(
+        guardian_threshold >= 1 && guardian_threshold as usize <= guardians.len(),
+        ErrorCode::InvalidGuardianConfig
+    );
+    for (i, guardian) in guardians.iter().enumerate() {
+        // The owner guarding their own key defeats the purpose.
+        require!(
+            guardian != owner
+                && *guardian != Pubkey::default()
+                && !guardians[..i].contains(guardian),
+            ErrorCode::InvalidGuardianConfig
+        );
+    }
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(account_handle: [u8; SquadMintFund::SQUAD_MINT_MAX_HANDLE_SIZE])]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    #[account(signer)]
+    pub multisig_owner: Signer<'info>,
+    #[account(
+        init,
+        seeds = [account_handle.as_ref(), multisig_owner.key().as_ref()],
+        bump,
+        payer = fee_payer,
+        space = 8 + SquadMintFund::MAX_SIZE
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.allows(&mint.key()) @ ErrorCode::InvalidMint
+    )]
+    pub config: Account<'info, Config>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = fee_payer,
+        seeds = [b"token_vault", multisig.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = multisig,
+        token::token_program = token_program,
+    )]
+    pub multisig_ata: InterfaceAccount<'info, TokenAccount>,
+    // The owner is the first member. init_if_needed: a dissolved fund at this
+    // address may have left the owner's membership open.
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        space = 8 + Membership::MAX_SIZE,
+        seeds = [b"membership", multisig.key().as_ref(), multisig_owner.key().as_ref()],
+        bump,
+    )]
+    pub membership: Account<'info, Membership>,
+
+    // PROGRAMS
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(Default, Debug)]
+pub struct SquadMintFund {
+    owner: Pubkey, // This is the person that init creating this fund he will soon add contributors
+    // Fixed-width handle: exactly SQUAD_MINT_MAX_HANDLE_SIZE (15) bytes — the UTF-8
+    // handle left-aligned and right-padded with trailing NUL (0) bytes. The client
+    // pads the string before calling `initialize`. The same 15 bytes are used as a
+    // PDA seed here and on every re-derivation, so derivation is always identical.
+    account_handle: [u8; SquadMintFund::SQUAD_MINT_MAX_HANDLE_SIZE],
+    has_active_vote: bool,
+    members: Vec<Pubkey>,
+    join_amount: u64,  // the minimum once set_join_range sets a range
+    master_nonce: u64, // u32
+    // Social recovery. Appended after the original fields; a fund created
+    // before guardians existed reads none once migrate_fund has zero-extended
+    // it (see version.rs).
+    guardians: Vec<Pubkey>,
+    guardian_threshold: u8,
+    has_pending_recovery: bool,
+    recovered_owner: Option<Pubkey>, // set once a recovery executes; see effective_owner()
+    // contributions[i] = join deposit plus dues paid by members[i]; drives
+    // pro-rata dissolution.
+    contributions: Vec<u64>,
+    dissolution: Option<DissolutionTerms>, // set once a Dissolve proposal passes
+    // Last; see version.rs and migrate_fund. Flags: FLAG_*. Reserved bytes
+    // INVITE_ROOT: the allowlist root, all zeros for none; MAX_JOIN_AMOUNT:
+    // the top of the join range.
+    trailer: AccountTrailer,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DissolutionTerms {
+    pub distribution: Distribution,
+    pub rent_recipient: Pubkey,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Distribution {
+    Equal,
+    ProRata, // by recorded contribution (join deposits and dues)
+}
+
+// What a Stream proposal asks for: `amount` to `recipient`, vesting from
+// `start` to `end` with nothing claimable before `cliff` (unix timestamps).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct StreamTerms {
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub start: i64,
+    pub cliff: i64,
+    pub end: i64,
+}
+//
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    // This is a payment proposal
+    #[account(init,
+              payer = fee_payer,
+              seeds = [b"proposal_tx_data", multisig.key().as_ref(), multisig.master_nonce.to_le_bytes().as_ref()],
+              bump,
+              space = 8 + Transaction::MAX_SIZE)]
+    pub transaction: Account<'info, Transaction>,
+    #[account(mut)]
+    pub multisig: Account<'info, SquadMintFund>,
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    #[account(
+        signer,
+        constraint = multisig.members.contains(&proposer.key()) @ ErrorCode::MemberNotPartOfFund
+    )]
+    pub proposer: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"token_vault", multisig.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = multisig,
+        token::token_program = token_program
+    )]
+    pub multisig_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // Programs
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateJoinRequestProposal<'info> {
+    pub proposing_joiner: Signer<'info>,
+    #[account(mut)]
+    pub multisig: Account<'info, SquadMintFund>, // the multi sig we are requesting to join
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump,
+        constraint = config.allows(&mint.key()) @ ErrorCode::InvalidMint
+    )]
+    pub config: Account<'info, Config>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    // Only read, to pin `mint` to the fund's own: several mints may be
+    // allowed, and a deposit in another one could never reach the vault.
+    #[account(
+        seeds = [b"token_vault", multisig.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = multisig,
+        token::token_program = token_program
+    )]
+    pub multisig_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(init,
+              payer = fee_payer,
+              seeds = [b"join_custodial_account", multisig.key().as_ref(), proposing_joiner.key().as_ref()],
+              bump,
+              space = 8 + JoinRequestCustodialWallet::MAX_SIZE)]
+    pub join_custodial_account: Account<'info, JoinRequestCustodialWallet>,
+    #[account(
+        init,
+        payer = fee_payer,
+        seeds = [b"join_custodial_account_ata", join_custodial_account.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = join_custodial_account,
+        token::token_program = token_program
+    )]
+    pub join_custodial_account_ata: InterfaceAccount<'info, TokenAccount>, // we can close this account and get our money back
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = proposing_joiner,
+        associated_token::token_program = token_program
+    )]
+    pub proposing_joiner_ata: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: the instructions sysvar (address below), read for signed invites
+    #[account(address = solana_instructions_sysvar::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinOpenFund<'info> {
+    #[account(mut,
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    pub joiner: Signer<'info>,
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = joiner,
+        associated_token::token_program = token_program
+    )]
+    pub joiner_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"token_vault", multisig.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = multisig,
+        token::token_program = token_program
+    )]
+    pub multisig_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        space = 8 + Membership::MAX_SIZE,
+        seeds = [b"membership", multisig.key().as_ref(), joiner.key().as_ref()],
+        bump,
+    )]
+    pub membership: Account<'info, Membership>,
+    #[account(
+        seeds = [b"config"],
+        bump,
+        has_one = treasury @ ErrorCode::InvalidTreasury,
+        constraint = config.allows(&mint.key()) @ ErrorCode::InvalidMint
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: the config's treasury (has_one above); only owns treasury_ata
+    pub treasury: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_ata: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: the instructions sysvar (address below), read for signed invites
+    #[account(address = solana_instructions_sysvar::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddMember<'info> {
+    #[account(mut,
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    #[account(mut, signer)]
+    pub fee_payer: Signer<'info>,
+    #[account(
+        signer,
+        constraint = multisig_owner.key() == multisig.effective_owner() @ ErrorCode::CannotAddMember
+    )]
+    pub multisig_owner: Signer<'info>,
+    // Writable for harvest_withheld_fees before the escrow/vault is closed.
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: validated against `new_member` and the custodial PDA seeds in the handler
+    pub proposing_joiner: UncheckedAccount<'info>,
+    /// CHECK: receives the reclaimed rent; must be whoever paid it (has_one below)
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+    #[account(mut,
+              close = rent_payer,
+              has_one = rent_payer @ ErrorCode::RentPayerMismatch,
+              seeds = [b"join_custodial_account", multisig.key().as_ref(), proposing_joiner.key().as_ref()],
+              bump,
+    )]
+    pub join_custodial_account: Account<'info, JoinRequestCustodialWallet>,
+    #[account(
+        mut,
+        seeds = [b"join_custodial_account_ata", join_custodial_account.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = join_custodial_account,
+        token::token_program = token_program,
+    )]
+    pub join_custodial_account_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"token_vault", multisig.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = multisig,
+        token::token_program = token_program
+    )]
+    pub multisig_ata: InterfaceAccount<'info, TokenAccount>,
+    // init_if_needed: a membership left open by an earlier stint (see
+    // close_membership) is reused.
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        space = 8 + Membership::MAX_SIZE,
+        seeds = [b"membership", multisig.key().as_ref(), proposing_joiner.key().as_ref()],
+        bump,
+    )]
+    pub membership: Account<'info, Membership>,
+
+    #[account(
+        seeds = [b"config"],
+        bump,
+        has_one = treasury @ ErrorCode::InvalidTreasury
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: the config's treasury (has_one above); only owns treasury_ata
+    pub treasury: UncheckedAccount<'info>,
+    // Created on first use for each mint, so fees can't block a fund.
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // Programs
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// N-1: reject_member refunds to the joiner's ATA; `init_if_needed` guarantees
+// a rejection can never be blocked by the joiner having closed that ATA.
+#[derive(Accounts)]
+pub struct RejectMember<'info> {
+    #[account(mut,
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    #[account(mut, signer)]
+    pub fee_payer: Signer<'info>,
+    #[account(
+        signer,
+        constraint = multisig_owner.key() == multisig.effective_owner() @ ErrorCode::CannotAddMember
+    )]
+    pub multisig_owner: Signer<'info>,
+    // Writable for harvest_withheld_fees before the escrow/vault is closed.
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: validated against `new_member` and the custodial PDA seeds in the handler
+    pub proposing_joiner: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        associated_token::mint = mint,
+        associated_token::authority = proposing_joiner,
+        associated_token::token_program = token_program
+    )]
+    pub proposing_joiner_ata: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: receives the reclaimed rent; must be whoever paid it (has_one below)
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+    #[account(mut,
+              close = rent_payer,
+              has_one = rent_payer @ ErrorCode::RentPayerMismatch,
+              seeds = [b"join_custodial_account", multisig.key().as_ref(), proposing_joiner.key().as_ref()],
+              bump,
+    )]
+    pub join_custodial_account: Account<'info, JoinRequestCustodialWallet>,
+    #[account(
+        mut,
+        seeds = [b"join_custodial_account_ata", join_custodial_account.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = join_custodial_account,
+        token::token_program = token_program,
+    )]
+    pub join_custodial_account_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"token_vault", multisig.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = multisig,
+        token::token_program = token_program
+    )]
+    pub multisig_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump,
+        has_one = treasury @ ErrorCode::InvalidTreasury
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: the config's treasury (has_one above); only owns treasury_ata
+    pub treasury: UncheckedAccount<'info>,
+    // Created on first use for each mint, so fees can't block a fund.
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // Programs
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// Like RejectMember (N-1): the refund goes to the joiner's canonical ATA with
+// `init_if_needed`, so a closed joiner ATA can't strand the escrow. The fund
+// may already be closed, so it is taken unchecked and bound through the
+// custodial PDA seeds instead.
+#[derive(Accounts)]
+pub struct CancelStaleJoinRequest<'info> {
+    /// CHECK: possibly closed (dissolved fund); bound via the custodial PDA seeds and inspected in the handler
+    pub multisig: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    // Writable for harvest_withheld_fees before the escrow/vault is closed.
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: bound via the custodial PDA seeds and checked against request_to_join_user in the handler
+    pub proposing_joiner: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        associated_token::mint = mint,
+        associated_token::authority = proposing_joiner,
+        associated_token::token_program = token_program
+    )]
+    pub proposing_joiner_ata: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: receives the reclaimed rent; must be whoever paid it (has_one below)
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+    #[account(mut,
+              close = rent_payer,
+              has_one = rent_payer @ ErrorCode::RentPayerMismatch,
+              seeds = [b"join_custodial_account", multisig.key().as_ref(), proposing_joiner.key().as_ref()],
+              bump,
+    )]
+    pub join_custodial_account: Account<'info, JoinRequestCustodialWallet>,
+    #[account(
+        mut,
+        seeds = [b"join_custodial_account_ata", join_custodial_account.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = join_custodial_account,
+        token::token_program = token_program,
+    )]
+    pub join_custodial_account_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // Programs
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+#[derive(Default, Debug)]
+pub struct Transaction {
+    // Payment Proposal TX
+    pub belongs_to_squad_mint_fund: Pubkey, // Multisig account , this could be part of transaction message
+    pub voted_mask: u16,                    // bit i set = member i has cast a vote (participation)
+    pub votes: u16, // bit i set = member i voted YES (NO leaves the bit clear)
+    pub message_data: TransactionMessage, // Signable message
+    pub did_meet_threshold: bool, // Replay protection
+    pub kind: ProposalKind,
+    pub rent_payer: Pubkey, // refunded the account's rent on close (audit L-1)
+    pub trailer: AccountTrailer,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum ProposalKind {
+    #[default]
+    Payout, // pay message_data.amount to message_data.proposed_to_account
+    Dissolve {
+        distribution: Distribution,
+    }, // wind the fund down; rent to proposed_to_account
+    Stream,       // fund the stream at proposed_to_account with message_data.amount
+    CancelStream, // stop the stream at proposed_to_account; unvested back to the vault
+}
+#[account]
+#[derive(Default, Debug)]
+pub struct JoinRequestCustodialWallet {
+    pub request_to_join_squad_mint_fund: Pubkey,
+    pub request_to_join_user: Pubkey,
+    join_amount: u64,       // it will be added to the pool of the squad
+    pub rent_payer: Pubkey, // paid rent for this account and its escrow ATA; refunded on close (audit L-1)
+    pub trailer: AccountTrailer,
+}
+
+// This is what the members of this fund will sign
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct TransactionMessage {
+    pub amount: u64,
+    pub proposer_account: Pubkey,
+    pub proposed_to_account: Pubkey,
+    pub nonce: u64,
+}
+
+#[derive(Accounts)]
+pub struct SubmitAndExecute<'info> {
+    #[account(
+        mut,
+        has_one = rent_payer @ ErrorCode::RentPayerMismatch,
+        seeds = [b"proposal_tx_data", multisig.key().as_ref(), multisig.master_nonce.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub transaction: Account<'info, Transaction>,
+    /// CHECK: receives the proposal's rent when it resolves; must be whoever paid it (has_one above)
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump,
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    #[account(
+        signer,
+        constraint = multisig.members.contains(&submitter.key()) @ ErrorCode::MemberNotPartOfFund
+    )]
+    pub submitter: Signer<'info>,
+    /// CHECK: Validated via transaction.message_data.proposed_to_account
+    pub proposed_to_owner: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"token_vault", multisig.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = multisig,
+        token::token_program = token_program
+    )]
+    pub multisig_ata: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: must be the canonical ATA of proposed_to_owner (checked in the handler);
+    /// created idempotently only when a YES payout executes
+    #[account(mut)]
+    pub proposed_to_ata: UncheckedAccount<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"config"],
+        bump,
+        has_one = treasury @ ErrorCode::InvalidTreasury
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: the config's treasury (has_one above); only owns treasury_ata
+    pub treasury: UncheckedAccount<'info>,
+    // Created on first use for each mint, so fees can't block a fund.
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // Programs
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    // Only for CancelStream proposals: the stream being cancelled, the same
+    // account as proposed_to_owner (checked in the handler).
+    #[account(mut)]
+    pub stream: Option<Account<'info, Stream>>,
+}
+
+// Guardians came after the first release: a v1 fund is migrated before its
+// guardian list can grow into slack the account may not have.
+#[derive(Accounts)]
+pub struct SetGuardians<'info> {
+    #[account(mut,
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump,
+        constraint = multisig.version().is_current() @ ErrorCode::FundNeedsMigration
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    #[account(
+        signer,
+        constraint = multisig_owner.key() == multisig.effective_owner() @ ErrorCode::CannotAddMember
+    )]
+    pub multisig_owner: Signer<'info>,
+}
+
+// The trailer holds the policy, and a v1 fund writes no trailer back.
+#[derive(Accounts)]
+pub struct SetAdmissionPolicy<'info> {
+    #[account(mut,
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump,
+        constraint = multisig.version().is_current() @ ErrorCode::FundNeedsMigration
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    #[account(
+        constraint = multisig_owner.key() == multisig.effective_owner() @ ErrorCode::CannotAddMember
+    )]
+    pub multisig_owner: Signer<'info>,
+}
+
+// SetAdmissionPolicy, plus the fund's mint: the minimum is in its decimals.
+#[derive(Accounts)]
+pub struct SetJoinRange<'info> {
+    #[account(mut,
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump,
+        constraint = multisig.version().is_current() @ ErrorCode::FundNeedsMigration
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    #[account(
+        constraint = multisig_owner.key() == multisig.effective_owner() @ ErrorCode::CannotAddMember
+    )]
+    pub multisig_owner: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"token_vault", multisig.key().as_ref()],
+        bump,
+        token::mint = mint
+    )]
+    pub multisig_ata: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SetDues<'info> {
+    #[account(
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    #[account(
+        constraint = multisig_owner.key() == multisig.effective_owner() @ ErrorCode::CannotAddMember
+    )]
+    pub multisig_owner: Signer<'info>,
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        space = 8 + DuesSchedule::MAX_SIZE,
+        seeds = [b"dues", multisig.key().as_ref()],
+        bump,
+    )]
+    pub dues: Account<'info, DuesSchedule>,
+    // The fund's mint, for the minimum amount in its decimals.
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"token_vault", multisig.key().as_ref()],
+        bump,
+        token::mint = mint
+    )]
+    pub multisig_ata: InterfaceAccount<'info, TokenAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+// Permissionless crank; member token accounts in remaining_accounts.
+#[derive(Accounts)]
+pub struct CollectDues<'info> {
+    #[account(mut,
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    #[account(mut,
+        seeds = [b"dues", multisig.key().as_ref()],
+        bump,
+    )]
+    pub dues: Account<'info, DuesSchedule>,
+    /// CHECK: the fund's rotation PDA; credited if it holds a rotation on
+    /// this schedule (handler)
+    #[account(mut, seeds = [b"rosca", multisig.key().as_ref()], bump)]
+    pub rosca: UncheckedAccount<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"token_vault", multisig.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = multisig,
+        token::token_program = token_program
+    )]
+    pub multisig_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// Like CloseMembership, the fund may already be closed.
+#[derive(Accounts)]
+pub struct CloseDues<'info> {
+    /// CHECK: possibly closed (dissolved fund); bound via the dues PDA seeds and inspected in the handler
+    pub multisig: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    /// CHECK: receives the reclaimed rent; must be whoever paid it (has_one below)
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+    #[account(mut,
+              close = rent_payer,
+              has_one = rent_payer @ ErrorCode::RentPayerMismatch,
+              seeds = [b"dues", multisig.key().as_ref()],
+              bump,
+    )]
+    pub dues: Account<'info, DuesSchedule>,
+}
+
+#[derive(Accounts)]
+pub struct StartRosca<'info> {
+    #[account(
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    #[account(
+        constraint = multisig_owner.key() == multisig.effective_owner() @ ErrorCode::CannotAddMember
+    )]
+    pub multisig_owner: Signer<'info>,
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    #[account(
+        seeds = [b"dues", multisig.key().as_ref()],
+        bump,
+    )]
+    pub dues: Account<'info, DuesSchedule>,
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        space = 8 + Rosca::MAX_SIZE,
+        seeds = [b"rosca", multisig.key().as_ref()],
+        bump,
+    )]
+    pub rosca: Account<'info, Rosca>,
+    pub system_program: Program<'info, System>,
+}
+
+// Permissionless crank; the recipient is checked against the rotation.
+#[derive(Accounts)]
+pub struct PayRoscaRound<'info> {
+    #[account(
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    #[account(
+        seeds = [b"dues", multisig.key().as_ref()],
+        bump,
+    )]
+    pub dues: Account<'info, DuesSchedule>,
+    #[account(mut,
+        seeds = [b"rosca", multisig.key().as_ref()],
+        bump,
+    )]
+    pub rosca: Account<'info, Rosca>,
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    /// CHECK: must be the member whose round it is (checked in the handler)
+    pub recipient: UncheckedAccount<'info>,
+    /// CHECK: must be the canonical ATA of recipient (checked in the handler);
+    /// created idempotently before the payout
+    #[account(mut)]
+    pub recipient_ata: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"token_vault", multisig.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = multisig,
+        token::token_program = token_program
+    )]
+    pub multisig_ata: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"config"],
+        bump,
+        has_one = treasury @ ErrorCode::InvalidTreasury
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: the config's treasury (has_one above); only owns treasury_ata
+    pub treasury: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program
+    )]
+    pub treasury_ata: InterfaceAccount<'info, TokenAccount>,
 
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
+// Like CloseDues, the fund may already be closed.
 #[derive(Accounts)]
-pub struct AddMember<'info> {
+pub struct CloseRosca<'info> {
+    /// CHECK: possibly closed (dissolved fund); bound via the rosca PDA seeds and inspected in the handler
+    pub multisig: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+    /// CHECK: receives the reclaimed rent; must be whoever paid it (has_one below)
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+    #[account(mut,
+              close = rent_payer,
+              has_one = rent_payer @ ErrorCode::RentPayerMismatch,
+              seeds = [b"rosca", multisig.key().as_ref()],
+              bump,
+    )]
+    pub rosca: Account<'info, Rosca>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeStream<'info> {
+    #[account(init,
+              payer = fee_payer,
+              seeds = [b"proposal_tx_data", multisig.key().as_ref(), multisig.master_nonce.to_le_bytes().as_ref()],
+              bump,
+              space = 8 + Transaction::MAX_SIZE)]
+    pub transaction: Account<'info, Transaction>,
     #[account(mut,
         seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
         bump
     )]
     pub multisig: Account<'info, SquadMintFund>,
-    #[account(mut, signer)]
+    #[account(mut)]
     pub fee_payer: Signer<'info>,
     #[account(
-        signer,
-        constraint = multisig_owner.key() == multisig.owner @ ErrorCode::CannotAddMember
+        constraint = multisig.members.contains(&proposer.key()) @ ErrorCode::MemberNotPartOfFund
     )]
-    pub multisig_owner: Signer<'info>,
+    pub proposer: Signer<'info>,
+    #[account(init,
+              payer = fee_payer,
+              seeds = [b"stream", multisig.key().as_ref(), multisig.master_nonce.to_le_bytes().as_ref()],
+              bump,
+              space = 8 + Stream::MAX_SIZE)]
+    pub stream: Account<'info, Stream>,
+    // Created up front so that approval is a plain payout into it.
+    #[account(
+        init,
+        payer = fee_payer,
+        associated_token::mint = mint,
+        associated_token::authority = stream,
+        associated_token::token_program = token_program
+    )]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
     pub mint: InterfaceAccount<'info, Mint>,
-    /// CHECK: validated against `new_member` and the custodial PDA seeds in the handler
-    pub proposing_joiner: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"token_vault", multisig.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = multisig,
+        token::token_program = token_program
+    )]
+    pub multisig_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeStreamCancel<'info> {
+    #[account(init,
+              payer = fee_payer,
+              seeds = [b"proposal_tx_data", multisig.key().as_ref(), multisig.master_nonce.to_le_bytes().as_ref()],
+              bump,
+              space = 8 + Transaction::MAX_SIZE)]
+    pub transaction: Account<'info, Transaction>,
     #[account(mut,
-              close = fee_payer,
-              seeds = [b"join_custodial_account", multisig.key().as_ref(), proposing_joiner.key().as_ref()],
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    #[account(
+        constraint = multisig.members.contains(&proposer.key()) @ ErrorCode::MemberNotPartOfFund
+    )]
+    pub proposer: Signer<'info>,
+    #[account(
+        seeds = [b"stream", multisig.key().as_ref(), stream.nonce.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub stream: Account<'info, Stream>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimStream<'info> {
+    #[account(mut,
+        has_one = recipient @ ErrorCode::InvalidDestinationOwner,
+        seeds = [b"stream", stream.fund.as_ref(), stream.nonce.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub stream: Account<'info, Stream>,
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = stream,
+        associated_token::token_program = token_program
+    )]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = recipient,
+        associated_token::mint = mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program
+    )]
+    pub recipient_ata: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+// Like CloseDues, the fund may already be closed.
+#[derive(Accounts)]
+pub struct CloseStream<'info> {
+    /// CHECK: possibly closed (dissolved fund); bound via the stream PDA seeds and inspected in the handler
+    pub multisig: UncheckedAccount<'info>,
+    /// CHECK: receives the reclaimed rent; must be whoever paid it (has_one below)
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+    #[account(mut,
+              close = rent_payer,
+              has_one = rent_payer @ ErrorCode::RentPayerMismatch,
+              seeds = [b"stream", multisig.key().as_ref(), stream.nonce.to_le_bytes().as_ref()],
               bump,
     )]
-    pub join_custodial_account: Account<'info, JoinRequestCustodialWallet>,
+    pub stream: Account<'info, Stream>,
     #[account(
         mut,
-        seeds = [b"join_custodial_account_ata", join_custodial_account.key().as_ref()],
-        bump,
-        token::mint = mint,
-        token::authority = join_custodial_account,
-        token::token_program = token_program,
+        associated_token::mint = mint,
+        associated_token::authority = stream,
+        associated_token::token_program = token_program
     )]
-    pub join_custodial_account_ata: InterfaceAccount<'info, TokenAccount>,
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct InitiateRecovery<'info> {
+    #[account(mut,
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    pub guardian: Signer<'info>, // validated against multisig.guardians in the handler
+    #[account(init,
+              payer = fee_payer,
+              seeds = [b"recovery_request", multisig.key().as_ref()],
+              bump,
+              space = 8 + RecoveryRequest::MAX_SIZE)]
+    pub recovery_request: Account<'info, RecoveryRequest>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveRecovery<'info> {
+    #[account(
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    pub guardian: Signer<'info>, // validated against multisig.guardians in the handler
+    #[account(mut,
+              seeds = [b"recovery_request", multisig.key().as_ref()],
+              bump,
+    )]
+    pub recovery_request: Account<'info, RecoveryRequest>,
+}
+
+// Permissionless: anyone may execute once the guardian quorum and the delay
+// are both satisfied.
+#[derive(Accounts)]
+pub struct ExecuteRecovery<'info> {
+    #[account(mut,
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    /// CHECK: receives the reclaimed rent; must be whoever paid it (has_one below)
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+    #[account(mut,
+              close = rent_payer,
+              has_one = rent_payer @ ErrorCode::RentPayerMismatch,
+              seeds = [b"recovery_request", multisig.key().as_ref()],
+              bump,
+    )]
+    pub recovery_request: Account<'info, RecoveryRequest>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRecovery<'info> {
+    #[account(mut,
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    #[account(
+        signer,
+        constraint = multisig_owner.key() == multisig.effective_owner() @ ErrorCode::CannotAddMember
+    )]
+    pub multisig_owner: Signer<'info>,
+    /// CHECK: receives the reclaimed rent; must be whoever paid it (has_one below)
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+    #[account(mut,
+              close = rent_payer,
+              has_one = rent_payer @ ErrorCode::RentPayerMismatch,
+              seeds = [b"recovery_request", multisig.key().as_ref()],
+              bump,
+    )]
+    pub recovery_request: Account<'info, RecoveryRequest>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeDissolution<'info> {
+    #[account(init,
+              payer = fee_payer,
+              seeds = [b"proposal_tx_data", multisig.key().as_ref(), multisig.master_nonce.to_le_bytes().as_ref()],
+              bump,
+              space = 8 + Transaction::MAX_SIZE)]
+    pub transaction: Account<'info, Transaction>,
+    #[account(mut,
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    #[account(
+        constraint = multisig.members.contains(&proposer.key()) @ ErrorCode::MemberNotPartOfFund
+    )]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// Permissionless crank once a Dissolve proposal has passed.
+#[derive(Accounts)]
+pub struct DissolveFund<'info> {
+    #[account(mut,
+        close = rent_recipient,
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    /// CHECK: must be the rent recipient the members voted for
+    #[account(mut,
+        constraint = multisig.dissolution.map(|d| d.rent_recipient) == Some(rent_recipient.key()) @ ErrorCode::InvalidDestinationOwner
+    )]
+    pub rent_recipient: UncheckedAccount<'info>,
+    // Writable for harvest_withheld_fees before the vault is closed.
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
         seeds = [b"token_vault", multisig.key().as_ref()],
@@ -660,143 +3447,321 @@ pub struct AddMember<'info> {
         token::token_program = token_program
     )]
     pub multisig_ata: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: the fund's dues PDA; closed if it holds a schedule (handler)
+    #[account(mut, seeds = [b"dues", multisig.key().as_ref()], bump)]
+    pub dues: UncheckedAccount<'info>,
+    /// CHECK: refunded the schedule's rent; must be its rent payer if there is one
+    #[account(mut)]
+    pub dues_rent_payer: UncheckedAccount<'info>,
 
     // Programs
     pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateFund<'info> {
+    /// CHECK: may be too short to decode as it is; migrate_fund zero-extends
+    /// it and checks the discriminator
+    #[account(mut, owner = crate::ID)]
+    pub multisig: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>, // covers the rent for the extra bytes
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateProposal<'info> {
+    /// CHECK: may be too short to decode as it is; migrate_proposal
+    /// zero-extends it and checks the discriminator
+    #[account(mut, owner = crate::ID)]
+    pub transaction: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>, // covers the rent for the extra bytes
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateJoinRequest<'info> {
+    /// CHECK: may be too short to decode as it is; migrate_join_request
+    /// zero-extends it and checks the discriminator
+    #[account(mut, owner = crate::ID)]
+    pub join_custodial_account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>, // covers the rent for the extra bytes
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterMembership<'info> {
+    #[account(
+        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, SquadMintFund>,
+    /// CHECK: checked against multisig.members in the handler
+    pub member: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        space = 8 + Membership::MAX_SIZE,
+        seeds = [b"membership", multisig.key().as_ref(), member.key().as_ref()],
+        bump,
+    )]
+    pub membership: Account<'info, Membership>,
     pub system_program: Program<'info, System>,
 }
 
-// N-1: reject_member refunds to the joiner's ATA; `init_if_needed` guarantees
-// a rejection can never be blocked by the joiner having closed that ATA.
+// The fund may already be closed (dissolved), so like CancelStaleJoinRequest it
+// is taken unchecked and bound through the membership PDA seeds.
 #[derive(Accounts)]
-pub struct RejectMember<'info> {
+pub struct CloseMembership<'info> {
+    /// CHECK: possibly closed (dissolved fund); bound via the membership PDA seeds and inspected in the handler
+    pub multisig: UncheckedAccount<'info>,
+    /// CHECK: bound via the membership PDA seeds
+    pub member: UncheckedAccount<'info>,
+    /// CHECK: receives the reclaimed rent; must be whoever paid it (has_one below)
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
     #[account(mut,
+              close = rent_payer,
+              has_one = rent_payer @ ErrorCode::RentPayerMismatch,
+              seeds = [b"membership", multisig.key().as_ref(), member.key().as_ref()],
+              bump,
+    )]
+    pub membership: Account<'info, Membership>,
+}
+
+// `init`: a handle another fund holds can't be claimed until it is released.
+#[derive(Accounts)]
+pub struct ClaimHandle<'info> {
+    #[account(
         seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
         bump
     )]
     pub multisig: Account<'info, SquadMintFund>,
-    #[account(mut, signer)]
-    pub fee_payer: Signer<'info>,
     #[account(
-        signer,
-        constraint = multisig_owner.key() == multisig.owner @ ErrorCode::CannotAddMember
+        constraint = multisig_owner.key() == multisig.effective_owner() @ ErrorCode::CannotAddMember
     )]
     pub multisig_owner: Signer<'info>,
-    pub mint: InterfaceAccount<'info, Mint>,
-    /// CHECK: validated against `new_member` and the custodial PDA seeds in the handler
-    pub proposing_joiner: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
     #[account(
-        init_if_needed,
+        init,
         payer = fee_payer,
-        associated_token::mint = mint,
-        associated_token::authority = proposing_joiner,
-        associated_token::token_program = token_program
+        space = 8 + HandleRegistry::MAX_SIZE,
+        seeds = [b"handle", multisig.account_handle.as_ref()],
+        bump,
     )]
-    pub proposing_joiner_ata: InterfaceAccount<'info, TokenAccount>,
+    pub handle_registry: Account<'info, HandleRegistry>,
+    pub system_program: Program<'info, System>,
+}
+
+// The fund is normally closed by now, so like CloseMembership it is taken
+// unchecked and bound through the registry's `fund` field.
+#[derive(Accounts)]
+pub struct ReleaseHandle<'info> {
+    /// CHECK: the fund holding the claim (has_one below); inspected in the handler
+    pub multisig: UncheckedAccount<'info>,
+    /// CHECK: receives the reclaimed rent; must be whoever paid it (has_one below)
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
     #[account(mut,
-              close = fee_payer,
-              seeds = [b"join_custodial_account", multisig.key().as_ref(), proposing_joiner.key().as_ref()],
+              close = rent_payer,
+              has_one = rent_payer @ ErrorCode::RentPayerMismatch,
+              constraint = handle_registry.fund == multisig.key() @ ErrorCode::HandleFundMismatch,
+              seeds = [b"handle", handle_registry.handle.as_ref()],
               bump,
     )]
-    pub join_custodial_account: Account<'info, JoinRequestCustodialWallet>,
+    pub handle_registry: Account<'info, HandleRegistry>,
+}
+
+// The upgrade authority proves itself through the program's ProgramData
+// account, which the upgradeable loader keeps at a fixed address.
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+    pub upgrade_authority: Signer<'info>,
     #[account(
-        mut,
-        seeds = [b"join_custodial_account_ata", join_custodial_account.key().as_ref()],
+        init,
+        payer = fee_payer,
+        seeds = [b"config"],
         bump,
-        token::mint = mint,
-        token::authority = join_custodial_account,
-        token::token_program = token_program,
+        space = 8 + Config::MAX_SIZE
     )]
-    pub join_custodial_account_ata: InterfaceAccount<'info, TokenAccount>,
+    pub config: Account<'info, Config>,
     #[account(
-        mut,
-        seeds = [b"token_vault", multisig.key().as_ref()],
+        constraint = program.programdata_address()? == Some(program_data.key()) @ ErrorCode::NotUpgradeAuthority
+    )]
+    pub program: Program<'info, crate::program::SquadMintMultiSig>,
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key()) @ ErrorCode::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(mut,
+        seeds = [b"config"],
         bump,
-        token::mint = mint,
-        token::authority = multisig,
-        token::token_program = token_program
+        has_one = admin @ ErrorCode::NotConfigAdmin
     )]
-    pub multisig_ata: InterfaceAccount<'info, TokenAccount>,
+    pub config: Account<'info, Config>,
+    pub admin: Signer<'info>,
+}
 
-    // Programs
-    pub token_program: Interface<'info, TokenInterface>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
+#[account]
+#[derive(Default, Debug)]
+pub struct RecoveryRequest {
+    pub belongs_to_squad_mint_fund: Pubkey,
+    pub new_owner: Pubkey,
+    pub approvals: u8,      // bit i set = multisig.guardians[i] approved
+    pub initiated_at: i64,  // unix timestamp; the delay runs from here
+    pub rent_payer: Pubkey, // refunded the account's rent on close
+    pub trailer: AccountTrailer,
 }
 
+// Index entry: `member` belongs to `fund`. At seeds
+// [b"membership", fund, member]; `member` comes first so clients can filter on
+// it at a fixed offset (layout.rs).
 #[account]
 #[derive(Default, Debug)]
-pub struct Transaction {
-    // Payment Proposal TX
-    pub belongs_to_squad_mint_fund: Pubkey, // Multisig account , this could be part of transaction message
-    pub voted_mask: u16,                    // bit i set = member i has cast a vote (participation)
-    pub votes: u16, // bit i set = member i voted YES (NO leaves the bit clear)
-    pub message_data: TransactionMessage, // Signable message
-    pub did_meet_threshold: bool, // Replay protection
+pub struct Membership {
+    pub member: Pubkey,
+    pub fund: Pubkey,
+    pub rent_payer: Pubkey, // refunded the account's rent on close
+    pub trailer: AccountTrailer,
 }
+
+// A claimed handle, at seeds [b"handle", handle]: the one fund it names.
 #[account]
 #[derive(Default, Debug)]
-pub struct JoinRequestCustodialWallet {
-    pub request_to_join_squad_mint_fund: Pubkey,
-    pub request_to_join_user: Pubkey,
-    join_amount: u64, // it will be added to the pool of the squad
+pub struct HandleRegistry {
+    pub fund: Pubkey,
+    pub handle: [u8; SquadMintFund::SQUAD_MINT_MAX_HANDLE_SIZE], // same bytes as the fund's account_handle
+    pub rent_payer: Pubkey, // refunded the account's rent on close
+    pub trailer: AccountTrailer,
 }
 
-// This is what the members of this fund will sign
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
-pub struct TransactionMessage {
-    pub amount: u64,
-    pub proposer_account: Pubkey,
-    pub proposed_to_account: Pubkey,
+// A fund's dues schedule, at seeds [b"dues", fund] (see dues.rs). Also the
+// delegate members approve on their token accounts.
+#[account]
+#[derive(Default, Debug)]
+pub struct DuesSchedule {
+    pub fund: Pubkey,
+    pub rent_payer: Pubkey, // refunded the account's rent on close
+    pub amount: u64,        // per member per period
+    pub period_seconds: i64,
+    pub start: i64, // the first period begins here
+    // vote::member_bit mask of members still behind after the last collection.
+    pub arrears: u16,
+    // periods_paid[i] = periods members[i] has paid, aligned with the fund's
+    // members. Members admitted since the last collection have no entry yet.
+    pub periods_paid: Vec<u32>,
+    pub trailer: AccountTrailer,
+}
+
+// A fund's rotating-savings cycle, at seeds [b"rosca", fund] (see rosca.rs).
+#[account]
+#[derive(Default, Debug)]
+pub struct Rosca {
+    pub fund: Pubkey,
+    pub rent_payer: Pubkey, // refunded the account's rent on close
+    // The dues schedule's start when the rotation began; a restart strands it.
+    pub dues_start: i64,
+    pub first_period: u32, // round r is funded once everyone paid first_period + r periods
+    pub round: u32,        // next round to pay; order.len() once the rotation is done
+    // order[r] = index into the fund's members of round r's recipient.
+    pub order: Vec<u8>,
+    // pots[r] = what reached the vault for round r's period from the members
+    // in the rotation, net of transfer fees: what round r pays out.
+    pub pots: Vec<u64>,
+    pub trailer: AccountTrailer,
+}
+
+// A vesting payout from the vault, at seeds [b"stream", fund, nonce] where
+// `nonce` is the proposal that funds it (see vesting.rs). Its tokens sit in
+// its own ATA, the escrow, moved there when that proposal passes.
+#[account]
+#[derive(Default, Debug)]
+pub struct Stream {
+    pub fund: Pubkey,
+    pub recipient: Pubkey,
+    pub rent_payer: Pubkey, // refunded the rent of the stream and its escrow on close
     pub nonce: u64,
+    pub start: i64,
+    pub cliff: i64,
+    pub end: i64,
+    pub claimed: u64, // paid out of the escrow to the recipient so far
+    // Set when a CancelStream proposal passes; vesting stopped there.
+    pub cancelled_at: Option<i64>,
+    pub trailer: AccountTrailer,
 }
 
-#[derive(Accounts)]
-pub struct SubmitAndExecute<'info> {
-    #[account(
-        mut,
-        seeds = [b"proposal_tx_data", multisig.key().as_ref(), multisig.master_nonce.to_le_bytes().as_ref()],
-        bump,
-    )]
-    pub transaction: Account<'info, Transaction>,
-    #[account(
-        mut,
-        seeds = [multisig.account_handle.as_ref(), multisig.owner.key().as_ref()],
-        bump,
-    )]
-    pub multisig: Account<'info, SquadMintFund>,
-    #[account(mut)]
-    pub fee_payer: Signer<'info>,
-    #[account(
-        signer,
-        constraint = multisig.members.contains(&submitter.key()) @ ErrorCode::MemberNotPartOfFund
-    )]
-    pub submitter: Signer<'info>,
-    /// CHECK: Validated via transaction.message_data.proposed_to_account
-    pub proposed_to_owner: UncheckedAccount<'info>,
-    #[account(
-        mut,
-        seeds = [b"token_vault", multisig.key().as_ref()],
-        bump,
-        token::mint = mint,
-        token::authority = multisig,
-        token::token_program = token_program
-    )]
-    pub multisig_ata: InterfaceAccount<'info, TokenAccount>,
-    #[account(
-        init_if_needed,
-        payer = fee_payer,
-        associated_token::mint = mint,
-        associated_token::authority = proposed_to_owner,
-        associated_token::token_program = token_program
-    )]
-    pub proposed_to_ata: InterfaceAccount<'info, TokenAccount>,
-    pub mint: InterfaceAccount<'info, Mint>,
+// Program-wide settings; one per deployment, at seeds [b"config"].
+#[account]
+#[derive(Default, Debug)]
+pub struct Config {
+    admin: Pubkey, // may change the config; set by the upgrade authority
+    // Mints `initialize` and `initiate_join_request` accept.
+    allowed_mints: Vec<Pubkey>,
+    // Protocol fee (see fees.rs), paid to the treasury's ATA for the fund's mint.
+    treasury: Pubkey,
+    join_fee_bps: u16,   // of each accepted join deposit
+    payout_fee_bps: u16, // of each executed payout
+    reject_fee: u64,     // flat, in millionths of a token, kept from each rejected join deposit
+    trailer: AccountTrailer,
+}
 
-    // Programs
-    pub token_program: Interface<'info, TokenInterface>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
+impl Config {
+    pub const MAX_ALLOWED_MINTS: usize = 8;
+    pub const MAX_SIZE: usize = 32 // admin
+        + (4 + Self::MAX_ALLOWED_MINTS * 32) // allowed_mints: 4-byte len + pubkeys
+        + 32 // treasury
+        + 2 // join_fee_bps
+        + 2 // payout_fee_bps
+        + 8 // reject_fee
+        + AccountTrailer::SIZE; // trailer
+
+    pub fn admin(&self) -> Pubkey {
+        self.admin
+    }
+
+    pub fn allowed_mints(&self) -> &[Pubkey] {
+        &self.allowed_mints
+    }
+
+    pub fn allows(&self, mint: &Pubkey) -> bool {
+        self.allowed_mints.contains(mint)
+    }
+
+    pub fn treasury(&self) -> Pubkey {
+        self.treasury
+    }
+
+    pub fn join_fee_bps(&self) -> u16 {
+        self.join_fee_bps
+    }
+
+    pub fn payout_fee_bps(&self) -> u16 {
+        self.payout_fee_bps
+    }
+
+    pub fn reject_fee(&self) -> u64 {
+        self.reject_fee
+    }
+
+    pub fn version(&self) -> AccountVersion {
+        self.trailer.version
+    }
+
+    pub fn flags(&self) -> u32 {
+        self.trailer.flags
+    }
 }
 
 impl SquadMintFund {
@@ -808,9 +3773,24 @@ impl SquadMintFund {
     // supermajority (deliberately hard to withdraw); a 50% "no" can reject.
     pub const SQUAD_MINT_YES_THRESHOLD_PERCENTAGE: u64 = 51;
     pub const SQUAD_MINT_NO_THRESHOLD_PERCENTAGE: u64 = 50;
-    // Shared minimum for join deposits and proposal payouts (no zero/dust amounts).
-    // USDC has 6 decimals, so 100_000 base units = 0.1 USDC.
+    // Shared minimum for join deposits, dues and payouts (no zero/dust amounts):
+    // 0.1 token, in millionths like every flat amount (see fees.rs). Checked
+    // as min_amount(decimals) of the fund's mint.
     pub const SQUAD_MINT_MIN_AMOUNT: u64 = 100_000;
+    // Guardian approvals are a u8 bitmask, so this must stay <= 8.
+    pub const SQUAD_MINT_MAX_GUARDIANS: usize = 5;
+    // Window in which a still-live owner can cancel a guardian recovery.
+    pub const SQUAD_MINT_RECOVERY_DELAY_SECONDS: i64 = 3 * 24 * 60 * 60;
+
+    // Trailer flag bits.
+    // Join requests need an invite.
+    pub const FLAG_REQUIRE_INVITE: u32 = 1 << 0;
+    // Joiners are admitted on the spot by join_open_fund, without an escrow.
+    pub const FLAG_OPEN_ADMISSION: u32 = 1 << 1;
+    // Trailer reserved bytes.
+    const INVITE_ROOT: std::ops::Range<usize> = 0..32;
+    // u64 LE; 0 (or anything below join_amount) means a fixed price.
+    const MAX_JOIN_AMOUNT: std::ops::Range<usize> = 32..40;
 
     // Borsh on-chain byte budget. The 8-byte account discriminator is added
     // separately at the `space = 8 + MAX_SIZE` constraint.
@@ -819,7 +3799,163 @@ impl SquadMintFund {
         + 1                                                     // has_active_vote
         + (4 + Self::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE * 32)    // members: 4-byte len + pubkeys
         + 8                                                     // join_amount
-        + 8; // master_nonce
+        + 8                                                     // master_nonce
+        + (4 + Self::SQUAD_MINT_MAX_GUARDIANS * 32)             // guardians: 4-byte len + pubkeys
+        + 1                                                     // guardian_threshold
+        + 1                                                     // has_pending_recovery
+        + (1 + 32)                                              // recovered_owner: Option tag + pubkey
+        + (4 + Self::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE * 8)     // contributions: 4-byte len + u64s
+        + (1 + DissolutionTerms::SIZE)                          // dissolution: Option tag + terms
+        + AccountTrailer::SIZE; // trailer
+
+    // What the first release allocated: every field up to master_nonce.
+    pub const V1_SIZE: usize = 324;
+
+    // SQUAD_MINT_MIN_AMOUNT in the base units of a mint with `decimals`, and
+    // never less than one of them.
+    pub fn min_amount(decimals: u8) -> u64 {
+        fees::in_mint_units(Self::SQUAD_MINT_MIN_AMOUNT, decimals).max(1)
+    }
+
+    // Owner privileges (membership admission, guardian changes) follow the
+    // recovered owner once a recovery executes. `owner` itself stays the
+    // creator's key because it is part of the PDA seeds.
+    pub fn effective_owner(&self) -> Pubkey {
+        self.recovered_owner.unwrap_or(self.owner)
+    }
+
+    // Read-only views for off-chain callers (tests, clients); the fields stay
+    // private so only instruction handlers mutate them.
+    pub fn owner(&self) -> Pubkey {
+        self.owner
+    }
+
+    pub fn account_handle(&self) -> &[u8; SquadMintFund::SQUAD_MINT_MAX_HANDLE_SIZE] {
+        &self.account_handle
+    }
+
+    pub fn members(&self) -> &[Pubkey] {
+        &self.members
+    }
+
+    pub fn join_amount(&self) -> u64 {
+        self.join_amount
+    }
+
+    pub fn master_nonce(&self) -> u64 {
+        self.master_nonce
+    }
+
+    pub fn has_active_vote(&self) -> bool {
+        self.has_active_vote
+    }
+
+    pub fn guardians(&self) -> &[Pubkey] {
+        &self.guardians
+    }
+
+    pub fn guardian_threshold(&self) -> u8 {
+        self.guardian_threshold
+    }
+
+    pub fn has_pending_recovery(&self) -> bool {
+        self.has_pending_recovery
+    }
+
+    pub fn dissolution(&self) -> Option<DissolutionTerms> {
+        self.dissolution
+    }
+
+    pub fn version(&self) -> AccountVersion {
+        self.trailer.version
+    }
+
+    pub fn flags(&self) -> u32 {
+        self.trailer.flags
+    }
+
+    pub fn requires_invite(&self) -> bool {
+        self.trailer.has(Self::FLAG_REQUIRE_INVITE)
+    }
+
+    pub fn open_admission(&self) -> bool {
+        self.trailer.has(Self::FLAG_OPEN_ADMISSION)
+    }
+
+    // The join range: `join_amount` is its minimum.
+    pub fn max_join_amount(&self) -> u64 {
+        let max = u64::from_le_bytes(
+            self.trailer.reserved[Self::MAX_JOIN_AMOUNT]
+                .try_into()
+                .unwrap(),
+        );
+        max.max(self.join_amount)
+    }
+
+    pub fn accepts_join_amount(&self, amount: u64) -> bool {
+        (self.join_amount..=self.max_join_amount()).contains(&amount)
+    }
+
+    fn set_max_join_amount(&mut self, max: u64) {
+        self.trailer.reserved[Self::MAX_JOIN_AMOUNT].copy_from_slice(&max.to_le_bytes());
+    }
+
+    pub fn invite_root(&self) -> Option<[u8; 32]> {
+        let root: [u8; 32] = self.trailer.reserved[Self::INVITE_ROOT].try_into().unwrap();
+        (root != [0; 32]).then_some(root)
+    }
+
+    fn set_invite_root(&mut self, root: Option<[u8; 32]>) {
+        self.trailer.reserved[Self::INVITE_ROOT].copy_from_slice(&root.unwrap_or_default());
+    }
+
+    // A resolved proposal frees its nonce; the next one gets nonce + 1.
+    fn next_master_nonce(&self) -> Option<u64> {
+        self.master_nonce.checked_add(1)
+    }
+
+    // Funds created before contributions were recorded have an empty (or short)
+    // list. Every member admitted before then paid exactly `join_amount`, except
+    // the owner at index 0 who paid nothing, so the history is reconstructible.
+    // The in-place half of migrate_fund, given the size the fund was allocated
+    // at before load_for_migration grew it. Returns the version it was at.
+    fn migrate(&mut self, allocated: usize) -> AccountVersion {
+        // One still in its first-release allocation never had a trailer
+        // written, whatever its slack decodes as.
+        let from = if allocated <= 8 + Self::V1_SIZE {
+            AccountVersion::V1
+        } else {
+            self.trailer.version
+        };
+        self.backfill_contributions();
+        if from <= AccountVersion::V1 {
+            self.trailer.flags = 0;
+            self.trailer.reserved = [0; AccountTrailer::RESERVED];
+        }
+        self.trailer.version = AccountVersion::CURRENT;
+        from
+    }
+
+    fn backfill_contributions(&mut self) {
+        while self.contributions.len() < self.members.len() {
+            let index = self.contributions.len();
+            self.contributions
+                .push(if index == 0 { 0 } else { self.join_amount });
+        }
+    }
+
+    pub fn contribution_of(&self, member_index: usize) -> u64 {
+        match self.contributions.get(member_index) {
+            Some(amount) => *amount,
+            None if member_index == 0 => 0,
+            None => self.join_amount,
+        }
+    }
+}
+
+impl DissolutionTerms {
+    // distribution (enum tag) + rent_recipient
+    pub const SIZE: usize = 1 + 32;
 }
 
 impl TransactionMessage {
@@ -827,9 +3963,106 @@ impl TransactionMessage {
     pub const SIZE: usize = 8 + 32 + 32 + 8;
 }
 
+impl ProposalKind {
+    // enum tag + the largest variant (Dissolve's Distribution tag)
+    pub const SIZE: usize = 1 + 1;
+}
+
 impl JoinRequestCustodialWallet {
-    // request_to_join_squad_mint_fund + request_to_join_user + join_amount
-    pub const MAX_SIZE: usize = 32 + 32 + 8;
+    // request_to_join_squad_mint_fund + request_to_join_user + join_amount + rent_payer + trailer
+    pub const MAX_SIZE: usize = 32 + 32 + 8 + 32 + AccountTrailer::SIZE;
+
+    pub fn join_amount(&self) -> u64 {
+        self.join_amount
+    }
+
+    // Requests opened before the rent payer was recorded have none. The old
+    // layout doesn't say who paid, so the refund goes to the requester, whose
+    // request it was.
+    fn backfill_rent_payer(&mut self) {
+        if self.rent_payer == Pubkey::default() {
+            self.rent_payer = self.request_to_join_user;
+        }
+    }
+}
+
+impl RecoveryRequest {
+    // belongs_to_squad_mint_fund + new_owner + approvals + initiated_at + rent_payer + trailer
+    pub const MAX_SIZE: usize = 32 + 32 + 1 + 8 + 32 + AccountTrailer::SIZE;
+}
+
+impl Membership {
+    // member + fund + rent_payer + trailer
+    pub const MAX_SIZE: usize = 32 + 32 + 32 + AccountTrailer::SIZE;
+
+    // A membership reused from an earlier stint keeps its original rent payer,
+    // who is the one refunded when it closes.
+    fn record(&mut self, fund: Pubkey, member: Pubkey, payer: Pubkey) {
+        if self.rent_payer == Pubkey::default() {
+            self.rent_payer = payer;
+        }
+        self.member = member;
+        self.fund = fund;
+        self.trailer = AccountTrailer::CURRENT;
+    }
+}
+
+impl HandleRegistry {
+    // fund + handle + rent_payer + trailer
+    pub const MAX_SIZE: usize =
+        32 + SquadMintFund::SQUAD_MINT_MAX_HANDLE_SIZE + 32 + AccountTrailer::SIZE;
+}
+
+impl DuesSchedule {
+    // fund + rent_payer + amount + period_seconds + start + arrears
+    // + periods_paid (4-byte len + u32s) + trailer
+    pub const MAX_SIZE: usize = 32
+        + 32
+        + 8
+        + 8
+        + 8
+        + 2
+        + (4 + SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE * 4)
+        + AccountTrailer::SIZE;
+
+    pub fn in_arrears(&self, member_index: usize) -> bool {
+        self.arrears & vote::member_bit(member_index) != 0
+    }
+}
+
+impl Rosca {
+    // fund + rent_payer + dues_start + first_period + round
+    // + order (4-byte len + u8s) + pots (4-byte len + u64s) + trailer
+    pub const MAX_SIZE: usize = 32
+        + 32
+        + 8
+        + 4
+        + 4
+        + (4 + SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE)
+        + (4 + SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE * 8)
+        + AccountTrailer::SIZE;
+
+    // Index into the fund's members of the next round's recipient, if any.
+    pub fn next_recipient(&self) -> Option<usize> {
+        self.order.get(self.round as usize).map(|&i| i as usize)
+    }
+}
+
+impl Stream {
+    // fund + recipient + rent_payer + nonce + start + cliff + end + claimed
+    // + cancelled_at (Option<i64>) + trailer
+    pub const MAX_SIZE: usize = 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + (1 + 8) + AccountTrailer::SIZE;
+
+    // What the recipient can claim now from an escrow holding `balance`. The
+    // total vesting is everything the escrow has received, claimed or not;
+    // once cancelled, it holds only what had vested, all of it claimable.
+    pub fn claimable(&self, balance: u64, now: i64) -> u64 {
+        if self.cancelled_at.is_some() {
+            return balance;
+        }
+        let total = balance + self.claimed;
+        vesting::vested(total, self.start, self.cliff, self.end, now).saturating_sub(self.claimed)
+    }
 }
 
 impl Transaction {
@@ -840,7 +4073,18 @@ impl Transaction {
         + 2                          // voted_mask
         + 2                          // votes
         + TransactionMessage::SIZE   // message_data
-        + 1; // did_meet_threshold
+        + 1                          // did_meet_threshold
+        + ProposalKind::SIZE         // kind
+        + 32                         // rent_payer
+        + AccountTrailer::SIZE; // trailer
+
+    // As for join requests: a proposal opened before the rent payer was
+    // recorded refunds its proposer.
+    fn backfill_rent_payer(&mut self) {
+        if self.rent_payer == Pubkey::default() {
+            self.rent_payer = self.message_data.proposer_account;
+        }
+    }
 }
 
 #[error_code]
@@ -869,9 +4113,9 @@ pub enum ErrorCode {
     InsufficientFunds,
     #[msg("Insufficient joining amount")]
     InsufficientJoiningAmount,
-    #[msg("Joining amount must be equal to that specified my by the wallet")]
+    #[msg("Joining amount is outside the fund's join range")]
     JoiningAmountShouldMatchTargetWallet,
-    #[msg("Fund must be created on the USDC mint")]
+    #[msg("Mint is not allowed by the program config")]
     InvalidMint,
     #[msg("Proposal does not belong to this fund")]
     ProposalFundMismatch,
@@ -883,6 +4127,140 @@ pub enum ErrorCode {
     JoinRequestUserMismatch,
     #[msg("Join request does not belong to this fund")]
     JoinRequestFundMismatch,
-    #[msg("Join request amount does not match the fund's join amount")]
+    #[msg("Join request amount is outside the fund's join range")]
     JoinAmountMismatch,
+    #[msg("Guardians must be unique, exclude the owner, and the threshold must be 1..=guardians (0 when none)")]
+    InvalidGuardianConfig,
+    #[msg("Signer is not a guardian of this fund")]
+    NotAGuardian,
+    #[msg("A recovery is already pending for this fund")]
+    RecoveryAlreadyPending,
+    #[msg("Recovery needs a new, non-default owner key")]
+    InvalidRecoveryOwner,
+    #[msg("This guardian has already approved the recovery")]
+    GuardianAlreadyApproved,
+    #[msg("Not enough guardian approvals to execute the recovery")]
+    RecoveryThresholdNotMet,
+    #[msg("The recovery delay has not elapsed yet")]
+    RecoveryDelayNotElapsed,
+    #[msg("This fund is being dissolved")]
+    FundDissolving,
+    #[msg("No dissolution has been approved for this fund")]
+    FundNotDissolving,
+    #[msg("Member token accounts must be each member's canonical ATA, in member order")]
+    InvalidMemberTokenAccount,
+    #[msg("The fund can still accept this join request; only the owner can release it")]
+    JoinRequestNotStale,
+    #[msg("Rent must be refunded to the account's original payer")]
+    RentPayerMismatch,
+    #[msg("Mints with a transfer hook are not supported")]
+    MintHasTransferHook,
+    #[msg("Mints with a permanent delegate are not supported")]
+    MintHasPermanentDelegate,
+    #[msg("Non-transferable mints are not supported")]
+    MintIsNonTransferable,
+    #[msg("The mint has a Token-2022 extension this program does not support")]
+    UnsupportedMintExtension,
+    #[msg("Amount plus transfer fee overflows")]
+    TransferFeeOverflow,
+    #[msg("Only the program's upgrade authority can initialize the config")]
+    NotUpgradeAuthority,
+    #[msg("Signer is not the config admin")]
+    NotConfigAdmin,
+    #[msg("The config admin must be a non-default key")]
+    InvalidConfigAdmin,
+    #[msg("Allowed mints must be unique, non-default keys, at most 8")]
+    InvalidAllowedMints,
+    #[msg("Treasury must be the config's non-default treasury key")]
+    InvalidTreasury,
+    #[msg("Protocol fee exceeds the hard-coded maximum")]
+    InvalidProtocolFee,
+    #[msg("This wallet is still a member of the fund")]
+    MembershipStillActive,
+    #[msg("The handle's fund still exists")]
+    HandleStillClaimed,
+    #[msg("Handle registry does not belong to this fund")]
+    HandleFundMismatch,
+    #[msg("This fund only takes join requests with an invite")]
+    InviteRequired,
+    #[msg("The invite is not valid for this fund and joiner")]
+    InvalidInvite,
+    #[msg("The invite has expired")]
+    InviteExpired,
+    #[msg("The fund must be migrated to the current layout first")]
+    FundNeedsMigration,
+    #[msg("This fund does not admit joiners instantly")]
+    FundNotOpen,
+    #[msg("This fund admits joiners instantly; use join_open_fund")]
+    FundIsOpen,
+    #[msg("The maximum join amount must not be below the minimum")]
+    InvalidJoinRange,
+    #[msg("Dues need at least the minimum amount and a period of at least a day")]
+    InvalidDuesSchedule,
+    #[msg("A rotation needs at least two members")]
+    RoscaTooSmall,
+    #[msg("The current rotation has rounds left to pay")]
+    RoscaInProgress,
+    #[msg("Every round of this rotation has been paid")]
+    RoscaComplete,
+    #[msg("The dues schedule was restarted; start a new rotation")]
+    RoscaScheduleChanged,
+    #[msg("Not every member in the rotation has paid this round's dues")]
+    RoundDuesOutstanding,
+    #[msg("Recipient is not this round's member")]
+    NotRoscaRecipient,
+    #[msg("A stream needs start <= cliff <= end, with start before end")]
+    InvalidStreamSchedule,
+    #[msg("Nothing has vested since the last claim")]
+    NothingToClaim,
+    #[msg("Stream account does not match the proposal")]
+    StreamMismatch,
+    #[msg("This stream has already been cancelled")]
+    StreamCancelled,
+    #[msg("The stream still holds tokens or awaits its vote")]
+    StreamNotSettled,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrating_a_v1_fund_clears_whatever_its_slack_decoded_as() {
+        let members = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut data = SquadMintFund::DISCRIMINATOR.to_vec();
+        (
+            members[0],
+            [7u8; 15],
+            false,
+            members.to_vec(),
+            1_110_000u64,
+            3u64,
+        )
+            .serialize(&mut data)
+            .unwrap();
+        // The fields appended since read as empty, then stale bytes where the
+        // trailer goes that start with a version byte.
+        data.extend([0; 12]);
+        data.push(AccountVersion::CURRENT.get());
+        data.resize(8 + SquadMintFund::V1_SIZE, 0xa7);
+        let allocated = data.len();
+        data.resize(8 + SquadMintFund::MAX_SIZE, 0); // load_for_migration
+
+        let mut fund = SquadMintFund::try_deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(fund.trailer.version, AccountVersion::CURRENT);
+        assert_ne!(fund.trailer.flags, 0);
+        assert_eq!(fund.migrate(allocated), AccountVersion::V1);
+        assert_eq!(fund.trailer, AccountTrailer::CURRENT);
+        assert_eq!(fund.contributions, [0, 1_110_000]);
+
+        // Migrating again keeps what the current program wrote.
+        fund.trailer.set(SquadMintFund::FLAG_OPEN_ADMISSION, true);
+        let trailer = fund.trailer;
+        assert_eq!(
+            fund.migrate(8 + SquadMintFund::MAX_SIZE),
+            AccountVersion::CURRENT
+        );
+        assert_eq!(fund.trailer, trailer);
+    }
 }
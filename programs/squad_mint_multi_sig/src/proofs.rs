@@ -0,0 +1,186 @@
+// Kani model-checking harnesses (https://model-checking.github.io/kani/).
+// Only compiled under `cargo kani` (cfg(kani)); run from this crate's
+// directory. Each harness proves its property for *every* input in range, not
+// a sample.
+
+use super::*;
+use crate::vote::{self, Outcome, Thresholds};
+use anchor_lang::prelude::borsh;
+
+const MAX_MEMBERS: usize = SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE;
+const MAX_GUARDIANS: usize = SquadMintFund::SQUAD_MINT_MAX_GUARDIANS;
+
+fn any_distribution() -> Distribution {
+    if kani::any() {
+        Distribution::Equal
+    } else {
+        Distribution::ProRata
+    }
+}
+
+// ---- Tally ------------------------------------------------------------------
+
+// tally's u128 math can't overflow or panic for any masks, member count or
+// thresholds, not just the ones the program passes.
+#[kani::proof]
+fn tally_never_panics() {
+    let thresholds = Thresholds {
+        yes_percentage: kani::any(),
+        no_percentage: kani::any(),
+    };
+    vote::tally(kani::any(), kani::any(), kani::any(), thresholds);
+}
+
+// For every reachable ballot (votes ⊆ voted_mask ⊆ the fund's member bits):
+// approval is exactly the 51% rule, rejection exactly the 50% rule when
+// approval didn't happen, and the two thresholds are never met together.
+#[kani::proof]
+fn tally_matches_the_percentage_rules() {
+    let members: usize = kani::any();
+    kani::assume(members >= 1 && members <= MAX_MEMBERS);
+    let voted_mask: u16 = kani::any();
+    let votes: u16 = kani::any();
+    let all = ((1u32 << members) - 1) as u16;
+    kani::assume(voted_mask & !all == 0 && votes & !voted_mask == 0);
+
+    let yes = votes.count_ones() as u64 * 100;
+    let no = (voted_mask & !votes).count_ones() as u64 * 100;
+    let n = members as u64;
+    let yes_meets = yes >= SquadMintFund::SQUAD_MINT_YES_THRESHOLD_PERCENTAGE * n;
+    let no_meets = no >= SquadMintFund::SQUAD_MINT_NO_THRESHOLD_PERCENTAGE * n;
+
+    let outcome = vote::tally(voted_mask, votes, n, Thresholds::SQUAD_MINT);
+    assert!(!(yes_meets && no_meets));
+    assert_eq!(outcome == Outcome::Approved, yes_meets);
+    assert_eq!(outcome == Outcome::Rejected, no_meets);
+}
+
+// ---- Bitmask slots ------------------------------------------------------------
+
+// Member indexes come from `members.iter().position(..)`, and every admission
+// path caps members at SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE: in that range the
+// shift never overflows and every member gets their own bit.
+#[kani::proof]
+fn member_bits_are_in_range_and_distinct() {
+    let i: usize = kani::any();
+    let j: usize = kani::any();
+    kani::assume(i < MAX_MEMBERS && j < MAX_MEMBERS);
+    let (a, b) = (vote::member_bit(i), vote::member_bit(j));
+    assert_eq!(a.count_ones(), 1);
+    assert_eq!(a == b, i == j);
+}
+
+#[kani::proof]
+fn guardian_bits_are_in_range_and_distinct() {
+    let i: usize = kani::any();
+    let j: usize = kani::any();
+    kani::assume(i < MAX_GUARDIANS && j < MAX_GUARDIANS);
+    let (a, b) = (vote::guardian_bit(i), vote::guardian_bit(j));
+    assert_eq!(a.count_ones(), 1);
+    assert_eq!(a == b, i == j);
+}
+
+// ---- master_nonce -------------------------------------------------------------
+
+// Resolution either moves the nonce to exactly nonce + 1 or fails
+// (NonceOverflow) at u64::MAX. It never wraps back onto a used proposal PDA.
+#[kani::proof]
+fn master_nonce_advances_by_exactly_one_or_fails() {
+    let fund = SquadMintFund {
+        master_nonce: kani::any(),
+        ..Default::default()
+    };
+    match fund.next_master_nonce() {
+        Some(next) => {
+            assert!(next > fund.master_nonce);
+            assert_eq!(next - fund.master_nonce, 1);
+        }
+        None => assert_eq!(fund.master_nonce, u64::MAX),
+    }
+}
+
+// ---- Account sizes --------------------------------------------------------------
+// A Pubkey always serializes to 32 bytes whatever its value, so the harnesses
+// vary the lengths and Option / enum tags (the only things that change a
+// Borsh size) and keep key contents fixed.
+
+// Any fund the handlers can produce fits `space = 8 + MAX_SIZE`, and a fund
+// with every list full and every Option set uses exactly MAX_SIZE (no slack).
+#[kani::proof]
+#[kani::unwind(10)]
+fn squad_mint_fund_always_fits_its_allocation() {
+    let members: usize = kani::any();
+    let guardians: usize = kani::any();
+    let contributions: usize = kani::any();
+    kani::assume(members >= 1 && members <= MAX_MEMBERS);
+    kani::assume(guardians <= MAX_GUARDIANS);
+    kani::assume(contributions <= members); // backfilled up to members.len()
+
+    let fund = SquadMintFund {
+        owner: Pubkey::default(),
+        account_handle: kani::any(),
+        has_active_vote: kani::any(),
+        members: vec![Pubkey::default(); members],
+        join_amount: kani::any(),
+        master_nonce: kani::any(),
+        guardians: vec![Pubkey::default(); guardians],
+        guardian_threshold: kani::any(),
+        has_pending_recovery: kani::any(),
+        recovered_owner: if kani::any() {
+            Some(Pubkey::default())
+        } else {
+            None
+        },
+        contributions: vec![0; contributions],
+        dissolution: if kani::any() {
+            Some(DissolutionTerms {
+                distribution: any_distribution(),
+                rent_recipient: Pubkey::default(),
+            })
+        } else {
+            None
+        },
+    };
+    let len = borsh::to_vec(&fund).unwrap().len();
+    assert!(len <= SquadMintFund::MAX_SIZE);
+    if members == MAX_MEMBERS
+        && guardians == MAX_GUARDIANS
+        && contributions == MAX_MEMBERS
+        && fund.recovered_owner.is_some()
+        && fund.dissolution.is_some()
+    {
+        assert_eq!(len, SquadMintFund::MAX_SIZE);
+    }
+}
+
+// Every proposal kind fits `space = 8 + Transaction::MAX_SIZE`; the largest
+// (Dissolve) fills it exactly.
+#[kani::proof]
+fn transaction_always_fits_its_allocation() {
+    let kind = if kani::any() {
+        ProposalKind::Payout
+    } else {
+        ProposalKind::Dissolve {
+            distribution: any_distribution(),
+        }
+    };
+    let transaction = Transaction {
+        belongs_to_squad_mint_fund: Pubkey::default(),
+        voted_mask: kani::any(),
+        votes: kani::any(),
+        message_data: TransactionMessage {
+            amount: kani::any(),
+            proposer_account: Pubkey::default(),
+            proposed_to_account: Pubkey::default(),
+            nonce: kani::any(),
+        },
+        did_meet_threshold: kani::any(),
+        kind,
+        rent_payer: Pubkey::default(),
+    };
+    let len = borsh::to_vec(&transaction).unwrap().len();
+    assert!(len <= Transaction::MAX_SIZE);
+    if matches!(kind, ProposalKind::Dissolve { .. }) {
+        assert_eq!(len, Transaction::MAX_SIZE);
+    }
+}
@@ -0,0 +1,110 @@
+// Rotating savings (start_rosca / pay_rosca_round). Built on the fund's dues
+// schedule (dues.rs): each dues period is one round, and once every member in
+// the rotation has paid that round's dues, the round's pot (the dues of all of
+// them) goes to one member, each member once per cycle. No vote per round; the
+// order is fixed when the rotation starts.
+//
+// The rotation covers the members at its start. Members admitted later pay
+// dues into the vault like anyone else but wait for the next rotation.
+//
+// A round pays out its pot: what actually reached the vault, net of transfer
+// fees, for its period from the members in the rotation. Nothing else in the
+// vault (join deposits, other dues) is ever part of it.
+
+use anchor_lang::solana_program::hash::hashv;
+
+const DRAW_PREFIX: &[u8] = b"squadmint rosca v1";
+
+// Member indexes in stored order: the creator first, then by admission.
+pub fn fixed(members: usize) -> Vec<u8> {
+    (0..members as u8).collect()
+}
+
+// A Fisher-Yates shuffle of `fixed(members)`, one hash of the seed per swap.
+// Deterministic, so anyone can check a drawn order against its seed.
+pub fn draw(members: usize, seed: &[u8; 32]) -> Vec<u8> {
+    let mut order = fixed(members);
+    for i in (1..members).rev() {
+        let hash = hashv(&[DRAW_PREFIX, seed, &[i as u8]]).to_bytes();
+        let pick = u64::from_le_bytes(hash[..8].try_into().unwrap()) % (i as u64 + 1);
+        order.swap(i, pick as usize);
+    }
+    order
+}
+
+// Adds `received`, what reached the vault for `periods` periods of a rotation
+// member's dues, to the pots of the rounds those periods fund. `paid` is how
+// many periods the member had paid before; round r is period first_period + r
+// (counted as in dues::periods_due). Spread evenly over the periods, so the
+// pots gain exactly `received` when all of them fall in the rotation.
+pub fn credit(pots: &mut [u64], first_period: u32, paid: u32, periods: u32, received: u64) {
+    let through = |n: u32| (received as u128 * n as u128 / periods as u128) as u64;
+    for j in 0..periods {
+        let round = (paid + j + 1).checked_sub(first_period);
+        if let Some(pot) = round.and_then(|round| pots.get_mut(round as usize)) {
+            *pot += through(j + 1) - through(j);
+        }
+    }
+}
+
+// Whether the first `rotation` members have all paid dues through period
+// `through` (a count of periods, as in dues::periods_due). Members without an
+// entry yet haven't been collected from at all.
+pub fn round_is_funded(periods_paid: &[u32], rotation: usize, through: u32) -> bool {
+    periods_paid
+        .get(..rotation)
+        .is_some_and(|paid| paid.iter().all(|&periods| periods >= through))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_drawn_order_is_a_permutation_of_the_members() {
+        for members in 0..=8 {
+            for seed in 0..32u8 {
+                let mut order = draw(members, &[seed; 32]);
+                order.sort();
+                assert_eq!(order, fixed(members), "{members} members, seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn a_draw_depends_only_on_its_seed() {
+        assert_eq!(draw(8, &[1; 32]), draw(8, &[1; 32]));
+        let orders: std::collections::HashSet<_> =
+            (0..32u8).map(|seed| draw(8, &[seed; 32])).collect();
+        assert!(orders.len() > 1);
+    }
+
+    #[test]
+    fn a_round_is_funded_once_everyone_in_the_rotation_has_paid() {
+        assert!(round_is_funded(&[3, 3, 3], 3, 3));
+        assert!(!round_is_funded(&[3, 2, 3], 3, 3));
+        // Members after the rotation don't hold it up.
+        assert!(round_is_funded(&[3, 3, 0], 2, 3));
+        // Nor can a rotation member without an entry be skipped.
+        assert!(!round_is_funded(&[3, 3], 3, 1));
+    }
+
+    #[test]
+    fn credits_land_in_the_pots_of_the_rounds_paid_for() {
+        // Rounds are periods 3, 4 and 5.
+        let mut pots = [0; 3];
+        credit(&mut pots, 3, 2, 1, 190);
+        assert_eq!(pots, [190, 0, 0]);
+        // Three periods at once, one of them past the rotation; what arrived
+        // is split without losing the remainder.
+        credit(&mut pots, 3, 3, 3, 100);
+        assert_eq!(pots, [190, 33, 33]);
+        let mut pots = [0; 3];
+        credit(&mut pots, 3, 2, 3, 100);
+        assert_eq!(pots, [33, 33, 34]);
+        // Periods before the rotation fund none of its rounds.
+        let mut pots = [0; 3];
+        credit(&mut pots, 3, 0, 2, 400);
+        assert_eq!(pots, [0; 3]);
+    }
+}
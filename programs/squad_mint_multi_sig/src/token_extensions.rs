@@ -0,0 +1,282 @@
+// Token-2022 mint extensions: which ones a fund's mint may carry, and the
+// transfer-fee arithmetic for the one that changes how much arrives. Works on
+// raw mint account data so the policy is tested on the host. A legacy SPL
+// Token mint is the bare base state: no extensions, no fee.
+//
+// A mint's extensions are fixed when it is created, so checking them once at
+// `initialize` covers the fund's lifetime. Only the extensions in `SUPPORTED`
+// are accepted; anything else, including extensions Token-2022 adds later, is
+// rejected rather than assumed harmless.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{
+        transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType,
+        StateWithExtensions,
+    },
+    state::Mint,
+};
+
+use crate::ErrorCode;
+
+// Accepted, and why each is safe here:
+// - TransferFeeConfig: deposits are grossed up (`gross_amount`) so the full
+//   join amount reaches the vault, and withheld fees are harvested to the mint
+//   before an escrow or the vault is closed.
+// - ConfidentialTransferMint / ConfidentialTransferFeeConfig: the program's
+//   escrows and vault are never configured for confidential balances, so every
+//   transfer into or out of them is public.
+// - InterestBearingConfig: changes the displayed amount, not raw balances.
+// - Metadata and group pointers / data: descriptive only.
+const SUPPORTED: &[ExtensionType] = &[
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::ConfidentialTransferMint,
+    ExtensionType::ConfidentialTransferFeeConfig,
+    ExtensionType::InterestBearingConfig,
+    ExtensionType::MetadataPointer,
+    ExtensionType::TokenMetadata,
+    ExtensionType::GroupPointer,
+    ExtensionType::TokenGroup,
+    ExtensionType::GroupMemberPointer,
+    ExtensionType::TokenGroupMember,
+];
+
+// Rejected with their own errors, because they are the ones an issuer is most
+// likely to turn on:
+// - TransferHook: every transfer runs issuer code and needs extra accounts the
+//   program doesn't pass, so deposits could be locked in the vault.
+// - PermanentDelegate: the delegate can move the vault's tokens without a vote.
+// - NonTransferable: deposits could never be paid out.
+// Present at all is enough: a hook program or delegate that is unset today can
+// be set later by its authority.
+pub fn check_mint(data: &[u8]) -> Result<()> {
+    let mint = unpack(data)?;
+    let extensions = mint
+        .get_extension_types()
+        .map_err(|_| error!(ErrorCode::UnsupportedMintExtension))?;
+    for extension in extensions {
+        match extension {
+            ExtensionType::TransferHook => return err!(ErrorCode::MintHasTransferHook),
+            ExtensionType::PermanentDelegate => return err!(ErrorCode::MintHasPermanentDelegate),
+            ExtensionType::NonTransferable => return err!(ErrorCode::MintIsNonTransferable),
+            extension if SUPPORTED.contains(&extension) => {}
+            _ => return err!(ErrorCode::UnsupportedMintExtension),
+        }
+    }
+    Ok(())
+}
+
+pub fn has_transfer_fee(data: &[u8]) -> Result<bool> {
+    Ok(transfer_fee_config(data)?.is_some())
+}
+
+// Amount to send so that exactly `net` arrives after the mint's transfer fee
+// in `epoch`; `net` itself for mints without one.
+pub fn gross_amount(data: &[u8], epoch: u64, net: u64) -> Result<u64> {
+    match transfer_fee_config(data)? {
+        None => Ok(net),
+        Some(config) => config
+            .get_epoch_fee(epoch)
+            .calculate_pre_fee_amount(net)
+            .ok_or_else(|| error!(ErrorCode::TransferFeeOverflow)),
+    }
+}
+
+fn transfer_fee_config(data: &[u8]) -> Result<Option<TransferFeeConfig>> {
+    Ok(unpack(data)?
+        .get_extension::<TransferFeeConfig>()
+        .ok()
+        .copied())
+}
+
+fn unpack(data: &[u8]) -> Result<StateWithExtensions<'_, Mint>> {
+    StateWithExtensions::<Mint>::unpack(data).map_err(|_| error!(ErrorCode::InvalidMint))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::{program_option::COption, program_pack::Pack};
+    use anchor_spl::token_2022::spl_token_2022::extension::{
+        confidential_transfer::ConfidentialTransferMint,
+        default_account_state::DefaultAccountState, non_transferable::NonTransferable,
+        permanent_delegate::PermanentDelegate, transfer_fee::TransferFee,
+        transfer_hook::TransferHook, BaseStateWithExtensionsMut, StateWithExtensionsMut,
+    };
+
+    fn base() -> Mint {
+        Mint {
+            mint_authority: COption::None,
+            supply: 0,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        }
+    }
+
+    fn legacy_mint() -> Vec<u8> {
+        let mut data = vec![0; Mint::LEN];
+        Mint::pack(base(), &mut data).unwrap();
+        data
+    }
+
+    // A Token-2022 mint with `extensions` initialised (zeroed) and `configure`
+    // run on it before the base state is written.
+    fn mint_with(
+        extensions: &[ExtensionType],
+        configure: impl FnOnce(&mut StateWithExtensionsMut<Mint>),
+    ) -> Vec<u8> {
+        let len = ExtensionType::try_calculate_account_len::<Mint>(extensions).unwrap();
+        let mut data = vec![0; len];
+        let mut mint = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        configure(&mut mint);
+        mint.base = base();
+        mint.pack_base();
+        mint.init_account_type().unwrap();
+        data
+    }
+
+    fn fee_mint(basis_points: u16, maximum_fee: u64) -> Vec<u8> {
+        mint_with(&[ExtensionType::TransferFeeConfig], |mint| {
+            let config = mint.init_extension::<TransferFeeConfig>(true).unwrap();
+            let fee = TransferFee {
+                epoch: 0.into(),
+                maximum_fee: maximum_fee.into(),
+                transfer_fee_basis_points: basis_points.into(),
+            };
+            config.older_transfer_fee = fee;
+            config.newer_transfer_fee = fee;
+        })
+    }
+
+    #[test]
+    fn legacy_mints_pass_and_charge_nothing() {
+        let data = legacy_mint();
+        assert!(check_mint(&data).is_ok());
+        assert!(!has_transfer_fee(&data).unwrap());
+        assert_eq!(gross_amount(&data, 7, 1_000_000).unwrap(), 1_000_000);
+    }
+
+    #[test]
+    fn rejects_transfer_hooks_even_without_a_program() {
+        let data = mint_with(&[ExtensionType::TransferHook], |mint| {
+            mint.init_extension::<TransferHook>(true).unwrap();
+        });
+        assert_eq!(
+            check_mint(&data).unwrap_err(),
+            ErrorCode::MintHasTransferHook.into()
+        );
+    }
+
+    #[test]
+    fn rejects_permanent_delegates() {
+        let data = mint_with(&[ExtensionType::PermanentDelegate], |mint| {
+            let extension = mint.init_extension::<PermanentDelegate>(true).unwrap();
+            extension.delegate = Some(Pubkey::new_unique()).try_into().unwrap();
+        });
+        assert_eq!(
+            check_mint(&data).unwrap_err(),
+            ErrorCode::MintHasPermanentDelegate.into()
+        );
+    }
+
+    #[test]
+    fn rejects_non_transferable_mints() {
+        let data = mint_with(&[ExtensionType::NonTransferable], |mint| {
+            mint.init_extension::<NonTransferable>(true).unwrap();
+        });
+        assert_eq!(
+            check_mint(&data).unwrap_err(),
+            ErrorCode::MintIsNonTransferable.into()
+        );
+    }
+
+    #[test]
+    fn accepts_confidential_transfer_mints() {
+        let data = mint_with(&[ExtensionType::ConfidentialTransferMint], |mint| {
+            let extension = mint
+                .init_extension::<ConfidentialTransferMint>(true)
+                .unwrap();
+            extension.auto_approve_new_accounts = true.into();
+        });
+        assert!(check_mint(&data).is_ok());
+        assert!(!has_transfer_fee(&data).unwrap());
+    }
+
+    // Not on the allowlist, so rejected without a dedicated error.
+    #[test]
+    fn rejects_extensions_outside_the_allowlist() {
+        let data = mint_with(&[ExtensionType::DefaultAccountState], |mint| {
+            mint.init_extension::<DefaultAccountState>(true).unwrap();
+        });
+        assert_eq!(
+            check_mint(&data).unwrap_err(),
+            ErrorCode::UnsupportedMintExtension.into()
+        );
+        // A hook alongside an allowed extension is still a hook.
+        let data = mint_with(
+            &[
+                ExtensionType::TransferFeeConfig,
+                ExtensionType::TransferHook,
+            ],
+            |mint| {
+                mint.init_extension::<TransferFeeConfig>(true).unwrap();
+                mint.init_extension::<TransferHook>(true).unwrap();
+            },
+        );
+        assert_eq!(
+            check_mint(&data).unwrap_err(),
+            ErrorCode::MintHasTransferHook.into()
+        );
+    }
+
+    #[test]
+    fn rejects_data_that_is_not_a_mint() {
+        assert_eq!(
+            check_mint(&[0; 10]).unwrap_err(),
+            ErrorCode::InvalidMint.into()
+        );
+    }
+
+    // The escrow -> vault hop is fee-charged too, so a join deposit is grossed
+    // up twice; after both hops exactly the join amount is left.
+    #[test]
+    fn grossed_up_deposits_survive_two_fee_charging_hops() {
+        let data = fee_mint(150, u64::MAX); // 1.5%
+        assert!(check_mint(&data).is_ok());
+        assert!(has_transfer_fee(&data).unwrap());
+        let fee = unpack(&data)
+            .unwrap()
+            .get_extension::<TransferFeeConfig>()
+            .unwrap()
+            .newer_transfer_fee;
+        for join_amount in [1, 999, 1_000_000, 123_456_789] {
+            let escrowed = gross_amount(&data, 0, join_amount).unwrap();
+            let sent = gross_amount(&data, 0, escrowed).unwrap();
+            assert!(sent > escrowed && escrowed > join_amount);
+            assert_eq!(fee.calculate_post_fee_amount(sent), Some(escrowed));
+            assert_eq!(fee.calculate_post_fee_amount(escrowed), Some(join_amount));
+        }
+    }
+
+    #[test]
+    fn gross_amount_respects_the_fee_cap_and_the_epoch() {
+        let data = fee_mint(5_000, 10); // 50%, but at most 10 base units
+        assert_eq!(gross_amount(&data, 0, 1_000).unwrap(), 1_010);
+
+        let data = mint_with(&[ExtensionType::TransferFeeConfig], |mint| {
+            let config = mint.init_extension::<TransferFeeConfig>(true).unwrap();
+            config.newer_transfer_fee = TransferFee {
+                epoch: 5.into(),
+                maximum_fee: u64::MAX.into(),
+                transfer_fee_basis_points: 1_000.into(), // 10% from epoch 5
+            };
+        });
+        assert_eq!(gross_amount(&data, 4, 900).unwrap(), 900);
+        assert_eq!(gross_amount(&data, 5, 900).unwrap(), 1_000);
+        assert_eq!(
+            gross_amount(&data, 5, u64::MAX).unwrap_err(),
+            ErrorCode::TransferFeeOverflow.into()
+        );
+    }
+}
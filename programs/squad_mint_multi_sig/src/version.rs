@@ -0,0 +1,227 @@
+// Account layout versions. Every account ends with an `AccountTrailer`: a
+// version byte, a flags bitfield and reserved bytes, so a later program can
+// tell which layout it is reading, add booleans without growing the account,
+// and carve new fields out of the reserved bytes instead of reallocating.
+//
+// Accounts written before versioning (v1) have no trailer. Where a v1
+// account's allocation leaves zeroed slack after its last field, the fields
+// appended since read from it as zeros and the trailer as V1, which writes
+// zeros back where a trailer would go, so the account keeps working in place
+// while its data fits that allocation. One whose data runs to the end of its allocation (every
+// proposal and join request of the first release, and a fund with every
+// member slot taken) ends before those fields and doesn't decode at all
+// until a migrate_* instruction zero-extends it. New accounts are stamped
+// CURRENT when they are created.
+
+use anchor_lang::prelude::*;
+use std::io::{Read, Write};
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct AccountVersion(u8);
+
+impl AccountVersion {
+    pub const V1: Self = Self(1);
+    pub const V2: Self = Self(2); // adds the trailer
+    pub const CURRENT: Self = Self::V2;
+
+    pub fn get(self) -> u8 {
+        self.0
+    }
+
+    pub fn is_current(self) -> bool {
+        self == Self::CURRENT
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AccountTrailer {
+    pub version: AccountVersion,
+    // Boolean settings; each account type defines its own bits. Zero in v1.
+    pub flags: u32,
+    // Zero. A later version turns a prefix of it into new fields.
+    pub reserved: [u8; AccountTrailer::RESERVED],
+}
+
+impl AccountTrailer {
+    pub const RESERVED: usize = 64;
+    // version + flags + reserved
+    pub const SIZE: usize = 1 + 4 + Self::RESERVED;
+
+    pub const V1: Self = Self::at(AccountVersion::V1);
+    pub const CURRENT: Self = Self::at(AccountVersion::CURRENT);
+
+    const fn at(version: AccountVersion) -> Self {
+        Self {
+            version,
+            flags: 0,
+            reserved: [0; Self::RESERVED],
+        }
+    }
+
+    pub fn has(&self, flag: u32) -> bool {
+        self.flags & flag == flag
+    }
+
+    pub fn set(&mut self, flag: u32, on: bool) {
+        if on {
+            self.flags |= flag;
+        } else {
+            self.flags &= !flag;
+        }
+    }
+}
+
+impl Default for AccountTrailer {
+    // What a v1 account, or a freshly zeroed one, reads as.
+    fn default() -> Self {
+        Self::V1
+    }
+}
+
+impl AnchorSerialize for AccountTrailer {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        if self.version == AccountVersion::V1 {
+            // Zero what follows the last field, as far as the account has
+            // room: bytes a shorter write-back left behind must never read as
+            // a trailer.
+            let mut slack = &[0; Self::SIZE][..];
+            while !slack.is_empty() {
+                match writer.write(slack)? {
+                    0 => break,
+                    n => slack = &slack[n..],
+                }
+            }
+            return Ok(());
+        }
+        writer.write_all(&[self.version.0])?;
+        self.flags.serialize(writer)?;
+        writer.write_all(&self.reserved)
+    }
+}
+
+impl AnchorDeserialize for AccountTrailer {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut byte = [0u8];
+        if reader.read(&mut byte)? == 0 {
+            return Ok(Self::V1); // v1 account with no slack left
+        }
+        // Slack is zero on every v1 account, first-release or written back
+        // since, so nothing but a version this program wrote reads as one.
+        let version = AccountVersion(byte[0]);
+        if version <= AccountVersion::V1 || version > AccountVersion::CURRENT {
+            return Ok(Self::V1);
+        }
+        let flags = u32::deserialize_reader(reader)?;
+        let mut reserved = [0; Self::RESERVED];
+        reader.read_exact(&mut reserved)?;
+        Ok(Self {
+            version,
+            flags,
+            reserved,
+        })
+    }
+}
+
+// Its current layout in the IDL: clients only ever read accounts this program
+// wrote at the current version.
+#[cfg(feature = "idl-build")]
+impl anchor_lang::idl::build::IdlBuild for AccountTrailer {
+    fn create_type() -> Option<anchor_lang::idl::types::IdlTypeDef> {
+        use anchor_lang::idl::types::{
+            IdlArrayLen, IdlDefinedFields, IdlField, IdlType, IdlTypeDef, IdlTypeDefTy,
+        };
+        let field = |name: &str, ty| IdlField {
+            name: name.into(),
+            docs: vec![],
+            ty,
+        };
+        Some(IdlTypeDef {
+            name: Self::get_full_path(),
+            docs: vec![],
+            serialization: Default::default(),
+            repr: None,
+            generics: vec![],
+            ty: IdlTypeDefTy::Struct {
+                fields: Some(IdlDefinedFields::Named(vec![
+                    field("version", IdlType::U8),
+                    field("flags", IdlType::U32),
+                    field(
+                        "reserved",
+                        IdlType::Array(Box::new(IdlType::U8), IdlArrayLen::Value(Self::RESERVED)),
+                    ),
+                ])),
+            },
+        })
+    }
+
+    fn get_full_path() -> String {
+        format!("{}::{}", module_path!(), "AccountTrailer")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Debug)]
+    struct Record {
+        amount: u64,
+        trailer: AccountTrailer,
+    }
+
+    #[test]
+    fn v1_accounts_read_and_write_their_v1_layout() {
+        let v1 = 7u64.to_le_bytes();
+        // No slack at all, and zero slack.
+        for data in [v1.to_vec(), [&v1[..], &[0; 80]].concat()] {
+            let record = Record::deserialize(&mut data.as_slice()).unwrap();
+            assert_eq!(record.trailer, AccountTrailer::V1);
+            let written = record.try_to_vec().unwrap();
+            assert_eq!(written[..8], v1);
+            assert_eq!(written[8..], [0; AccountTrailer::SIZE]);
+        }
+    }
+
+    #[test]
+    fn v1_write_backs_zero_the_slack_they_cover() {
+        // What a v1 record would leave behind had its data shrunk in place.
+        let mut data = [&7u64.to_le_bytes()[..], &[0x02; 80]].concat();
+        let record = Record {
+            amount: 9,
+            trailer: AccountTrailer::V1,
+        };
+        record.serialize(&mut &mut data[..]).unwrap();
+        assert_eq!(data[8..8 + AccountTrailer::SIZE], [0; AccountTrailer::SIZE]);
+        let read = Record::deserialize(&mut data.as_slice()).unwrap();
+        assert_eq!(read, record);
+
+        // An account with less room than a trailer is zeroed to its end.
+        let mut data = [&7u64.to_le_bytes()[..], &[0x02; 3]].concat();
+        record.serialize(&mut &mut data[..]).unwrap();
+        assert_eq!(data[8..], [0; 3]);
+    }
+
+    #[test]
+    fn current_accounts_carry_their_trailer() {
+        let mut record = Record {
+            amount: 7,
+            trailer: AccountTrailer::CURRENT,
+        };
+        record.trailer.set(1 << 3, true);
+        let data = record.try_to_vec().unwrap();
+        assert_eq!(data.len(), 8 + AccountTrailer::SIZE);
+        assert_eq!(data[8], AccountVersion::CURRENT.get());
+        assert_eq!(data[9..13], 8u32.to_le_bytes());
+        assert_eq!(Record::deserialize(&mut data.as_slice()).unwrap(), record);
+    }
+
+    #[test]
+    fn flags_set_and_clear_single_bits() {
+        let mut trailer = AccountTrailer::CURRENT;
+        trailer.set(0b01, true);
+        trailer.set(0b10, true);
+        trailer.set(0b01, false);
+        assert_eq!(trailer.flags, 0b10);
+        assert!(trailer.has(0b10) && !trailer.has(0b01));
+    }
+}
@@ -0,0 +1,54 @@
+// Vesting streams (propose_stream / claim_stream). A stream's total vests
+// linearly from `start` to `end`. Nothing is claimable before `cliff`; at the
+// cliff everything vested since `start` becomes claimable at once.
+
+// start <= cliff <= end, over a non-empty span.
+pub fn is_valid(start: i64, cliff: i64, end: i64) -> bool {
+    start <= cliff && cliff <= end && start < end
+}
+
+// The part of `total` vested at time `at`.
+pub fn vested(total: u64, start: i64, cliff: i64, end: i64, at: i64) -> u64 {
+    if at < cliff {
+        return 0;
+    }
+    if at >= end {
+        return total;
+    }
+    let elapsed = (at as i128 - start as i128) as u128;
+    let span = (end as i128 - start as i128) as u128;
+    (total as u128 * elapsed / span) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 24 * 60 * 60;
+
+    #[test]
+    fn nothing_vests_before_the_cliff_then_it_catches_up() {
+        let (start, cliff, end) = (0, 90 * DAY, 360 * DAY);
+        assert_eq!(vested(1_200, start, cliff, end, cliff - 1), 0);
+        assert_eq!(vested(1_200, start, cliff, end, cliff), 300);
+        assert_eq!(vested(1_200, start, cliff, end, 180 * DAY), 600);
+        assert_eq!(vested(1_200, start, cliff, end, end), 1_200);
+        assert_eq!(vested(1_200, start, cliff, end, i64::MAX), 1_200);
+    }
+
+    #[test]
+    fn vesting_rounds_down_and_does_not_overflow() {
+        assert_eq!(vested(10, 0, 0, 3, 1), 3);
+        assert_eq!(vested(u64::MAX, 0, 0, 2, 1), u64::MAX / 2);
+        assert_eq!(vested(u64::MAX, i64::MIN, i64::MIN, i64::MAX, 0), 1 << 63);
+    }
+
+    #[test]
+    fn schedules_need_an_ordered_non_empty_span() {
+        assert!(is_valid(0, 0, 1));
+        assert!(is_valid(0, 1, 1));
+        assert!(!is_valid(0, 0, 0));
+        assert!(!is_valid(1, 0, 2));
+        assert!(!is_valid(0, 2, 1));
+    }
+}
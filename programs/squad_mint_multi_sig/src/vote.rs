@@ -0,0 +1,193 @@
+// Quorum math and the approval bitmask slots. Kept free of accounts and Anchor
+// context so the exact pass/reject boundaries (audit M-3) can be tested on the
+// host; the handlers call these directly, so tests and program can't drift.
+
+use crate::SquadMintFund;
+
+// Every member / guardian index fits its mask, so the shifts below can't
+// overflow (proofs.rs checks the same under Kani).
+const _: () = assert!(SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE <= u16::BITS as usize);
+const _: () = assert!(SquadMintFund::SQUAD_MINT_MAX_GUARDIANS <= u8::BITS as usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Thresholds {
+    pub yes_percentage: u64,
+    pub no_percentage: u64,
+}
+
+impl Thresholds {
+    // M-3: intentionally asymmetric; see SquadMintFund's threshold constants.
+    pub const SQUAD_MINT: Self = Self {
+        yes_percentage: SquadMintFund::SQUAD_MINT_YES_THRESHOLD_PERCENTAGE,
+        no_percentage: SquadMintFund::SQUAD_MINT_NO_THRESHOLD_PERCENTAGE,
+    };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+// Bit i of `voted_mask` = member i has voted; bit i of `votes` = member i voted
+// YES (always a subset of voted_mask). YES is checked first, so a vote that
+// somehow crossed both thresholds would approve; with thresholds summing past
+// 100% that can't happen.
+//
+// Plain count_ones() is correct because members can't be removed while a
+// proposal is open, so every set bit belongs to a current member.
+pub fn tally(voted_mask: u16, votes: u16, eligible: u64, thresholds: Thresholds) -> Outcome {
+    let yes_votes = votes.count_ones() as u128;
+    let no_votes = (voted_mask & !votes).count_ones() as u128;
+    let eligible = eligible as u128;
+    if yes_votes * 100 >= thresholds.yes_percentage as u128 * eligible {
+        Outcome::Approved
+    } else if no_votes * 100 >= thresholds.no_percentage as u128 * eligible {
+        Outcome::Rejected
+    } else {
+        Outcome::Pending
+    }
+}
+
+// Bit for members[member_index] in a proposal's voted_mask / votes.
+pub fn member_bit(member_index: usize) -> u16 {
+    1u16 << member_index
+}
+
+// Bit for guardians[guardian_index] in a recovery request's approvals.
+pub fn guardian_bit(guardian_index: usize) -> u8 {
+    1u8 << guardian_index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const MAX_MEMBERS: usize = SquadMintFund::SQUAD_MINT_MAX_PRIVATE_GROUP_SIZE;
+
+    // Smallest YES / NO counts that decide a proposal, indexed by group size.
+    // Written out by hand from 51% / 50% rather than derived from the formula.
+    const MIN_YES_TO_PASS: [u32; MAX_MEMBERS + 1] = [0, 1, 2, 2, 3, 3, 4, 4, 5];
+    const MIN_NO_TO_REJECT: [u32; MAX_MEMBERS + 1] = [0, 1, 1, 2, 2, 3, 3, 4, 4];
+
+    // Every (voted_mask, votes) pair with votes ⊆ voted_mask over `members` bits.
+    fn every_ballot(members: usize) -> impl Iterator<Item = (u16, u16)> {
+        (0u16..1 << members).flat_map(|voted_mask| {
+            let mut yes = voted_mask;
+            let mut subsets = vec![];
+            loop {
+                subsets.push((voted_mask, yes));
+                if yes == 0 {
+                    break;
+                }
+                yes = (yes - 1) & voted_mask;
+            }
+            subsets
+        })
+    }
+
+    #[test]
+    fn exhaustive_boundaries_for_every_group_size() {
+        for members in 1..=MAX_MEMBERS {
+            let mut seen = 0;
+            for (voted_mask, votes) in every_ballot(members) {
+                let yes = votes.count_ones();
+                let no = (voted_mask & !votes).count_ones();
+                let expected = if yes >= MIN_YES_TO_PASS[members] {
+                    Outcome::Approved
+                } else if no >= MIN_NO_TO_REJECT[members] {
+                    Outcome::Rejected
+                } else {
+                    Outcome::Pending
+                };
+                assert_eq!(
+                    tally(voted_mask, votes, members as u64, Thresholds::SQUAD_MINT),
+                    expected,
+                    "members={members} voted={voted_mask:#b} yes={votes:#b}"
+                );
+                seen += 1;
+            }
+            // Each member is unvoted / NO / YES: 3^n ballots.
+            assert_eq!(seen, 3usize.pow(members as u32));
+        }
+    }
+
+    #[test]
+    fn the_asymmetry_the_audit_called_out() {
+        let t = Thresholds::SQUAD_MINT;
+        // 2 members, 1-1 split: 50% YES can't spend, 50% NO rejects.
+        assert_eq!(tally(0b11, 0b01, 2, t), Outcome::Rejected);
+        // Exactly half YES with nobody against stays open.
+        assert_eq!(tally(0b0011, 0b0011, 4, t), Outcome::Pending);
+        assert_eq!(tally(0b0111, 0b0111, 4, t), Outcome::Approved);
+        // A sole member decides alone either way.
+        assert_eq!(tally(0b1, 0b1, 1, t), Outcome::Approved);
+        assert_eq!(tally(0b1, 0b0, 1, t), Outcome::Rejected);
+        assert_eq!(tally(0, 0, 1, t), Outcome::Pending);
+    }
+
+    fn ballot(members: usize) -> impl Strategy<Value = (u16, u16)> {
+        let all = ((1u32 << members) - 1) as u16;
+        (0..=all, 0..=all).prop_map(|(voted_mask, yes)| (voted_mask, yes & voted_mask))
+    }
+
+    fn sized_ballot() -> impl Strategy<Value = (usize, u16, u16)> {
+        (1usize..=16).prop_flat_map(|members| {
+            ballot(members).prop_map(move |(voted_mask, votes)| (members, voted_mask, votes))
+        })
+    }
+
+    proptest! {
+        // Only the counts matter, never which members cast them.
+        #[test]
+        fn outcome_ignores_which_members_voted(
+            (members, voted_mask, votes) in sized_ballot(),
+            rotate in 0u32..16,
+        ) {
+            let all = ((1u32 << members) - 1) as u16;
+            let rot = |m: u16| ((m as u32 | (m as u32) << members) >> (rotate % members as u32)) as u16 & all;
+            prop_assert_eq!(
+                tally(voted_mask, votes, members as u64, Thresholds::SQUAD_MINT),
+                tally(rot(voted_mask), rot(votes), members as u64, Thresholds::SQUAD_MINT)
+            );
+        }
+
+        // One more YES never un-approves, and one more NO never un-rejects.
+        #[test]
+        fn extra_votes_are_monotonic((members, voted_mask, votes) in sized_ballot(), member in 0usize..16) {
+            let bit = 1u16 << (member % members);
+            prop_assume!(voted_mask & bit == 0);
+            let before = tally(voted_mask, votes, members as u64, Thresholds::SQUAD_MINT);
+            let with_yes = tally(voted_mask | bit, votes | bit, members as u64, Thresholds::SQUAD_MINT);
+            let with_no = tally(voted_mask | bit, votes, members as u64, Thresholds::SQUAD_MINT);
+            if before == Outcome::Approved {
+                prop_assert_eq!(with_yes, Outcome::Approved);
+            }
+            if before == Outcome::Rejected {
+                prop_assert_eq!(with_no, Outcome::Rejected);
+            }
+        }
+
+        // With the production thresholds a full turnout always decides, up to
+        // the 16 members a u16 mask can hold.
+        #[test]
+        fn full_turnout_always_decides((members, _, yes) in sized_ballot()) {
+            let all = ((1u32 << members) - 1) as u16;
+            let outcome = tally(all, yes, members as u64, Thresholds::SQUAD_MINT);
+            prop_assert_ne!(outcome, Outcome::Pending);
+            prop_assert_eq!(
+                outcome == Outcome::Approved,
+                yes.count_ones() as u64 * 100 >= 51 * members as u64
+            );
+        }
+
+        // Any thresholds: nobody voting never decides a non-empty group.
+        #[test]
+        fn no_votes_stay_pending(members in 1u64..=16, yes_pct in 1u64..=100, no_pct in 1u64..=100) {
+            let t = Thresholds { yes_percentage: yes_pct, no_percentage: no_pct };
+            prop_assert_eq!(tally(0, 0, members, t), Outcome::Pending);
+        }
+    }
+}
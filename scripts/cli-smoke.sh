@@ -0,0 +1,113 @@
+#!/usr/bin/env bash
+#
+# End-to-end smoke test of the `squadmint` CLI against a throwaway local
+# validator: set up the program config, create a fund, join it, pay out
+# through a vote, and check the vault. Exercises --dry-run, real sends, and offline signing of a vote with a
+# durable nonce.
+#
+# Needs solana-test-validator and spl-token on PATH (both ship with the Solana
+# CLI) and the program built to target/deploy/squad_mint_multi_sig.so
+# (`anchor build`, or `cargo build-sbf ... --sbf-out-dir target/deploy`).
+set -euo pipefail
+
+ROOT="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
+PROGRAM_ID=BW1dtKfuqUPZxyYKfFCgUwo8tzqnGfw9of5L4yfAzuRz
+SO="$ROOT/target/deploy/squad_mint_multi_sig.so"
+URL=http://127.0.0.1:8899
+WORK="$(mktemp -d)"
+
+[ -f "$SO" ] || { echo "missing $SO — run anchor build first" >&2; exit 1; }
+
+cleanup() {
+  [ -n "${VALIDATOR_PID:-}" ] && kill "$VALIDATOR_PID" 2>/dev/null || true
+  rm -rf "$WORK"
+}
+trap cleanup EXIT
+
+cargo build --manifest-path "$ROOT/Cargo.toml" -p squadmint --quiet
+SQUADMINT="$ROOT/target/debug/squadmint"
+
+for name in owner joiner; do
+  solana-keygen new --no-bip39-passphrase --silent -o "$WORK/$name.json"
+done
+OWNER="$(solana address -k "$WORK/owner.json")"
+JOINER="$(solana address -k "$WORK/joiner.json")"
+
+# Upgradeable, with the owner as upgrade authority, so it can create the config.
+solana-test-validator --reset --quiet --ledger "$WORK/ledger" \
+  --upgradeable-program "$PROGRAM_ID" "$SO" "$OWNER" &
+VALIDATOR_PID=$!
+for _ in $(seq 60); do
+  solana -u "$URL" cluster-version >/dev/null 2>&1 && break
+  sleep 1
+done
+for name in owner joiner; do
+  solana -u "$URL" airdrop 10 "$WORK/$name.json" >/dev/null
+done
+
+# The same fixed mint keypair tests/helper_function.ts uses (37KQMrbB...).
+echo '[210,40,28,92,229,219,114,103,145,62,217,155,249,74,11,10,251,253,161,193,126,149,95,42,223,199,95,89,84,139,245,90,31,85,170,177,15,252,17,17,71,117,214,26,224,159,91,94,8,163,199,52,77,141,3,95,238,223,130,19,49,141,254,1]' \
+  > "$WORK/mint.json"
+
+spl-token -u "$URL" --fee-payer "$WORK/owner.json" \
+  create-token --decimals 6 --mint-authority "$OWNER" "$WORK/mint.json" >/dev/null
+MINT="$(solana address -k "$WORK/mint.json")"
+spl-token -u "$URL" --fee-payer "$WORK/owner.json" \
+  create-account "$MINT" --owner "$JOINER" >/dev/null
+spl-token -u "$URL" --fee-payer "$WORK/owner.json" \
+  mint "$MINT" 10 --mint-authority "$WORK/owner.json" --recipient-owner "$JOINER" >/dev/null
+
+as_owner() { "$SQUADMINT" -u "$URL" -k "$WORK/owner.json" "$@"; }
+as_joiner() { "$SQUADMINT" -u "$URL" -k "$WORK/joiner.json" "$@"; }
+# expect <pattern> <command...>: run it, echo its output, fail unless it matches.
+# (Captured first: `cmd | grep -q` can SIGPIPE cmd under pipefail.)
+expect() {
+  local pattern="$1" out
+  shift
+  out="$("$@")"
+  echo "$out"
+  grep -q -- "$pattern" <<<"$out" || { echo "expected '$pattern' from: $*" >&2; exit 1; }
+}
+
+as_owner config init --admin "$OWNER" --allow "$MINT"
+expect "$MINT" as_owner config show
+
+FUND="$(as_owner fund create --handle smoke --join-amount 2.5 | awk '/^fund/ { print $2 }')"
+as_owner fund show "$FUND"
+expect "$FUND" as_owner fund list --owner "$OWNER"
+
+as_joiner join request "$FUND"
+as_owner join show "$FUND" "$JOINER"
+expect "simulation       ok" as_owner --dry-run join accept "$FUND" "$JOINER"
+as_owner join accept "$FUND" "$JOINER"
+expect "balance          2.500000" as_owner vault balance "$FUND"
+expect "$FUND" as_owner fund list --member "$JOINER"
+
+# Owner proposes (auto-YES = 1 of 2, not yet 51%); the joiner's YES executes it.
+as_owner proposal create "$FUND" --amount 1 --to "$OWNER"
+as_owner proposal show "$FUND"
+as_joiner proposal vote "$FUND" yes
+expect "balance          1.500000" as_owner vault balance "$FUND"
+expect "master nonce     1" as_owner fund show "$FUND"
+
+# Air-gapped vote: the owner's (online) machine builds the joiner's vote
+# against a durable nonce, the joiner signs the file without touching the RPC,
+# the owner co-signs as fee payer and nonce authority and sends it.
+solana-keygen new --no-bip39-passphrase --silent -o "$WORK/nonce.json"
+solana -u "$URL" -k "$WORK/owner.json" \
+  create-nonce-account "$WORK/nonce.json" 0.01 --nonce-authority "$OWNER" >/dev/null
+NONCE="$(solana address -k "$WORK/nonce.json")"
+
+as_owner proposal create "$FUND" --amount 0.5 --to "$OWNER"
+as_owner proposal vote "$FUND" yes --nonce "$NONCE" \
+  --signer "$JOINER" --fee-payer "$OWNER" --unsigned "$WORK/vote.tx"
+# No reachable RPC for the "offline" joiner.
+offline_joiner() { "$SQUADMINT" -u http://127.0.0.1:1 -k "$WORK/joiner.json" "$@"; }
+expect "submit_and_execute vote yes" offline_joiner tx inspect "$WORK/vote.tx"
+expect "still needs" offline_joiner tx sign "$WORK/vote.tx"
+expect "complete" as_owner tx sign "$WORK/vote.tx"
+as_owner tx send "$WORK/vote.tx"
+expect "balance          1.000000" as_owner vault balance "$FUND"
+expect "master nonce     2" as_owner fund show "$FUND"
+
+echo "OK: squadmint CLI smoke test passed"
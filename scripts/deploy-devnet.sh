@@ -23,6 +23,7 @@ set -euo pipefail
 
 # ---- Pinned constants (single source of truth) ----------------------------
 PROGRAM_ID="BW1dtKfuqUPZxyYKfFCgUwo8tzqnGfw9of5L4yfAzuRz"
+# Allowed in the devnet program config (`squadmint config init`), not built in.
 DEVNET_USDC_MINT="GHCrkDPkTDigsEXMctzEsWc48nWPufcgaqqK4vexQX1f"
 
 ROOT="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
@@ -43,9 +44,9 @@ echo "  rpc        : $SAFE_URL"
 # ---- 0. id-consistency guard ----------------------------------------------
 bash "$ROOT/scripts/check-program-id.sh"
 
-# ---- 1. build with the devnet mint pinned ---------------------------------
-echo "▶ Building (SQUADMINT_USDC_MINT=$DEVNET_USDC_MINT)…"
-SQUADMINT_USDC_MINT="$DEVNET_USDC_MINT" anchor build
+# ---- 1. build (cluster-independent; mints live in the program config) -----
+echo "▶ Building…"
+anchor build
 [[ -f "$SO" ]] || { echo "::error::built artifact missing: $SO"; exit 1; }
 SO_LEN="$(wc -c < "$SO" | tr -d ' ')"
 
@@ -104,3 +105,4 @@ if [[ "$AFTER_SLOT" == "$BEFORE_SLOT" ]]; then
   echo "::error::deploy slot did not advance — the upgrade did NOT take. Investigate."; exit 1
 fi
 echo "✅ Upgraded $PROGRAM_ID in place: slot $BEFORE_SLOT → $AFTER_SLOT"
+echo "  no config on this cluster yet? squadmint -u <RPC> -k $WALLET config init --admin $WALLET_PUB --allow $DEVNET_USDC_MINT"
@@ -34,6 +34,7 @@ import {
     amountToSmalletDecimal,
     createWallet,
     decimals,
+    dissolveFund,
     encodeHandle,
     findATAForPDAForAuthority,
     findATAForPDAForAuthority2,
@@ -43,6 +44,8 @@ import {
     findPDAForMultisigTransaction,
     initializeAccount,
     initiateJoinRequest,
+    initiateRecovery,
+    proposeDissolution,
     transferTokens,
     WalletWithAta,
 } from "./helper_function";
@@ -111,11 +114,8 @@ const createProposalRaw = async (
             feePayer: feePayer.publicKey,
             proposer: proposer.keyPair.publicKey,
             mint,
-            proposedToOwner: proposedTo.keyPair.publicKey,
             multisigAta: vaultAta,
-            proposedToAta: proposedTo.ataAccount.address,
             tokenProgram,
-            associatedTokenProgram,
             systemProgram,
         })
         .signers([feePayer, proposer.keyPair])
@@ -155,11 +155,8 @@ describe("SquadMint — hacker / red-team tests", () => {
                 feePayer: feePayer.publicKey,
                 proposer: attacker.keyPair.publicKey,
                 mint,
-                proposedToOwner: attacker.keyPair.publicKey,
                 multisigAta: vaultAta,
-                proposedToAta: attacker.ataAccount.address,
                 tokenProgram,
-                associatedTokenProgram,
                 systemProgram,
             })
             .signers([feePayer, attacker.keyPair])
@@ -323,6 +320,33 @@ describe("SquadMint — hacker / red-team tests", () => {
         await expect(attempt).to.be.rejected; // JoiningAmountShouldMatchTargetWallet
     });
 
+    it("cannot propose a payout back to the fund or its vault", async () => {
+        const { owner, pda } = await makeFund("hk_selfPay", 0);
+        await fundVault(pda, 5);
+        const vaultAta = await findATAForPDAForAuthority2(program.programId, pda);
+
+        // Neither owner key could ever sign for an ATA derived from them.
+        for (const target of [pda, vaultAta]) {
+            const fund = await program.account.squadMintFund.fetch(pda);
+            const txPda = await findPDAForMultisigTransaction(program.programId, pda, fund.accountHandle, fund.masterNonce);
+            const attempt = program.methods
+                .createProposal(new BN(amountToSmalletDecimal(1)), target)
+                .accounts({
+                    transaction: txPda,
+                    multisig: pda,
+                    feePayer: feePayer.publicKey,
+                    proposer: owner.keyPair.publicKey,
+                    mint,
+                    multisigAta: vaultAta,
+                    tokenProgram,
+                    systemProgram,
+                })
+                .signers([feePayer, owner.keyPair])
+                .rpc();
+            await expect(attempt).to.be.rejectedWith(/InvalidDestinationOwner/);
+        }
+    });
+
     // ============== Drain the whole program (cross-fund) ==============
 
     it("cannot drain another fund's vault by swapping in a foreign multisig_ata", async () => {
@@ -372,7 +396,7 @@ describe("SquadMint — hacker / red-team tests", () => {
     // ============== Corrupt the setup ==============
 
     it("cannot create a fund on a non-USDC mint", async () => {
-        // Fresh random mint -> address differs from the pinned USDC_MINT.
+        // Fresh random mint -> not on the config's allowed list.
         const fakeMint = await createMint(
             connection,
             feePayer,
@@ -385,7 +409,7 @@ describe("SquadMint — hacker / red-team tests", () => {
         const pda = await findPDAForAuthority(program.programId, owner.publicKey, "hk_fakeMint");
 
         const attempt = program.methods
-            .initialize(encodeHandle("hk_fakeMint"), JOIN_AMOUNT())
+            .initialize(encodeHandle("hk_fakeMint"), JOIN_AMOUNT(), [], 0)
             .accounts({
                 multisigOwner: owner.publicKey,
                 feePayer: feePayer.publicKey,
@@ -414,7 +438,7 @@ describe("SquadMint — hacker / red-team tests", () => {
         const custodialAta = findATAForPDAForJoinCustodialAccount(program.programId, custodialPda);
 
         const attempt = program.methods
-            .initiateJoinRequest(JOIN_AMOUNT())
+            .initiateJoinRequest(JOIN_AMOUNT(), null)
             .accounts({
                 multisig: pda,
                 feePayer: feePayer.publicKey,
@@ -432,4 +456,95 @@ describe("SquadMint — hacker / red-team tests", () => {
 
         await expect(attempt).to.be.rejectedWith(/InvalidMint/);
     });
+
+    // ============== Ownership takeover ==============
+
+    it("a non-guardian cannot open a recovery to seize owner privileges", async () => {
+        const guardian = anchor.web3.Keypair.generate();
+        const owner = await createWallet(connection, mint, feePayer, 2);
+        const pda = await initializeAccount(program, owner.keyPair, feePayer, mint, "hk_recovery", [guardian.publicKey], 1);
+
+        const attacker = anchor.web3.Keypair.generate();
+        const attempt = initiateRecovery(program, pda, attacker, attacker.publicKey, feePayer);
+        await expect(attempt).to.be.rejectedWith(/NotAGuardian/);
+
+        const fund = await program.account.squadMintFund.fetch(pda);
+        expect(fund.hasPendingRecovery).to.be.false;
+        expect(fund.recoveredOwner).to.be.null;
+    });
+
+    // ============== Dissolution ==============
+
+    it("cannot siphon a dissolution payout by substituting a member's token account", async () => {
+        const { owner, pda, members } = await makeFund("hk_dissolve", 1); // owner + memberA
+        await fundVault(pda, 5);
+        const memberA = members[1];
+
+        const { transaction } = await proposeDissolution(
+            program, pda, owner.keyPair, { equal: {} }, owner.keyPair.publicKey, feePayer
+        );
+        const vaultAta = await findATAForPDAForAuthority2(program.programId, pda);
+        await program.methods
+            .submitAndExecute(true)
+            .accounts({
+                transaction,
+                multisig: pda,
+                feePayer: feePayer.publicKey,
+                submitter: memberA.keyPair.publicKey,
+                mint,
+                proposedToOwner: owner.keyPair.publicKey,
+                multisigAta: vaultAta,
+                proposedToAta: owner.ataAccount.address,
+                tokenProgram,
+                associatedTokenProgram,
+                systemProgram,
+            })
+            .signers([feePayer, memberA.keyPair])
+            .rpc();
+
+        // The owner cranks the approved dissolution but routes memberA's share
+        // to a token account they control.
+        const attackerSink = await createWallet(connection, mint, feePayer, 1);
+        const attempt = dissolveFund(
+            program, pda, owner.keyPair.publicKey,
+            [owner.ataAccount.address, attackerSink.ataAccount.address], mint,
+        );
+        await expect(attempt).to.be.rejectedWith(/InvalidMemberTokenAccount/);
+
+        const vault = await getAccount(connection, vaultAta);
+        expect(vault.amount > BigInt(0)).to.be.true;
+    });
+
+    // ============== Rent harvesting (audit L-1) ==============
+
+    it("the closing fee payer cannot redirect a join request's rent to themselves", async () => {
+        const { owner, pda } = await makeFund("hk_rentGrab", 0);
+        const joiner = await createWallet(connection, mint, feePayer, 2);
+        await initiateJoinRequest(program, pda, joiner, JOIN_AMOUNT(), feePayer, mint);
+        const custodialPda = await findPDAForJoinCustodialAccount(program.programId, pda, joiner.keyPair.publicKey);
+
+        const harvester = anchor.web3.Keypair.generate();
+        const attempt = program.methods
+            .rejectMember(joiner.keyPair.publicKey)
+            .accounts({
+                multisig: pda,
+                feePayer: feePayer.publicKey,
+                multisigOwner: owner.keyPair.publicKey,
+                mint,
+                proposingJoiner: joiner.keyPair.publicKey,
+                proposingJoinerAta: joiner.ataAccount.address,
+                rentPayer: harvester.publicKey,
+                joinCustodialAccount: custodialPda,
+                joinCustodialAccountAta: findATAForPDAForJoinCustodialAccount(program.programId, custodialPda),
+                multisigAta: await findATAForPDAForAuthority2(program.programId, pda),
+                tokenProgram,
+                associatedTokenProgram,
+                systemProgram,
+            })
+            .signers([feePayer, owner.keyPair])
+            .rpc();
+
+        await expect(attempt).to.be.rejectedWith(/RentPayerMismatch/);
+        expect(await connection.getBalance(harvester.publicKey)).to.equal(0);
+    });
 });
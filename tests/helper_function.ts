@@ -179,13 +179,15 @@ const initializeAccount = async (program: Program<SquadMintMultiSig>,
                                  owner: anchor.web3.Keypair,
                                  squadMintFeePayer: anchor.web3.Keypair,
                                  mint: anchor.web3.PublicKey,
-                                 walletHandle: string): Promise<anchor.web3.PublicKey> => {
+                                 walletHandle: string,
+                                 guardians: PublicKey[] = [],
+                                 guardianThreshold: number = 0): Promise<anchor.web3.PublicKey> => {
     // const accountKeypair = anchor.web3.Keypair.generate();
     const pda = await findPDAForAuthority(program.programId, owner.publicKey, walletHandle);
     // const pdaATA = await findATAForPDAForAuthority(pda, mint)
     const pdaATA = await findATAForPDAForAuthority2(program.programId, pda)
     console.log("🦾️ Found PDA on our Client for Wallet:  \n" + walletHandle + " PDA: \n"  + pda.toBase58() + "  Authority: \n" + owner.publicKey.toBase58() + " PDA ATA: \n" + pdaATA + " mint \n" + mint.toBase58() + "And fee payer:  \n" + squadMintFeePayer.publicKey.toBase58())
-    await program.methods.initialize(encodeHandle(walletHandle), new BN(amountToSmalletDecimal(1.11)))
+    await program.methods.initialize(encodeHandle(walletHandle), new BN(amountToSmalletDecimal(1.11)), guardians, guardianThreshold)
         .accounts({
             multisigOwner: owner.publicKey,
             feePayer: squadMintFeePayer.publicKey,
@@ -239,11 +241,11 @@ const checkAccountFieldsAreInitializedCorrectly = async (
 
     return fund;
 };
-// Fixed mint keypair so the test mint address always equals the program's
-// pinned USDC_MINT const (37KQMrbBtkNFYJvDKW3tGxEs1WuvqcEeu44JGrjPkYsz).
-// `initialize` rejects any other mint, so this must match the const in
-// programs/squad_mint_multi_sig/src/lib.rs. (Fresh localnet per `anchor
-// test`, so re-creating the same mint address each run is fine.)
+// Fixed mint keypair so the test mint is always at the same address
+// (37KQMrbBtkNFYJvDKW3tGxEs1WuvqcEeu44JGrjPkYsz), shared with the LiteSVM
+// harness and scripts/cli-smoke.sh. It's the only mint the test config
+// allows (see shared_setup.ts). (Fresh localnet per `anchor test`, so
+// re-creating the same mint address each run is fine.)
 const USDC_MINT_KEYPAIR = anchor.web3.Keypair.fromSecretKey(
     Uint8Array.from([
         210, 40, 28, 92, 229, 219, 114, 103, 145, 62, 217, 155, 249, 74, 11, 10,
@@ -257,15 +259,14 @@ const USDC_MINT_KEYPAIR = anchor.web3.Keypair.fromSecretKey(
     connection: anchor.web3.Connection,
     payer: anchor.web3.Keypair,
 ) {
-    // Create the mint at the fixed USDC keypair so its address matches the
-    // program's pinned USDC_MINT const.
+    // Create the mint at the fixed USDC keypair so its address is stable.
     const mintPubkey = await createMint(
         connection,
         payer,        // payer for transaction & rent
         payer.publicKey,        // mint authority
         payer.publicKey,        // freeze authority (optional)
         decimals,
-        USDC_MINT_KEYPAIR,      // deterministic mint address == USDC_MINT
+        USDC_MINT_KEYPAIR,      // deterministic mint address
     );
 
     const tokenAccount = await getOrCreateAssociatedTokenAccount(
@@ -331,11 +332,12 @@ const USDC_MINT_KEYPAIR = anchor.web3.Keypair.fromSecretKey(
 
     // 5. Execute
     const sig = await program.methods
-        .initiateJoinRequest(amount)
+        .initiateJoinRequest(amount, null)
         .accounts({
             multisig: multisigPda,
             feePayer: feePayer.publicKey,
             mint: mint,
+            multisigAta: multisigAta,
             proposingJoiner: requestToJoinMember.keyPair.publicKey,
             proposingJoinerAta: requestToJoinMember.ataAccount.address,
             joinCustodialAccount: joinCustodialPda,
@@ -433,6 +435,137 @@ const rejectMember = async (
     return { sig, multisigPda, joinCustodialPda };
 };
 
+const findPDAForRecoveryRequest = (
+    programId: anchor.web3.PublicKey,
+    multisigPda: anchor.web3.PublicKey,
+): anchor.web3.PublicKey => {
+    const [pda, _canonicalBump] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+            utf8.encode("recovery_request"),
+            multisigPda.toBytes()
+        ], programId
+    );
+    return pda;
+};
+
+const initiateRecovery = async (
+    program: Program<SquadMintMultiSig>,
+    multisigPda: PublicKey,
+    guardian: Keypair,
+    newOwner: PublicKey,
+    feePayer: Keypair,
+) => {
+    const recoveryRequest = findPDAForRecoveryRequest(program.programId, multisigPda);
+    const sig = await program.methods
+        .initiateRecovery(newOwner)
+        .accounts({
+            multisig: multisigPda,
+            feePayer: feePayer.publicKey,
+            guardian: guardian.publicKey,
+            recoveryRequest,
+            systemProgram: anchor.web3.SystemProgram.programId
+        })
+        .signers([feePayer, guardian])
+        .rpc();
+
+    console.log("Recovery initiated:", sig);
+    return { sig, recoveryRequest };
+};
+
+// Opens a Dissolve proposal in the fund's single active-vote slot. The
+// proposer auto-votes YES, exactly like createProposal.
+const proposeDissolution = async (
+    program: Program<SquadMintMultiSig>,
+    multisigPda: PublicKey,
+    proposer: Keypair,
+    distribution: { equal: {} } | { proRata: {} },
+    rentRecipient: PublicKey,
+    feePayer: Keypair,
+) => {
+    const fund = await program.account.squadMintFund.fetch(multisigPda);
+    const transaction = await findPDAForMultisigTransaction(program.programId, multisigPda, "", fund.masterNonce);
+    const sig = await program.methods
+        .proposeDissolution(distribution, rentRecipient)
+        .accounts({
+            transaction,
+            multisig: multisigPda,
+            feePayer: feePayer.publicKey,
+            proposer: proposer.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId
+        })
+        .signers([feePayer, proposer])
+        .rpc();
+
+    console.log("Dissolution proposed:", sig);
+    return { sig, transaction };
+};
+
+// Pays the vault out to `memberAtas` (every member's ATA, in member order) and
+// closes the vault and the fund. Permissionless once a dissolution passed.
+const dissolveFund = async (
+    program: Program<SquadMintMultiSig>,
+    multisigPda: PublicKey,
+    rentRecipient: PublicKey,
+    memberAtas: PublicKey[],
+    mint: PublicKey,
+    // Whoever paid a dues schedule's rent; unused by funds without one.
+    duesRentPayer: PublicKey = rentRecipient,
+) => {
+    const multisigAta = await findATAForPDAForAuthority2(program.programId, multisigPda);
+    const sig = await program.methods
+        .dissolveFund()
+        .accounts({
+            multisig: multisigPda,
+            rentRecipient,
+            mint,
+            multisigAta,
+            duesRentPayer,
+            tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .remainingAccounts(memberAtas.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false })))
+        .rpc();
+
+    console.log("Fund dissolved:", sig);
+    return { sig };
+};
+
+// Permissionless: refunds a join escrow the fund can no longer accept (full,
+// dissolving or dissolved). `feePayer` need not be the owner or the joiner.
+const cancelStaleJoinRequest = async (
+    program: Program<SquadMintMultiSig>,
+    multisigPda: PublicKey,
+    requestToJoinMember: WalletWithAta,
+    feePayer: Keypair,
+    mint: PublicKey
+) => {
+    const joinCustodialPda = await findPDAForJoinCustodialAccount(
+        program.programId,
+        multisigPda,
+        requestToJoinMember.keyPair.publicKey
+    );
+    const joinCustodialAta = findATAForPDAForJoinCustodialAccount(program.programId, joinCustodialPda);
+
+    const sig = await program.methods
+        .cancelStaleJoinRequest()
+        .accounts({
+            multisig: multisigPda,
+            feePayer: feePayer.publicKey,
+            mint,
+            proposingJoiner: requestToJoinMember.keyPair.publicKey,
+            proposingJoinerAta: requestToJoinMember.ataAccount.address,
+            joinCustodialAccount: joinCustodialPda,
+            joinCustodialAccountAta: joinCustodialAta,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: anchor.web3.SystemProgram.programId
+        })
+        .signers([feePayer])
+        .rpc();
+
+    console.log("Stale join request cancelled:", sig);
+    return { sig, joinCustodialPda };
+};
+
 // const fetchAccount = async (program: Program<HelloWorld>, authority: anchor.web3.PublicKey) => {
 //     return await program.account.myAccount.fetch(await findPDAForAuthority(program.programId, authority))
 // }
@@ -457,5 +590,10 @@ export {
     addMember,
     decodeHandle,
     encodeHandle,
-    WalletWithAta, rejectMember
+    WalletWithAta, rejectMember,
+    findPDAForRecoveryRequest,
+    initiateRecovery,
+    proposeDissolution,
+    dissolveFund,
+    cancelStaleJoinRequest
 };
\ No newline at end of file
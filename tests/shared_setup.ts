@@ -1,5 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
 import { PublicKey } from "@solana/web3.js";
+import { SquadMintMultiSig } from "../target/types/squad_mint_multi_sig";
 import { createTestMint } from "./helper_function";
 
 export interface SharedCtx {
@@ -40,14 +42,39 @@ const fundedPayer = async (
     return kp;
 };
 
+// The program config can only be created once per ledger, by the upgrade
+// authority: the provider wallet `anchor test` deployed with. Allows just the
+// test mint; the fee payer becomes its admin.
+const initializeConfig = async (
+    feePayer: anchor.web3.Keypair,
+    mint: PublicKey,
+) => {
+    const program = anchor.workspace.SquadMintMultiSig as Program<SquadMintMultiSig>;
+    const provider = program.provider as anchor.AnchorProvider;
+    const [programData] = PublicKey.findProgramAddressSync(
+        [program.programId.toBuffer()],
+        new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111"),
+    );
+    await program.methods
+        .initializeConfig(feePayer.publicKey, [mint])
+        .accounts({
+            feePayer: feePayer.publicKey,
+            upgradeAuthority: provider.wallet.publicKey,
+            programData,
+        })
+        .signers([feePayer])
+        .rpc();
+};
+
 /**
  * Both test files (squad_mint_multi_sig.ts + hacker_tests.ts) run in a single
- * mocha process against one ledger, and the program pins a SINGLE mint address
- * (USDC_MINT). That mint can therefore only be created once, by one authority.
+ * mocha process against one ledger, and the test mint lives at a fixed
+ * address, as does the program config allowing it. Each can therefore only be
+ * created once.
  *
- * This memoizes one fee payer (which doubles as the mint authority) plus the
- * pinned mint so whichever file's `before()` runs first creates them and the
- * other reuses the exact same instances. Order-independent.
+ * This memoizes one fee payer (which doubles as the mint authority and config
+ * admin) plus the mint so whichever file's `before()` runs first creates them
+ * and the other reuses the exact same instances. Order-independent.
  */
 export async function getSharedCtx(
     connection: anchor.web3.Connection,
@@ -55,6 +82,7 @@ export async function getSharedCtx(
     if (_ctx) return _ctx;
     const feePayer = await fundedPayer(connection);
     const testMint = await createTestMint(connection, feePayer);
+    await initializeConfig(feePayer, testMint.mintPubkey);
     _ctx = { feePayer, testMint };
     return _ctx;
 }
@@ -10,14 +10,15 @@ let chaiAsPromised: any;
 import {
     addMember,
     amountToSmalletDecimal,
+    cancelStaleJoinRequest,
     checkAccountFieldsAreInitializedCorrectly,
-    createWallet, decimals, decodeHandle, encodeHandle, findATAForPDAForAuthority,
+    createFeePayerWallet, createWallet, decimals, decodeHandle, dissolveFund, encodeHandle, findATAForPDAForAuthority,
     findATAForPDAForAuthority2, findATAForPDAForJoinCustodialAccount,
     findPDAForAuthority, findPDAForJoinCustodialAccount,
     findPDAForMultisigTransaction,
     getAllAccountsByAuthority,
     initializeAccount,
-    initiateJoinRequest, rejectMember,
+    initiateJoinRequest, initiateRecovery, proposeDissolution, rejectMember,
     transferTokens, WalletWithAta
 } from "./helper_function";
 
@@ -28,6 +29,7 @@ import {
     closeAccount,
     getAccount,
     getAssociatedTokenAddress,
+    getAssociatedTokenAddressSync,
     getOrCreateAssociatedTokenAccount, mintTo,
     TOKEN_PROGRAM_ID,
     transfer
@@ -547,11 +549,8 @@ describe("SquadMint Multisig program tests", () => {
                 feePayer: squadMintFeePayer.publicKey,
                 proposer: memberOpenFundWallet.keyPair.publicKey,
                 mint: testMint.mintPubkey,
-                proposedToOwner: proposedToWallet.keyPair.publicKey,
                 multisigAta: multisigAta,
-                proposedToAta: proposedToWallet.ataAccount.address,
                 tokenProgram: TOKEN_PROGRAM_ID,
-                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                 systemProgram: anchor.web3.SystemProgram.programId // can remove this
             })
             .signers([squadMintFeePayer, memberOpenFundWallet.keyPair])
@@ -599,11 +598,8 @@ describe("SquadMint Multisig program tests", () => {
                 feePayer: squadMintFeePayer.publicKey,
                 proposer: memberOpenFundWallet.keyPair.publicKey,
                 mint: testMint.mintPubkey,
-                proposedToOwner: proposedToWallet.keyPair.publicKey,
                 multisigAta: ata,
-                proposedToAta: proposedToWallet.ataAccount.address,
                 tokenProgram: TOKEN_PROGRAM_ID,
-                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                 systemProgram: anchor.web3.SystemProgram.programId
             })
             .signers([squadMintFeePayer, memberOpenFundWallet.keyPair])
@@ -641,11 +637,8 @@ describe("SquadMint Multisig program tests", () => {
                 feePayer: squadMintFeePayer.publicKey,
                 proposer: memberOpenFundWallet.keyPair.publicKey,
                 mint: testMint.mintPubkey,
-                proposedToOwner: proposedToWallet.keyPair.publicKey,
                 multisigAta: ata,
-                proposedToAta: proposedToWallet.ataAccount.address,
                 tokenProgram: TOKEN_PROGRAM_ID,
-                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                 systemProgram: anchor.web3.SystemProgram.programId
             })
             .signers([squadMintFeePayer, unInitializedMember])
@@ -739,11 +732,8 @@ describe("SquadMint Multisig program tests", () => {
                 feePayer: squadMintFeePayer.publicKey,
                 proposer: walletOwnerAndCreator2.keyPair.publicKey,
                 mint: testMint.mintPubkey,
-                proposedToOwner: proposedToWallet.keyPair.publicKey,
                 multisigAta: ata,
-                proposedToAta: proposedToWallet.ataAccount.address,
                 tokenProgram: TOKEN_PROGRAM_ID,
-                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                 systemProgram: anchor.web3.SystemProgram.programId
             })
             .signers([squadMintFeePayer, walletOwnerAndCreator2.keyPair])
@@ -778,11 +768,8 @@ describe("SquadMint Multisig program tests", () => {
                 feePayer: squadMintFeePayer.publicKey,
                 proposer: walletOwnerAndCreator2.keyPair.publicKey,
                 mint: testMint.mintPubkey,
-                proposedToOwner: proposedToWallet.keyPair.publicKey,
                 multisigAta: ata,
-                proposedToAta: proposedToWallet.ataAccount.address,
                 tokenProgram: TOKEN_PROGRAM_ID,
-                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                 systemProgram: anchor.web3.SystemProgram.programId
             })
             .signers([squadMintFeePayer, walletOwnerAndCreator2.keyPair])
@@ -937,7 +924,7 @@ describe("SquadMint Multisig program tests", () => {
         const pda = await findPDAForAuthority(program.programId, owner.publicKey, "lowJoinFund");
         const pdaAta = await findATAForPDAForAuthority2(program.programId, pda);
 
-        const result = program.methods.initialize(encodeHandle("lowJoinFund"), new BN(50000))
+        const result = program.methods.initialize(encodeHandle("lowJoinFund"), new BN(50000), [], 0)
             .accounts({
                 multisigOwner: owner.publicKey,
                 feePayer: squadMintFeePayer.publicKey,
@@ -1005,11 +992,8 @@ describe("SquadMint Multisig program tests", () => {
                 feePayer: squadMintFeePayer.publicKey,
                 proposer: walletOwnerAndCreator.keyPair.publicKey,
                 mint: testMint.mintPubkey,
-                proposedToOwner: proposedToWallet.keyPair.publicKey,
                 multisigAta: ata,
-                proposedToAta: proposedToWallet.ataAccount.address,
                 tokenProgram: TOKEN_PROGRAM_ID,
-                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                 systemProgram: anchor.web3.SystemProgram.programId
             })
             .signers([squadMintFeePayer, walletOwnerAndCreator.keyPair])
@@ -1101,11 +1085,8 @@ describe("SquadMint Multisig program tests", () => {
                 feePayer: squadMintFeePayer.publicKey,
                 proposer: walletOwnerAndCreator.keyPair.publicKey,
                 mint: testMint.mintPubkey,
-                proposedToOwner: proposedToWallet.keyPair.publicKey,
                 multisigAta: ata,
-                proposedToAta: proposedToWallet.ataAccount.address,
                 tokenProgram: TOKEN_PROGRAM_ID,
-                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                 systemProgram: anchor.web3.SystemProgram.programId
             })
             .signers([squadMintFeePayer, walletOwnerAndCreator.keyPair])
@@ -1220,11 +1201,8 @@ describe("SquadMint Multisig program tests", () => {
                 feePayer: squadMintFeePayer.publicKey,
                 proposer: alice.keyPair.publicKey,
                 mint: testMint.mintPubkey,
-                proposedToOwner: proposedToWallet.keyPair.publicKey,
                 multisigAta: ata,
-                proposedToAta: proposedToWallet.ataAccount.address,
                 tokenProgram: TOKEN_PROGRAM_ID,
-                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                 systemProgram: anchor.web3.SystemProgram.programId
             })
             .signers([squadMintFeePayer, alice.keyPair])
@@ -1393,11 +1371,8 @@ describe("SquadMint Multisig program tests", () => {
                 feePayer: squadMintFeePayer.publicKey,
                 proposer: walletOwnerAndCreator2.keyPair.publicKey,
                 mint: testMint.mintPubkey,
-                proposedToOwner: proposedToWallet.keyPair.publicKey,
                 multisigAta: someOtherAta,
-                proposedToAta: proposedToWallet.ataAccount.address,
                 tokenProgram: TOKEN_PROGRAM_ID,
-                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                 systemProgram: anchor.web3.SystemProgram.programId
             })
             .signers([squadMintFeePayer, walletOwnerAndCreator2.keyPair])
@@ -1447,11 +1422,8 @@ describe("SquadMint Multisig program tests", () => {
                 feePayer: squadMintFeePayer.publicKey,
                 proposer: walletOwnerAndCreator.keyPair.publicKey,
                 mint: testMint.mintPubkey,
-                proposedToOwner: proposedToWallet.keyPair.publicKey,
                 multisigAta: ata,
-                proposedToAta: proposedToWallet.ataAccount.address,
                 tokenProgram: TOKEN_PROGRAM_ID,
-                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                 systemProgram: anchor.web3.SystemProgram.programId
             })
             .signers([squadMintFeePayer, walletOwnerAndCreator.keyPair])
@@ -1482,11 +1454,8 @@ describe("SquadMint Multisig program tests", () => {
                 feePayer: squadMintFeePayer.publicKey,
                 proposer: walletOwnerAndCreator.keyPair.publicKey,
                 mint: testMint.mintPubkey,
-                proposedToOwner: memberOpenFundWallet2.keyPair.publicKey,
                 multisigAta: ata,
-                proposedToAta: memberOpenFundWallet2.ataAccount.address,
                 tokenProgram: TOKEN_PROGRAM_ID,
-                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                 systemProgram: anchor.web3.SystemProgram.programId
             })
             .signers([squadMintFeePayer, walletOwnerAndCreator.keyPair])
@@ -1529,4 +1498,310 @@ describe("SquadMint Multisig program tests", () => {
             program.account.joinRequestCustodialWallet.fetch(joinCustodialPda)
         ).to.be.rejected;
     });
+
+    // ==================== Social recovery (guardians) ====================
+
+    it("Guardian quorum can open a recovery, the delay blocks early execution, and the owner can cancel", async () => {
+        const owner = await createWallet(connection, testMint.mintPubkey, squadMintFeePayer, 2);
+        const guardians = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+        const pda = await initializeAccount(
+            program, owner.keyPair, squadMintFeePayer, testMint.mintPubkey, "recoveryFund",
+            guardians.map((g) => g.publicKey), 2
+        );
+
+        const fund = await program.account.squadMintFund.fetch(pda);
+        expect(fund.guardians.map((g) => g.toBase58())).to.deep.equal(guardians.map((g) => g.publicKey.toBase58()));
+        expect(fund.guardianThreshold).to.equal(2);
+        expect(fund.recoveredOwner).to.be.null;
+
+        const newOwner = anchor.web3.Keypair.generate();
+        const { recoveryRequest } = await initiateRecovery(program, pda, guardians[0], newOwner.publicKey, squadMintFeePayer);
+        await program.methods.approveRecovery()
+            .accounts({
+                multisig: pda,
+                guardian: guardians[1].publicKey,
+                recoveryRequest,
+            })
+            .signers([guardians[1]])
+            .rpc();
+
+        const request = await program.account.recoveryRequest.fetch(recoveryRequest);
+        expect(request.newOwner.toBase58()).to.equal(newOwner.publicKey.toBase58());
+        expect(popcount(request.approvals)).to.equal(2);
+
+        // Quorum is met, but the delay gives a still-live owner days to react.
+        const early = program.methods.executeRecovery()
+            .accounts({
+                multisig: pda,
+                rentPayer: squadMintFeePayer.publicKey,
+                recoveryRequest,
+            })
+            .rpc();
+        await expect(early).to.be.rejectedWith(/RecoveryDelayNotElapsed/);
+
+        await program.methods.cancelRecovery()
+            .accounts({
+                multisig: pda,
+                multisigOwner: owner.keyPair.publicKey,
+                rentPayer: squadMintFeePayer.publicKey,
+                recoveryRequest,
+            })
+            .signers([owner.keyPair])
+            .rpc();
+
+        const after = await program.account.squadMintFund.fetch(pda);
+        expect(after.hasPendingRecovery).to.be.false;
+        expect(after.recoveredOwner).to.be.null;
+        await expect(program.account.recoveryRequest.fetch(recoveryRequest)).to.be.rejected;
+    });
+
+    it("Owner can designate guardians later, but never themselves and not while a recovery is pending", async () => {
+        const owner = await createWallet(connection, testMint.mintPubkey, squadMintFeePayer, 2);
+        const pda = await initializeAccount(program, owner.keyPair, squadMintFeePayer, testMint.mintPubkey, "lateGuardians");
+        const guardian = anchor.web3.Keypair.generate();
+
+        const selfGuard = program.methods.setGuardians([owner.keyPair.publicKey], 1)
+            .accounts({ multisig: pda, multisigOwner: owner.keyPair.publicKey })
+            .signers([owner.keyPair])
+            .rpc();
+        await expect(selfGuard).to.be.rejectedWith(/InvalidGuardianConfig/);
+
+        await program.methods.setGuardians([guardian.publicKey], 1)
+            .accounts({ multisig: pda, multisigOwner: owner.keyPair.publicKey })
+            .signers([owner.keyPair])
+            .rpc();
+        const fund = await program.account.squadMintFund.fetch(pda);
+        expect(fund.guardians).to.have.lengthOf(1);
+        expect(fund.guardianThreshold).to.equal(1);
+
+        await initiateRecovery(program, pda, guardian, anchor.web3.Keypair.generate().publicKey, squadMintFeePayer);
+
+        const reshuffle = program.methods.setGuardians([], 0)
+            .accounts({ multisig: pda, multisigOwner: owner.keyPair.publicKey })
+            .signers([owner.keyPair])
+            .rpc();
+        await expect(reshuffle).to.be.rejectedWith(/RecoveryAlreadyPending/);
+    });
+
+    // ==================== Dissolution ====================
+
+    it("Members can vote to dissolve a fund: vault split pro-rata, vault and fund closed, rent to the chosen recipient", async () => {
+        const owner = await createWallet(connection, testMint.mintPubkey, squadMintFeePayer, 2);
+        const pda = await initializeAccount(program, owner.keyPair, squadMintFeePayer, testMint.mintPubkey, "dissolveFund");
+        const member = await createWallet(connection, testMint.mintPubkey, squadMintFeePayer, 2);
+        const joinAmount = new BN(amountToSmalletDecimal(1.11));
+        await initiateJoinRequest(program, pda, member, joinAmount, squadMintFeePayer, testMint.mintPubkey);
+        const custodial = await findPDAForJoinCustodialAccount(program.programId, pda, member.keyPair.publicKey);
+        await addMember(program, pda, custodial, member, owner, owner, squadMintFeePayer, testMint.mintPubkey);
+
+        const fund = await program.account.squadMintFund.fetch(pda);
+        expect(fund.contributions.map((c) => c.toString())).to.deep.equal(["0", joinAmount.toString()]);
+
+        const vaultAta = await findATAForPDAForAuthority2(program.programId, pda);
+        const rentRecipient = anchor.web3.Keypair.generate().publicKey;
+        const { transaction } = await proposeDissolution(
+            program, pda, member.keyPair, { proRata: {} }, rentRecipient, squadMintFeePayer
+        );
+
+        // Proposer's auto-YES is 1/2 = 50% < 51%; the owner's YES decides it.
+        await program.methods.submitAndExecute(true)
+            .accounts({
+                transaction,
+                multisig: pda,
+                feePayer: squadMintFeePayer.publicKey,
+                submitter: owner.keyPair.publicKey,
+                mint: testMint.mintPubkey,
+                proposedToOwner: rentRecipient,
+                multisigAta: vaultAta,
+                proposedToAta: await findATAForPDAForAuthority(rentRecipient, testMint.mintPubkey),
+                tokenProgram: TOKEN_PROGRAM_ID,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                systemProgram: anchor.web3.SystemProgram.programId
+            })
+            .signers([squadMintFeePayer, owner.keyPair])
+            .rpc();
+
+        const approved = await program.account.squadMintFund.fetch(pda);
+        expect(approved.dissolution.rentRecipient.toBase58()).to.equal(rentRecipient.toBase58());
+        expect(approved.hasActiveVote).to.be.false;
+
+        // Once approved the fund stops accepting new money.
+        const lateJoiner = await createWallet(connection, testMint.mintPubkey, squadMintFeePayer, 2);
+        await expect(
+            initiateJoinRequest(program, pda, lateJoiner, joinAmount, squadMintFeePayer, testMint.mintPubkey)
+        ).to.be.rejectedWith(/FundDissolving/);
+
+        const memberBefore = await getAccount(connection, member.ataAccount.address);
+        const ownerBefore = await getAccount(connection, owner.ataAccount.address);
+        await dissolveFund(program, pda, rentRecipient, [owner.ataAccount.address, member.ataAccount.address], testMint.mintPubkey);
+
+        // Pro-rata by contribution: the owner paid no deposit, so the member gets it all.
+        const memberAfter = await getAccount(connection, member.ataAccount.address);
+        const ownerAfter = await getAccount(connection, owner.ataAccount.address);
+        expect(memberAfter.amount - memberBefore.amount).to.equal(BigInt(joinAmount.toString()));
+        expect(ownerAfter.amount).to.equal(ownerBefore.amount);
+
+        await expect(getAccount(connection, vaultAta)).to.be.rejected;
+        await expect(program.account.squadMintFund.fetch(pda)).to.be.rejected;
+        expect(await connection.getBalance(rentRecipient)).to.be.greaterThan(0);
+    });
+
+    it("dissolve_fund is rejected until members approve a dissolution", async () => {
+        const owner = await createWallet(connection, testMint.mintPubkey, squadMintFeePayer, 2);
+        const pda = await initializeAccount(program, owner.keyPair, squadMintFeePayer, testMint.mintPubkey, "noDissolve");
+
+        const attempt = dissolveFund(program, pda, owner.keyPair.publicKey, [owner.ataAccount.address], testMint.mintPubkey);
+        await expect(attempt).to.be.rejected;
+
+        const fund = await program.account.squadMintFund.fetch(pda);
+        expect(fund.dissolution).to.be.null;
+    });
+
+    // ==================== Stale join requests ====================
+
+    it("Anyone can refund a pending join request once the fund has filled up", async () => {
+        const owner = await createWallet(connection, testMint.mintPubkey, squadMintFeePayer, 2);
+        const pda = await initializeAccount(program, owner.keyPair, squadMintFeePayer, testMint.mintPubkey, "staleFull");
+        const joinAmount = new BN(amountToSmalletDecimal(1.11));
+
+        const stranded = await createWallet(connection, testMint.mintPubkey, squadMintFeePayer, 2);
+        await initiateJoinRequest(program, pda, stranded, joinAmount, squadMintFeePayer, testMint.mintPubkey);
+        const strandedBefore = await getAccount(connection, stranded.ataAccount.address);
+
+        // While the fund can still accept it, the escrow is the owner's call.
+        await expect(
+            cancelStaleJoinRequest(program, pda, stranded, squadMintFeePayer, testMint.mintPubkey)
+        ).to.be.rejectedWith(/JoinRequestNotStale/);
+
+        // The owner fills the fund with other joiners instead.
+        for (let i = 0; i < 7; i++) {
+            const joiner = await createWallet(connection, testMint.mintPubkey, squadMintFeePayer, 2);
+            await initiateJoinRequest(program, pda, joiner, joinAmount, squadMintFeePayer, testMint.mintPubkey);
+            const joinCustodialPda = await findPDAForJoinCustodialAccount(program.programId, pda, joiner.keyPair.publicKey);
+            await addMember(program, pda, joinCustodialPda, joiner, owner, owner, squadMintFeePayer, testMint.mintPubkey);
+        }
+
+        const { joinCustodialPda } = await cancelStaleJoinRequest(
+            program, pda, stranded, squadMintFeePayer, testMint.mintPubkey
+        );
+
+        const strandedAfter = await getAccount(connection, stranded.ataAccount.address);
+        expect(strandedAfter.amount - strandedBefore.amount).to.equal(BigInt(joinAmount.toString()));
+        await expect(program.account.joinRequestCustodialWallet.fetch(joinCustodialPda)).to.be.rejected;
+    });
+
+    it("Anyone can refund a pending join request after its fund was dissolved", async () => {
+        const owner = await createWallet(connection, testMint.mintPubkey, squadMintFeePayer, 2);
+        const pda = await initializeAccount(program, owner.keyPair, squadMintFeePayer, testMint.mintPubkey, "staleGone");
+        const joinAmount = new BN(amountToSmalletDecimal(1.11));
+
+        const stranded = await createWallet(connection, testMint.mintPubkey, squadMintFeePayer, 2);
+        await initiateJoinRequest(program, pda, stranded, joinAmount, squadMintFeePayer, testMint.mintPubkey);
+
+        // Sole member: the owner's proposal + re-tally is 100% YES.
+        const { transaction } = await proposeDissolution(
+            program, pda, owner.keyPair, { equal: {} }, owner.keyPair.publicKey, squadMintFeePayer
+        );
+        await program.methods.submitAndExecute(true)
+            .accounts({
+                transaction,
+                multisig: pda,
+                feePayer: squadMintFeePayer.publicKey,
+                submitter: owner.keyPair.publicKey,
+                mint: testMint.mintPubkey,
+                proposedToOwner: owner.keyPair.publicKey,
+                multisigAta: await findATAForPDAForAuthority2(program.programId, pda),
+                proposedToAta: owner.ataAccount.address,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                systemProgram: anchor.web3.SystemProgram.programId
+            })
+            .signers([squadMintFeePayer, owner.keyPair])
+            .rpc();
+        await dissolveFund(program, pda, owner.keyPair.publicKey, [owner.ataAccount.address], testMint.mintPubkey);
+        await expect(program.account.squadMintFund.fetch(pda)).to.be.rejected;
+
+        const strandedBefore = await getAccount(connection, stranded.ataAccount.address);
+        await cancelStaleJoinRequest(program, pda, stranded, squadMintFeePayer, testMint.mintPubkey);
+        const strandedAfter = await getAccount(connection, stranded.ataAccount.address);
+        expect(strandedAfter.amount - strandedBefore.amount).to.equal(BigInt(joinAmount.toString()));
+    });
+
+    // ==================== Rent refunds (audit L-1) ====================
+
+    it("Join-request rent goes back to whoever paid it, not to the owner's closing fee payer", async () => {
+        const owner = await createWallet(connection, testMint.mintPubkey, squadMintFeePayer, 2);
+        const pda = await initializeAccount(program, owner.keyPair, squadMintFeePayer, testMint.mintPubkey, "rentRefund");
+
+        // The joiner pays their own request rent from a separate wallet.
+        const joiner = await createWallet(connection, testMint.mintPubkey, squadMintFeePayer, 2);
+        const joinerPayer = await createFeePayerWallet(connection, 1);
+        const joinAmount = new BN(amountToSmalletDecimal(1.11));
+        const { joinCustodialPda, joinCustodialAta } = await initiateJoinRequest(
+            program, pda, joiner, joinAmount, joinerPayer, testMint.mintPubkey
+        );
+        const custodial = await program.account.joinRequestCustodialWallet.fetch(joinCustodialPda);
+        expect(custodial.rentPayer.toBase58()).to.equal(joinerPayer.publicKey.toBase58());
+
+        const requestRent =
+            (await connection.getBalance(joinCustodialPda)) + (await connection.getBalance(joinCustodialAta));
+        const payerBefore = await connection.getBalance(joinerPayer.publicKey);
+
+        await rejectMember(program, pda, joinCustodialPda, joiner, owner, owner, squadMintFeePayer, testMint.mintPubkey);
+
+        const payerAfter = await connection.getBalance(joinerPayer.publicKey);
+        expect(payerAfter - payerBefore).to.equal(requestRent);
+    });
+
+    // ============ Deferred recipient ATA (audit L-2) ============
+
+    it("Proposing to a PDA recipient creates no ATA; a passing vote creates it on payout", async () => {
+        const owner = await createWallet(connection, testMint.mintPubkey, squadMintFeePayer, 2);
+        const pda = await initializeAccount(program, owner.keyPair, squadMintFeePayer, testMint.mintPubkey, "deferredAta");
+        const multisigAta = await findATAForPDAForAuthority2(program.programId, pda);
+        await transferTokens(connection, squadMintFeePayer, owner.ataAccount.address, multisigAta, owner.keyPair, 1);
+
+        // An off-curve recipient (any PDA) with no token account yet.
+        const [recipient] = PublicKey.findProgramAddressSync([Buffer.from("deferred_ata_recipient")], program.programId);
+        const recipientAta = getAssociatedTokenAddressSync(testMint.mintPubkey, recipient, true);
+
+        const fund = await program.account.squadMintFund.fetch(pda);
+        const transaction = await findPDAForMultisigTransaction(program.programId, pda, fund.accountHandle, fund.masterNonce);
+        await program.methods
+            .createProposal(MIN_PROPOSAL, recipient)
+            .accounts({
+                transaction,
+                multisig: pda,
+                feePayer: squadMintFeePayer.publicKey,
+                proposer: owner.keyPair.publicKey,
+                mint: testMint.mintPubkey,
+                multisigAta,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: anchor.web3.SystemProgram.programId
+            })
+            .signers([squadMintFeePayer, owner.keyPair])
+            .rpc();
+        expect(await connection.getAccountInfo(recipientAta)).to.be.null;
+
+        await program.methods.submitAndExecute(true)
+            .accounts({
+                transaction,
+                multisig: pda,
+                feePayer: squadMintFeePayer.publicKey,
+                submitter: owner.keyPair.publicKey,
+                mint: testMint.mintPubkey,
+                proposedToOwner: recipient,
+                multisigAta,
+                proposedToAta: recipientAta,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                systemProgram: anchor.web3.SystemProgram.programId
+            })
+            .signers([squadMintFeePayer, owner.keyPair])
+            .rpc();
+
+        const received = await getAccount(connection, recipientAta);
+        expect(received.owner.toBase58()).to.equal(recipient.toBase58());
+        expect(received.amount).to.equal(BigInt(MIN_PROPOSAL.toString()));
+    });
 });
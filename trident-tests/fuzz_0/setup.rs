@@ -0,0 +1,174 @@
+//! State written straight into Trident's SVM at the start of each iteration:
+//! the program config, the two mints and the actors' token accounts. Like the
+//! LiteSVM harness, token state is set rather than minted through CPIs, so the
+//! mints can live at fixed addresses without their keypairs.
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{program_option::COption, program_pack::Pack},
+    AnchorSerialize, Discriminator,
+};
+use anchor_spl::{
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::{TransferFee, TransferFeeConfig},
+            BaseStateWithExtensions, BaseStateWithExtensionsMut, ExtensionType,
+            StateWithExtensions, StateWithExtensionsMut,
+        },
+        state::{Account, AccountState, Mint},
+    },
+};
+use solana_account::{AccountSharedData, ReadableAccount};
+use squad_mint_client::{pda, AccountTrailer, Config, TokenMint, PROGRAM_ID};
+use trident_fuzz::fuzzing::Trident;
+
+pub const DECIMALS: u8 = 6;
+
+// The config the program would hold after `initialize_config` and
+// `set_protocol_fee`: `admin` is also the treasury.
+pub fn write_config(
+    trident: &mut Trident,
+    admin: &Pubkey,
+    allowed_mints: &[Pubkey],
+    join_fee_bps: u16,
+    payout_fee_bps: u16,
+    reject_fee: u64,
+) {
+    let mut data = Config::DISCRIMINATOR.to_vec();
+    (
+        *admin,
+        allowed_mints.to_vec(),
+        *admin,
+        join_fee_bps,
+        payout_fee_bps,
+        reject_fee,
+        AccountTrailer::CURRENT,
+    )
+        .serialize(&mut data)
+        .unwrap();
+    data.resize(8 + Config::MAX_SIZE, 0);
+    write(trident, &pda::config(), data, &PROGRAM_ID);
+}
+
+// The Token-2022 base states are byte-for-byte SPL Token's, so they pack both.
+pub fn write_legacy_mint(trident: &mut Trident, address: &Pubkey) {
+    let mut data = vec![0; Mint::LEN];
+    Mint::pack(base_mint(), &mut data).unwrap();
+    write(trident, address, data, &spl_token::ID);
+}
+
+// A Token-2022 mint charging `basis_points` of every transfer, capped at
+// `maximum_fee` base units, in every epoch.
+pub fn write_fee_mint(
+    trident: &mut Trident,
+    address: &Pubkey,
+    basis_points: u16,
+    maximum_fee: u64,
+) {
+    let extensions = [ExtensionType::TransferFeeConfig];
+    let len = ExtensionType::try_calculate_account_len::<Mint>(&extensions).unwrap();
+    let mut data = vec![0; len];
+    let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+    let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+    let fee = TransferFee {
+        epoch: 0.into(),
+        maximum_fee: maximum_fee.into(),
+        transfer_fee_basis_points: basis_points.into(),
+    };
+    config.older_transfer_fee = fee;
+    config.newer_transfer_fee = fee;
+    state.base = base_mint();
+    state.pack_base();
+    state.init_account_type().unwrap();
+    write(trident, address, data, &spl_token_2022::ID);
+}
+
+fn base_mint() -> Mint {
+    Mint {
+        mint_authority: COption::None,
+        supply: 0,
+        decimals: DECIMALS,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+}
+
+// `owner`'s canonical token account for `mint`, holding `amount`. Token-2022
+// accounts get the extensions the ATA program would give them.
+pub fn write_token_account(trident: &mut Trident, mint: &TokenMint, owner: &Pubkey, amount: u64) {
+    let base = Account {
+        mint: mint.address,
+        owner: *owner,
+        amount,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    };
+    let data = if mint.token_program == spl_token::ID {
+        let mut data = vec![0; Account::LEN];
+        Account::pack(base, &mut data).unwrap();
+        data
+    } else {
+        let mint_data = trident.get_account(&mint.address).data().to_vec();
+        let mint_extensions = StateWithExtensions::<Mint>::unpack(&mint_data)
+            .unwrap()
+            .get_extension_types()
+            .unwrap();
+        let mut extensions = ExtensionType::get_required_init_account_extensions(&mint_extensions);
+        extensions.push(ExtensionType::ImmutableOwner);
+        let len = ExtensionType::try_calculate_account_len::<Account>(&extensions).unwrap();
+        let mut data = vec![0; len];
+        let mut state = StateWithExtensionsMut::<Account>::unpack_uninitialized(&mut data).unwrap();
+        for extension in extensions {
+            state.init_account_extension_from_type(extension).unwrap();
+        }
+        state.base = base;
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    };
+    write(trident, &mint.ata(owner), data, &mint.token_program);
+}
+
+fn write(trident: &mut Trident, address: &Pubkey, data: Vec<u8>, owner: &Pubkey) {
+    let mut account = AccountSharedData::new(1_000_000_000, data.len(), owner);
+    account.set_data_from_slice(&data);
+    trident.set_account_custom(address, &account);
+}
+
+pub fn exists(trident: &mut Trident, address: &Pubkey) -> bool {
+    trident.get_account(address).lamports() > 0
+}
+
+// SPL Token or Token-2022 (the base layouts are the same); 0 for an account
+// that doesn't exist yet. Fees withheld on the account aren't counted.
+pub fn token_balance(trident: &mut Trident, address: &Pubkey) -> u64 {
+    let account = trident.get_account(address);
+    if account.lamports() == 0 {
+        return 0;
+    }
+    StateWithExtensions::<Account>::unpack(account.data())
+        .unwrap()
+        .base
+        .amount
+}
+
+// What `mint` withholds from a transfer of `amount`: 0 for a legacy mint.
+// Read from the mint itself, not from the program's gross-up arithmetic.
+pub fn transfer_fee(trident: &mut Trident, mint: &TokenMint, amount: u64) -> u64 {
+    if mint.token_program == spl_token::ID {
+        return 0;
+    }
+    let account = trident.get_account(&mint.address);
+    let state = StateWithExtensions::<Mint>::unpack(account.data()).unwrap();
+    state
+        .get_extension::<TransferFeeConfig>()
+        .unwrap()
+        .newer_transfer_fee
+        .calculate_fee(amount)
+        .unwrap()
+}
@@ -0,0 +1,504 @@
+//! fuzz_0 — stateful fuzzing of the six core instructions.
+//!
+//! Each iteration starts from fresh actors and a freshly drawn fee config,
+//! then Trident calls the `#[flow]`s in random order. Every flow builds its
+//! instruction with `squad_mint_client`, runs it, and asserts the invariants
+//! for every fund created so far, whether the transaction succeeded or not. A
+//! failed assertion panics, which fails the run.
+
+mod setup;
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::Instruction};
+use solana_account::ReadableAccount;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use squad_mint_client::{instructions, pda, state, TokenMint};
+use squad_mint_multi_sig::fees;
+use trident_fuzz::fuzzing::*;
+
+const ACTORS: usize = 10;
+const MAX_FUNDS: usize = 3;
+// Each actor starts with 100 tokens of both mints.
+const ACTOR_BALANCE: u64 = 100_000_000;
+// The legacy SPL Token mint, at the address the other suites use.
+const USDC_MINT: Pubkey = Pubkey::from_str_const("37KQMrbBtkNFYJvDKW3tGxEs1WuvqcEeu44JGrjPkYsz");
+
+// What the fuzzer knows independently of the program's own accounting.
+struct FundModel {
+    pda: Pubkey,
+    mint: TokenMint,
+    join_amount: u64,
+    deposited: u64, // join deposits credited to the vault, net of every fee
+    paid_out: u64,  // executed payouts, in full: their fees come out of what's paid
+}
+
+struct Snapshot {
+    vault: u64,
+    nonce: u64,
+    has_active_vote: bool,
+    members: Vec<Pubkey>,
+    contributions: u64,
+}
+
+// One flow's transaction, seen from the fund it was aimed at.
+struct Outcome {
+    fund: usize,
+    actor: Pubkey,
+    ok: bool,
+    before: Vec<Snapshot>,
+    after: Snapshot,
+}
+
+impl Outcome {
+    fn vault_delta(&self) -> i128 {
+        self.after.vault as i128 - self.before[self.fund].vault as i128
+    }
+
+    fn nonce_before(&self) -> u64 {
+        self.before[self.fund].nonce
+    }
+}
+
+#[derive(FuzzTestMethods)]
+struct FuzzTest {
+    trident: Trident,
+    actors: Vec<Keypair>,
+    treasury: Pubkey,
+    fee_mint: TokenMint,
+    join_fee_bps: u16,
+    payout_fee_bps: u16,
+    funds: Vec<FundModel>,
+}
+
+#[flow_executor]
+impl FuzzTest {
+    fn new() -> Self {
+        Self {
+            trident: Trident::default(),
+            actors: vec![],
+            treasury: Pubkey::default(),
+            fee_mint: TokenMint::token_2022(Pubkey::default()),
+            join_fee_bps: 0,
+            payout_fee_bps: 0,
+            funds: vec![],
+        }
+    }
+
+    // Fresh keypairs every iteration, so every fund, join request and
+    // proposal address is new even if the SVM carries state over.
+    #[init]
+    fn start(&mut self) {
+        self.funds.clear();
+        self.treasury = Keypair::new().pubkey();
+        self.join_fee_bps = self.trident.random_from_range(0..=fees::MAX_FEE_BPS);
+        self.payout_fee_bps = self.trident.random_from_range(0..=fees::MAX_FEE_BPS);
+        let reject_fee = self.trident.random_from_range(0..=fees::MAX_REJECT_FEE);
+        self.fee_mint = TokenMint::token_2022(Keypair::new().pubkey());
+        let legacy = TokenMint::spl_token(USDC_MINT);
+
+        setup::write_legacy_mint(&mut self.trident, &legacy.address);
+        // Up to 5%, sometimes capped low enough that the cap is what applies.
+        let fee_bps = self.trident.random_from_range(0..=500u16);
+        let maximum_fee = self.trident.random_from_range(1..=u64::from(u32::MAX));
+        setup::write_fee_mint(
+            &mut self.trident,
+            &self.fee_mint.address,
+            fee_bps,
+            maximum_fee,
+        );
+        setup::write_config(
+            &mut self.trident,
+            &self.treasury,
+            &[legacy.address, self.fee_mint.address],
+            self.join_fee_bps,
+            self.payout_fee_bps,
+            reject_fee,
+        );
+
+        self.actors = (0..ACTORS).map(|_| Keypair::new()).collect();
+        for actor in &self.actors {
+            self.trident.airdrop(&actor.pubkey(), 10_000_000_000);
+            for mint in [legacy, self.fee_mint] {
+                setup::write_token_account(
+                    &mut self.trident,
+                    &mint,
+                    &actor.pubkey(),
+                    ACTOR_BALANCE,
+                );
+            }
+        }
+    }
+
+    #[flow]
+    fn initialize(&mut self) {
+        if self.funds.len() == MAX_FUNDS {
+            return;
+        }
+        let owner = self.random_actor();
+        let handle = format!("fuzz{}", self.trident.random_from_range(0..4u8));
+        let handle = squad_mint_client::encode_handle(&handle).unwrap();
+        let join_amount = self.random_amount();
+        let mint = if self.trident.random_from_range(0..2u8) == 0 {
+            TokenMint::spl_token(USDC_MINT)
+        } else {
+            self.fee_mint
+        };
+        let ix = instructions::initialize(
+            &self.payer(),
+            &owner,
+            &handle,
+            &mint,
+            join_amount,
+            vec![],
+            0,
+        );
+        if self.process(ix, "initialize") {
+            self.funds.push(FundModel {
+                pda: pda::fund(&handle, &owner),
+                mint,
+                join_amount,
+                deposited: 0,
+                paid_out: 0,
+            });
+        }
+        self.check();
+    }
+
+    #[flow]
+    fn initiate_join_request(&mut self) {
+        let Some(fund) = self.random_fund() else {
+            return;
+        };
+        let joiner = self.random_actor();
+        // The fund's join amount, or anything at all.
+        let amount = if self.trident.random_from_range(0..2u8) == 0 {
+            self.funds[fund].join_amount
+        } else {
+            self.random_amount()
+        };
+        let model = &self.funds[fund];
+        let ix = instructions::initiate_join_request(
+            &self.payer(),
+            &model.pda,
+            &joiner,
+            &model.mint,
+            amount,
+            None,
+        );
+        let outcome = self.execute(fund, joiner, ix, "initiate_join_request");
+        self.assert_vault_and_nonce_unchanged(&outcome);
+        self.finish(&outcome);
+    }
+
+    #[flow]
+    fn add_member(&mut self) {
+        let Some(fund) = self.random_fund() else {
+            return;
+        };
+        let (signer, joiner) = (self.random_actor(), self.random_actor());
+        let model = &self.funds[fund];
+        let (pda, mint) = (model.pda, model.mint);
+        // What should reach the vault, worked out from the escrow before the
+        // instruction runs: the escrow less the protocol's join fee, less the
+        // mint's fee on the transfer into the vault.
+        let request = pda::join_request(&pda, &joiner);
+        let escrowed = setup::token_balance(&mut self.trident, &pda::join_escrow(&request));
+        let join_amount = self
+            .account_data(&request)
+            .and_then(|data| state::join_request(&data).ok())
+            .map_or(0, |request| request.join_amount());
+        let join_fee = fees::bps_of(join_amount, self.join_fee_bps).min(escrowed);
+        let moved = escrowed - join_fee;
+        let credited = moved - setup::transfer_fee(&mut self.trident, &mint, moved);
+
+        let ix = instructions::add_member(
+            &self.payer(),
+            &pda,
+            &signer,
+            &joiner,
+            &self.payer(),
+            &mint,
+            &self.treasury,
+        );
+        let outcome = self.execute(fund, signer, ix, "add_member");
+        if outcome.ok {
+            let before = &outcome.before[fund];
+            assert_eq!(
+                outcome.vault_delta(),
+                credited as i128,
+                "add_member deposit"
+            );
+            assert_eq!(outcome.after.members.len(), before.members.len() + 1);
+            // The member is credited with exactly what arrived.
+            assert_eq!(
+                outcome.after.contributions - before.contributions,
+                credited,
+                "add_member credit"
+            );
+            assert_eq!(outcome.after.nonce, outcome.nonce_before());
+            self.funds[fund].deposited += credited;
+        } else {
+            self.assert_vault_and_nonce_unchanged(&outcome);
+        }
+        self.finish(&outcome);
+    }
+
+    #[flow]
+    fn reject_member(&mut self) {
+        let Some(fund) = self.random_fund() else {
+            return;
+        };
+        let (signer, joiner) = (self.random_actor(), self.random_actor());
+        let model = &self.funds[fund];
+        let ix = instructions::reject_member(
+            &self.payer(),
+            &model.pda,
+            &signer,
+            &joiner,
+            &self.payer(),
+            &model.mint,
+            &self.treasury,
+        );
+        let outcome = self.execute(fund, signer, ix, "reject_member");
+        self.assert_vault_and_nonce_unchanged(&outcome);
+        self.finish(&outcome);
+    }
+
+    #[flow]
+    fn create_proposal(&mut self) {
+        let Some(fund) = self.random_fund() else {
+            return;
+        };
+        let proposer = self.random_actor();
+        let model = &self.funds[fund];
+        let (pda, mint) = (model.pda, model.mint);
+        // Recipients include the fund and its vault, not just wallets.
+        let recipient = match self.trident.random_from_range(0..ACTORS + 2) {
+            ACTORS => pda,
+            i if i == ACTORS + 1 => pda::vault(&pda),
+            i => self.actors[i].pubkey(),
+        };
+        let amount = self.random_amount();
+        let nonce = self.snapshot(&pda).nonce;
+        let ix = instructions::create_proposal(
+            &self.payer(),
+            &pda,
+            nonce,
+            &proposer,
+            &mint,
+            amount,
+            &recipient,
+        );
+        let outcome = self.execute(fund, proposer, ix, "create_proposal");
+        self.assert_vault_and_nonce_unchanged(&outcome);
+        self.finish(&outcome);
+    }
+
+    #[flow]
+    fn submit_and_execute(&mut self) {
+        let Some(fund) = self.random_fund() else {
+            return;
+        };
+        let submitter = self.random_actor();
+        let vote = self.trident.random_from_range(0..2u8) == 0;
+        let model = &self.funds[fund];
+        let (pda, mint) = (model.pda, model.mint);
+        let nonce = self.snapshot(&pda).nonce;
+        let Some(proposal) = self
+            .account_data(&pda::proposal(&pda, nonce))
+            .and_then(|data| state::proposal(&data).ok())
+        else {
+            return;
+        };
+        let amount = proposal.message_data.amount;
+        let recipient_ata = mint.ata(&proposal.message_data.proposed_to_account);
+        let recipient_before = setup::token_balance(&mut self.trident, &recipient_ata);
+        // The payout fee comes out of what the vault sends the recipient, and
+        // the mint's fee out of what arrives.
+        let sent = amount - fees::bps_of(amount, self.payout_fee_bps);
+        let received = sent - setup::transfer_fee(&mut self.trident, &mint, sent);
+
+        let ix = instructions::submit_and_execute(
+            &self.payer(),
+            &pda,
+            &submitter,
+            &proposal,
+            &mint,
+            &self.treasury,
+            vote,
+        );
+        let outcome = self.execute(fund, submitter, ix, "submit_and_execute");
+        let resolved = !setup::exists(&mut self.trident, &pda::proposal(&pda, nonce));
+        if outcome.ok && resolved {
+            // Strictly increases, by exactly one, on resolution.
+            assert_eq!(outcome.after.nonce, nonce + 1, "nonce on resolution");
+            let delta = outcome.vault_delta();
+            // Rejected, or the vault paid exactly the amount voted.
+            assert!(
+                delta == 0 || delta == -(amount as i128),
+                "payout moved {delta}, proposal was for {amount}"
+            );
+            if delta != 0 {
+                let recipient_after = setup::token_balance(&mut self.trident, &recipient_ata);
+                assert_eq!(
+                    recipient_after - recipient_before,
+                    received,
+                    "payout received"
+                );
+                self.funds[fund].paid_out += amount;
+            }
+        } else {
+            assert_eq!(outcome.after.nonce, nonce, "nonce without resolution");
+            assert_eq!(
+                outcome.vault_delta(),
+                0,
+                "vault moved on an unresolved vote"
+            );
+        }
+        self.finish(&outcome);
+    }
+
+    #[end]
+    fn end(&mut self) {
+        self.check();
+    }
+}
+
+impl FuzzTest {
+    fn payer(&self) -> Pubkey {
+        self.trident.payer().pubkey()
+    }
+
+    fn random_actor(&mut self) -> Pubkey {
+        self.actors[self.trident.random_from_range(0..ACTORS)].pubkey()
+    }
+
+    fn random_fund(&mut self) -> Option<usize> {
+        if self.funds.is_empty() {
+            return None;
+        }
+        Some(self.trident.random_from_range(0..self.funds.len()))
+    }
+
+    // Below the program's minimum as often as above it.
+    fn random_amount(&mut self) -> u64 {
+        self.trident.random_from_range(0..=u64::from(u32::MAX))
+    }
+
+    fn process(&mut self, ix: Instruction, label: &str) -> bool {
+        self.trident
+            .process_transaction(&[ix], Some(label))
+            .is_success()
+    }
+
+    fn account_data(&mut self, address: &Pubkey) -> Option<Vec<u8>> {
+        setup::exists(&mut self.trident, address)
+            .then(|| self.trident.get_account(address).data().to_vec())
+    }
+
+    fn snapshot(&mut self, fund: &Pubkey) -> Snapshot {
+        let data = self.trident.get_account(fund).data().to_vec();
+        let state = state::fund(&data).unwrap();
+        let contributions = (0..state.members().len())
+            .map(|i| state.contribution_of(i))
+            .sum();
+        Snapshot {
+            vault: setup::token_balance(&mut self.trident, &pda::vault(fund)),
+            nonce: state.master_nonce(),
+            has_active_vote: state.has_active_vote(),
+            members: state.members().to_vec(),
+            contributions,
+        }
+    }
+
+    fn execute(&mut self, fund: usize, actor: Pubkey, ix: Instruction, label: &str) -> Outcome {
+        let pdas: Vec<Pubkey> = self.funds.iter().map(|f| f.pda).collect();
+        let before = pdas.iter().map(|pda| self.snapshot(pda)).collect();
+        let ok = self.process(ix, label);
+        let after = self.snapshot(&pdas[fund]);
+        Outcome {
+            fund,
+            actor,
+            ok,
+            before,
+            after,
+        }
+    }
+
+    fn assert_vault_and_nonce_unchanged(&self, outcome: &Outcome) {
+        assert_eq!(outcome.after.nonce, outcome.nonce_before(), "nonce moved");
+        assert_eq!(outcome.vault_delta(), 0, "vault moved (ok: {})", outcome.ok);
+    }
+
+    // Checks every flow ends with, after its own.
+    fn finish(&mut self, outcome: &Outcome) {
+        // No non-member can ever change the vault balance.
+        if !outcome.before[outcome.fund]
+            .members
+            .contains(&outcome.actor)
+        {
+            assert_eq!(
+                outcome.vault_delta(),
+                0,
+                "non-member {} moved the vault",
+                outcome.actor
+            );
+        }
+
+        // Every other fund is untouched by an instruction aimed at this one.
+        let pdas: Vec<Pubkey> = self.funds.iter().map(|f| f.pda).collect();
+        for (i, pda) in pdas.iter().enumerate() {
+            if i != outcome.fund {
+                let now = self.snapshot(pda);
+                assert_eq!(
+                    now.vault, outcome.before[i].vault,
+                    "cross-fund vault change"
+                );
+                assert_eq!(
+                    now.nonce, outcome.before[i].nonce,
+                    "cross-fund nonce change"
+                );
+            }
+        }
+
+        self.check();
+    }
+
+    // Invariants that hold for every fund after every step.
+    fn check(&mut self) {
+        let models: Vec<(Pubkey, u64, u64)> = self
+            .funds
+            .iter()
+            .map(|f| (f.pda, f.deposited, f.paid_out))
+            .collect();
+        for (pda, deposited, paid_out) in models {
+            let snapshot = self.snapshot(&pda);
+            // Solvency: the vault never pays out more than was deposited, and
+            // holds exactly what was credited minus what was paid out.
+            let expected = deposited
+                .checked_sub(paid_out)
+                .expect("paid out more than was deposited");
+            assert_eq!(
+                snapshot.vault, expected,
+                "vault diverged from deposits - payouts"
+            );
+            // The program's own credits add up to every deposit it took.
+            assert_eq!(
+                snapshot.contributions, deposited,
+                "contributions diverged from deposits"
+            );
+            // has_active_vote <=> the Transaction PDA at the current nonce exists.
+            let proposal = pda::proposal(&pda, snapshot.nonce);
+            assert_eq!(
+                snapshot.has_active_vote,
+                setup::exists(&mut self.trident, &proposal),
+                "has_active_vote out of sync with the proposal account"
+            );
+        }
+    }
+}
+
+fn main() {
+    // Iterations, and flow calls per iteration.
+    FuzzTest::fuzz(1000, 60);
+}